@@ -16,6 +16,7 @@ async fn main() -> anyhow::Result<()> {
         output_file: "advanced_context.md".to_string(),
         include_hidden: true,
         include_deps: true,
+        ..Default::default()
     };
 
     println!("📁 Analyzing project: {:?}", config.repo_path);
@@ -16,6 +16,7 @@ async fn main() -> anyhow::Result<()> {
         output_file: "example_context.md".to_string(),
         include_hidden: false,
         include_deps: false,
+        ..Default::default()
     };
 
     println!("📁 Analyzing project: {:?}", config.repo_path);
@@ -0,0 +1,805 @@
+//! Call graph extraction for the AI Context Generator.
+//!
+//! This module walks the bodies of every crate-local function and method and
+//! links the calls it can attribute to other crate-local functions, giving a
+//! best-effort call graph and the matching "used by" index. Calls are
+//! resolved through paths and `use` imports (following re-exports and
+//! library crates of the same project), `self`, and the types of parameters,
+//! `let` bindings and struct fields. Calls through trait objects, generic
+//! parameters or closures are not resolved.
+
+use proc_macro2::{Group, TokenStream, TokenTree};
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{
+    Block, Expr, ExprMethodCall, ExprPath, FnArg, ImplItemFn, ItemFn, ItemImpl, ItemMod, ItemTrait,
+    Local, Pat, Signature, TraitItemFn, Type,
+};
+
+use crate::module_tree::{
+    join, qualify, CrateRoot, ModuleTree, PathResolver, ResolvedPath, RootKind,
+};
+use crate::parser::{FunctionInfo, ParsedSources, RustAnalysis, RustParser};
+use crate::scanner::ScanResult;
+
+/// Smart pointers looked through when inferring the type of a receiver.
+const POINTER_TYPES: &[&str] = &["Box", "Rc", "Arc"];
+
+/// Best-effort call graph of the crate-local functions of a project.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::call_graph::CallGraph;
+/// use ai_context_gen::module_tree::ModuleTree;
+/// use ai_context_gen::{Config, RepositoryScanner};
+/// use std::fs;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let dir = tempfile::tempdir()?;
+/// fs::create_dir(dir.path().join("src"))?;
+/// fs::write(
+///     dir.path().join("src/lib.rs"),
+///     r#"
+///     mod scanner;
+///     pub use scanner::Scanner;
+///
+///     pub fn run() -> usize {
+///         let scanner = Scanner::new();
+///         scanner.scan().len()
+///     }
+///     "#,
+/// )?;
+/// fs::write(
+///     dir.path().join("src/scanner.rs"),
+///     r#"
+///     pub struct Scanner;
+///
+///     impl Scanner {
+///         pub fn new() -> Self { Scanner }
+///         pub fn scan(&self) -> Vec<String> { self.walk() }
+///         fn walk(&self) -> Vec<String> { Vec::new() }
+///     }
+///     "#,
+/// )?;
+///
+/// let config = Config { repo_path: dir.path().to_path_buf(), ..Config::default() };
+/// let scan_result = RepositoryScanner::new(config).scan().await?;
+/// let graph = CallGraph::build(&scan_result, &ModuleTree::resolve(&scan_result));
+///
+/// let run = graph.find("run").unwrap();
+/// let callees: Vec<&str> = graph
+///     .callees(run)
+///     .map(|callee| graph.functions[callee].short_path())
+///     .collect();
+/// assert_eq!(callees, ["scanner::Scanner::new", "scanner::Scanner::scan"]);
+///
+/// let walk = graph.find("scanner::Scanner::walk").unwrap();
+/// let callers: Vec<usize> = graph.callers(walk).collect();
+/// assert_eq!(callers, [graph.find("scanner::Scanner::scan").unwrap()]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CallGraph {
+    /// Crate-local functions and methods, grouped by crate root in module
+    /// tree order.
+    pub functions: Vec<FunctionNode>,
+
+    /// Calls and references between them, deduplicated, in source order.
+    pub calls: Vec<CallEdge>,
+}
+
+/// A crate-local function or method in a [`CallGraph`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionNode {
+    /// Target name of the crate root defining the function.
+    pub target: String,
+
+    /// Kind of that crate root.
+    pub kind: RootKind,
+
+    /// Fully qualified path (e.g., "crate::scanner::RepositoryScanner::scan").
+    /// Methods are qualified by the module of their impl block.
+    pub path: String,
+
+    /// File defining the function, relative to the repository root.
+    pub file: PathBuf,
+
+    /// Line of the function's declaration.
+    pub line: usize,
+}
+
+/// A call, or a reference such as `.map(Self::parse)`, from one function to
+/// another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CallEdge {
+    /// Index of the calling function in [`CallGraph::functions`].
+    pub caller: usize,
+
+    /// Index of the called function in [`CallGraph::functions`].
+    pub callee: usize,
+}
+
+impl FunctionNode {
+    /// Path within the crate, without the leading `crate::`.
+    pub fn short_path(&self) -> &str {
+        self.path.strip_prefix("crate::").unwrap_or(&self.path)
+    }
+}
+
+impl CallGraph {
+    /// Builds the call graph of every crate root in `module_tree`.
+    ///
+    /// A file reachable from several roots is attributed to the first one,
+    /// usually the library. Files that do not parse contribute the items
+    /// that can be recovered.
+    pub fn build(scan_result: &ScanResult, module_tree: &ModuleTree) -> Self {
+        Self::from_sources(module_tree, &ParsedSources::parse(scan_result))
+    }
+
+    /// Builds the call graph of every crate root in `module_tree` from
+    /// already parsed sources. Function bodies are read from their syntax
+    /// trees.
+    pub fn from_sources(module_tree: &ModuleTree, sources: &ParsedSources) -> Self {
+        let mut symbols = Symbols {
+            paths: PathResolver::new(module_tree),
+            ..Symbols::default()
+        };
+
+        let modules: Vec<(usize, &Path, &RustAnalysis, &syn::File, &str)> = module_tree
+            .module_files()
+            .into_iter()
+            .filter_map(|(root, module)| {
+                let path = module.file.as_deref()?;
+                Some((
+                    root,
+                    path,
+                    sources.get(path)?,
+                    sources.syntax_tree(path)?,
+                    module.path.as_str(),
+                ))
+            })
+            .collect();
+
+        let mut graph = CallGraph::default();
+        for &(root, path, analysis, _, module) in &modules {
+            symbols.register(
+                &mut graph,
+                &module_tree.roots[root],
+                root,
+                path,
+                analysis,
+                module,
+            );
+        }
+
+        let mut seen = HashSet::new();
+        for &(root, _, _, syntax_tree, module) in &modules {
+            let mut visitor = CallVisitor {
+                symbols: &symbols,
+                root,
+                file_module: module,
+                scope: Vec::new(),
+                modules: vec![module.to_string()],
+                self_types: Vec::new(),
+                callers: Vec::new(),
+                bindings: Vec::new(),
+                calls: Vec::new(),
+            };
+            visitor.visit_file(syntax_tree);
+
+            for edge in visitor.calls {
+                if seen.insert(edge) {
+                    graph.calls.push(edge);
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Finds a function by its full path or its path without `crate::`
+    /// (e.g., "scanner::RepositoryScanner::scan").
+    pub fn find(&self, path: &str) -> Option<usize> {
+        self.functions
+            .iter()
+            .position(|function| function.path == path || function.short_path() == path)
+    }
+
+    /// Functions called or referenced by `function`, in source order.
+    pub fn callees(&self, function: usize) -> impl Iterator<Item = usize> + '_ {
+        self.calls
+            .iter()
+            .filter(move |edge| edge.caller == function)
+            .map(|edge| edge.callee)
+    }
+
+    /// Functions calling or referencing `function`.
+    pub fn callers(&self, function: usize) -> impl Iterator<Item = usize> + '_ {
+        self.calls
+            .iter()
+            .filter(move |edge| edge.callee == function)
+            .map(|edge| edge.caller)
+    }
+
+    /// Renders the graph as a Mermaid flowchart, leaving out functions
+    /// without any call.
+    ///
+    /// ```rust
+    /// use ai_context_gen::call_graph::{CallEdge, CallGraph, FunctionNode};
+    /// use ai_context_gen::module_tree::RootKind;
+    ///
+    /// let node = |path: &str| FunctionNode {
+    ///     target: "demo".to_string(),
+    ///     kind: RootKind::Lib,
+    ///     path: path.to_string(),
+    ///     file: "src/lib.rs".into(),
+    ///     line: 1,
+    /// };
+    /// let graph = CallGraph {
+    ///     functions: vec![node("crate::run"), node("crate::Scanner::scan")],
+    ///     calls: vec![CallEdge { caller: 0, callee: 1 }],
+    /// };
+    /// assert_eq!(
+    ///     graph.to_mermaid(),
+    ///     "flowchart LR\n    f0[\"run\"]\n    f1[\"Scanner::scan\"]\n    f0 --> f1\n"
+    /// );
+    /// ```
+    pub fn to_mermaid(&self) -> String {
+        let mut connected: Vec<usize> = self
+            .calls
+            .iter()
+            .flat_map(|edge| [edge.caller, edge.callee])
+            .collect();
+        connected.sort_unstable();
+        connected.dedup();
+
+        let mut mermaid = String::from("flowchart LR\n");
+        for &function in &connected {
+            mermaid.push_str(&format!(
+                "    f{function}[\"{}\"]\n",
+                self.display_path(function, None)
+            ));
+        }
+        for edge in &self.calls {
+            mermaid.push_str(&format!("    f{} --> f{}\n", edge.caller, edge.callee));
+        }
+        mermaid
+    }
+
+    /// Path of `function` as seen from crate root `from`: prefixed with its
+    /// target name when it lives in another crate.
+    pub fn display_path(&self, function: usize, from: Option<&str>) -> String {
+        let node = &self.functions[function];
+        match from {
+            Some(target) if target != node.target => {
+                format!("{}::{}", node.target, node.short_path())
+            }
+            _ => node.short_path().to_string(),
+        }
+    }
+}
+
+/// Crate-local names known to the call resolution. Crates are identified by
+/// their index in [`ModuleTree::roots`].
+#[derive(Default)]
+struct Symbols {
+    /// Modules, libraries and the names brought into each module by `use`.
+    /// Libraries are searched for methods and fields in module tree order.
+    paths: PathResolver,
+    /// Functions by full path.
+    functions: HashMap<(usize, String), usize>,
+    /// Methods by `Type::method`, wherever their impl lives.
+    methods: HashMap<(usize, String), Vec<usize>>,
+    /// Return type of each function, with `Self` replaced by the impl type.
+    returns: Vec<Option<Type>>,
+    /// Field types of each struct, by struct name.
+    fields: HashMap<(usize, String), HashMap<String, Type>>,
+    /// Modules glob-imported into each module.
+    globs: HashMap<(usize, String), Vec<(usize, String)>>,
+}
+
+impl Symbols {
+    /// Records the functions, struct fields and imports of one file.
+    fn register(
+        &mut self,
+        graph: &mut CallGraph,
+        crate_root: &CrateRoot,
+        root: usize,
+        file: &Path,
+        analysis: &RustAnalysis,
+        module: &str,
+    ) {
+        // Methods are owned by the last segment of their scope: the impl type
+        // or the trait
+        let methods =
+            analysis
+                .implementations
+                .iter()
+                .flat_map(|implementation| &implementation.methods)
+                .chain(analysis.traits.iter().flat_map(|trait_info| {
+                    trait_info.methods.iter().map(|method| &method.function)
+                }))
+                .map(|function| (function, function.path.rsplit("::").nth(1)));
+        let mut functions: Vec<(&FunctionInfo, Option<&str>)> = analysis
+            .functions
+            .iter()
+            .map(|function| (function, None))
+            .chain(methods)
+            .collect();
+        functions.sort_by_key(|(function, _)| function.span.start_line);
+
+        for (function, owner) in functions {
+            let full_path = qualify(module, &function.path);
+            let id = graph.functions.len();
+
+            graph.functions.push(FunctionNode {
+                target: crate_root.target.clone(),
+                kind: crate_root.kind,
+                path: full_path.clone(),
+                file: file.to_path_buf(),
+                line: function.span.start_line,
+            });
+            self.returns.push(return_type(function, owner));
+            self.functions.insert((root, full_path), id);
+            if let Some(owner) = owner {
+                self.methods
+                    .entry((root, format!("{owner}::{}", function.name)))
+                    .or_default()
+                    .push(id);
+            }
+        }
+
+        for struct_info in &analysis.structs {
+            let fields = struct_info
+                .fields
+                .iter()
+                .filter_map(|field| {
+                    let ty = syn::parse_str::<Type>(&field.field_type).ok()?;
+                    Some((field.name.clone(), ty))
+                })
+                .collect();
+            self.fields.insert((root, struct_info.name.clone()), fields);
+        }
+
+        for import in &analysis.imports {
+            let scope = qualify(module, &import.module_path);
+            let segments: Vec<String> = import.path.split("::").map(str::to_string).collect();
+            if import.is_glob {
+                let target = self.absolute(root, &scope, &segments[..segments.len() - 1]);
+                if let Some(target) = target {
+                    self.globs.entry((root, scope)).or_default().push(target);
+                }
+                continue;
+            }
+
+            let name = import
+                .alias
+                .clone()
+                .or_else(|| segments.last().cloned())
+                .unwrap_or_default();
+            if let Some(target) = self.absolute(root, &scope, &segments) {
+                self.paths.add_import(root, scope, name, target);
+            }
+        }
+    }
+
+    /// Turns a `use` path written in `module` into a crate and full path, or
+    /// `None` for paths into `std` and external crates.
+    fn absolute(&self, root: usize, module: &str, segments: &[String]) -> Option<(usize, String)> {
+        match self.paths.absolute(root, module, segments)? {
+            ResolvedPath::Crate(root, path) => Some((root, path)),
+            ResolvedPath::Std(_) | ResolvedPath::External(_) => None,
+        }
+    }
+
+    /// Resolves a path used in an expression in `module` to a function.
+    fn resolve(&self, root: usize, module: &str, segments: &[String]) -> Option<usize> {
+        let (first, rest) = segments.split_first()?;
+
+        if let Some((target_root, target)) = self.paths.import(root, module, first) {
+            return self.lookup(*target_root, &join(target, rest));
+        }
+        if let Some((target_root, target)) = self.absolute(root, module, segments) {
+            return self.lookup(target_root, &target);
+        }
+        if let Some(function) = self.lookup(root, &join(module, segments)) {
+            return Some(function);
+        }
+        self.globs
+            .get(&(root, module.to_string()))
+            .into_iter()
+            .flatten()
+            .find_map(|(glob_root, glob)| self.lookup(*glob_root, &join(glob, segments)))
+    }
+
+    /// Finds the function at a full path, following re-exports along the
+    /// way, then falling back to `Type::method` wherever the impl lives.
+    fn lookup(&self, root: usize, path: &str) -> Option<usize> {
+        let (root, path) = self.paths.follow_reexports(root, path);
+        if let Some(&function) = self.functions.get(&(root, path.clone())) {
+            return Some(function);
+        }
+
+        let mut segments = path.rsplit("::");
+        let method = segments.next()?;
+        let owner = segments.next()?;
+        self.method(&[root], owner, method)
+    }
+
+    /// Finds the only method `owner::method` among the crates in `roots`,
+    /// trying them in order.
+    fn method(&self, roots: &[usize], owner: &str, method: &str) -> Option<usize> {
+        roots.iter().find_map(|&root| {
+            match self
+                .methods
+                .get(&(root, format!("{owner}::{method}")))?
+                .as_slice()
+            {
+                [function] => Some(*function),
+                _ => None,
+            }
+        })
+    }
+
+    /// Type of field `member` of the struct named `owner`.
+    fn field(&self, roots: &[usize], owner: &str, member: &str) -> Option<Type> {
+        roots
+            .iter()
+            .find_map(|&root| self.fields.get(&(root, owner.to_string()))?.get(member))
+            .cloned()
+    }
+}
+
+/// Walks function bodies, recording the calls [`Symbols`] can resolve.
+struct CallVisitor<'a> {
+    symbols: &'a Symbols,
+    root: usize,
+    /// Module path of the file being walked.
+    file_module: &'a str,
+    /// Path of the current item relative to the file, as the parser builds it.
+    scope: Vec<String>,
+    /// Enclosing modules; the last one is used for name resolution.
+    modules: Vec<String>,
+    /// Self types of the enclosing impls and traits.
+    self_types: Vec<String>,
+    /// Enclosing functions, `None` when not a known node.
+    callers: Vec<Option<usize>>,
+    /// Types of the parameters and locals of each enclosing function.
+    bindings: Vec<HashMap<String, Type>>,
+    calls: Vec<CallEdge>,
+}
+
+impl CallVisitor<'_> {
+    fn module(&self) -> &str {
+        self.modules.last().map_or(self.file_module, String::as_str)
+    }
+
+    /// Crates searched for methods and fields: this one, then the libraries.
+    fn crates(&self) -> Vec<usize> {
+        let mut crates = vec![self.root];
+        crates.extend(
+            self.symbols
+                .paths
+                .libraries()
+                .iter()
+                .filter(|&&lib| lib != self.root),
+        );
+        crates
+    }
+
+    fn record(&mut self, callee: usize) {
+        if let Some(Some(caller)) = self.callers.last() {
+            self.calls.push(CallEdge {
+                caller: *caller,
+                callee,
+            });
+        }
+    }
+
+    /// Walks the body of a function declared in the current scope.
+    fn visit_function(&mut self, sig: &Signature, block: &Block) {
+        let name = sig.ident.to_string();
+        let relative = qualify(&self.scope.join("::"), &name);
+        let caller = self
+            .symbols
+            .functions
+            .get(&(self.root, qualify(self.file_module, &relative)))
+            .copied();
+
+        let self_type = self.self_types.last().cloned();
+        let mut bindings = HashMap::new();
+        for input in &sig.inputs {
+            match input {
+                FnArg::Receiver(_) => {
+                    if let Some(self_type) = &self_type {
+                        if let Ok(ty) = syn::parse_str::<Type>(self_type) {
+                            bindings.insert("self".to_string(), ty);
+                        }
+                    }
+                }
+                FnArg::Typed(typed) => {
+                    if let Pat::Ident(ident) = typed.pat.as_ref() {
+                        let ty = match &self_type {
+                            Some(self_type) => replace_self(&typed.ty, self_type),
+                            None => (*typed.ty).clone(),
+                        };
+                        bindings.insert(ident.ident.to_string(), ty);
+                    }
+                }
+            }
+        }
+
+        self.scope.push(name);
+        self.callers.push(caller);
+        self.bindings.push(bindings);
+        self.visit_block(block);
+        self.bindings.pop();
+        self.callers.pop();
+        self.scope.pop();
+    }
+
+    /// Resolves an expression path, with `Self` standing for the current
+    /// impl type.
+    fn resolve_path(&self, path: &ExprPath) -> Option<usize> {
+        if path.qself.is_some() {
+            return None;
+        }
+        let mut segments: Vec<String> = path
+            .path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+
+        if let [name] = segments.as_slice() {
+            let is_local = self
+                .bindings
+                .last()
+                .is_some_and(|bindings| bindings.contains_key(name));
+            if is_local {
+                return None;
+            }
+        }
+        if segments.first().is_some_and(|first| first == "Self") {
+            segments[0] = self.self_types.last()?.clone();
+        }
+
+        self.symbols.resolve(self.root, self.module(), &segments)
+    }
+
+    /// Best-effort static type of `expr`.
+    fn infer(&self, expr: &Expr) -> Option<Type> {
+        match expr {
+            Expr::Path(path) if path.qself.is_none() => {
+                let ident = path.path.get_ident()?.to_string();
+                self.bindings.last()?.get(&ident).cloned()
+            }
+            Expr::Field(field) => {
+                let owner = nominal(&self.infer(&field.base)?)?;
+                let member = match &field.member {
+                    syn::Member::Named(ident) => ident.to_string(),
+                    syn::Member::Unnamed(index) => index.index.to_string(),
+                };
+                self.symbols.field(&self.crates(), &owner, &member)
+            }
+            Expr::Call(call) => match call.func.as_ref() {
+                Expr::Path(path) => {
+                    let function = self.resolve_path(path)?;
+                    self.symbols.returns[function].clone()
+                }
+                _ => None,
+            },
+            Expr::MethodCall(call) => {
+                let receiver = self.infer(&call.receiver)?;
+                if call.method == "unwrap" || call.method == "expect" {
+                    return unwrap_fallible(&receiver);
+                }
+                let owner = nominal(&receiver)?;
+                let method = call.method.to_string();
+                let function = self.symbols.method(&self.crates(), &owner, &method)?;
+                self.symbols.returns[function].clone()
+            }
+            Expr::Try(expr) => unwrap_fallible(&self.infer(&expr.expr)?),
+            Expr::Await(expr) => self.infer(&expr.base),
+            Expr::Reference(expr) => self.infer(&expr.expr),
+            Expr::Paren(expr) => self.infer(&expr.expr),
+            Expr::Struct(expr) => {
+                let name = expr.path.segments.last()?.ident.to_string();
+                let name = match name.as_str() {
+                    "Self" => self.self_types.last()?.clone(),
+                    _ => name,
+                };
+                syn::parse_str(&name).ok()
+            }
+            Expr::Cast(expr) => Some((*expr.ty).clone()),
+            _ => None,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CallVisitor<'_> {
+    fn visit_item_mod(&mut self, item: &'ast ItemMod) {
+        if item.content.is_none() {
+            return;
+        }
+        let name = item.ident.to_string();
+        self.modules.push(qualify(self.module(), &name));
+        self.scope.push(name);
+        visit::visit_item_mod(self, item);
+        self.scope.pop();
+        self.modules.pop();
+    }
+
+    fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+        self.visit_function(&item.sig, &item.block);
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast ItemImpl) {
+        let name = RustParser::type_name(&item.self_ty);
+        self.scope.push(name.clone());
+        self.self_types.push(name);
+        visit::visit_item_impl(self, item);
+        self.self_types.pop();
+        self.scope.pop();
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast ImplItemFn) {
+        self.visit_function(&item.sig, &item.block);
+    }
+
+    fn visit_item_trait(&mut self, item: &'ast ItemTrait) {
+        let name = item.ident.to_string();
+        self.scope.push(name.clone());
+        self.self_types.push(name);
+        visit::visit_item_trait(self, item);
+        self.self_types.pop();
+        self.scope.pop();
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast TraitItemFn) {
+        if let Some(block) = &item.default {
+            self.visit_function(&item.sig, block);
+        }
+    }
+
+    fn visit_local(&mut self, local: &'ast Local) {
+        visit::visit_local(self, local);
+
+        let (pat, ty) = match &local.pat {
+            Pat::Type(typed) => (typed.pat.as_ref(), Some((*typed.ty).clone())),
+            pat => (pat, None),
+        };
+        if let Pat::Ident(ident) = pat {
+            let ty = ty.or_else(|| {
+                let init = local.init.as_ref()?;
+                self.infer(&init.expr)
+            });
+            if let (Some(ty), Some(bindings)) = (ty, self.bindings.last_mut()) {
+                bindings.insert(ident.ident.to_string(), ty);
+            }
+        }
+    }
+
+    fn visit_expr_path(&mut self, path: &'ast ExprPath) {
+        if let Some(callee) = self.resolve_path(path) {
+            self.record(callee);
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        self.visit_expr(&call.receiver);
+
+        let callee = self.infer(&call.receiver).and_then(|receiver| {
+            let owner = nominal(&receiver)?;
+            self.symbols
+                .method(&self.crates(), &owner, &call.method.to_string())
+        });
+        if let Some(callee) = callee {
+            self.record(callee);
+        }
+
+        for arg in &call.args {
+            self.visit_expr(arg);
+        }
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        // Arguments of `format!`, `assert!`, `vec!`... are plain expressions
+        // that syn leaves as tokens
+        if let Ok(args) = mac.parse_body_with(Punctuated::<Expr, syn::Token![,]>::parse_terminated)
+        {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+}
+
+/// Return type of `function`, with `Self` replaced by `owner` for methods.
+fn return_type(function: &FunctionInfo, owner: Option<&str>) -> Option<Type> {
+    let ty = syn::parse_str::<Type>(function.return_type.as_deref()?).ok()?;
+    Some(match owner {
+        Some(owner) => replace_self(&ty, owner),
+        None => ty,
+    })
+}
+
+/// Replaces every `Self` in `ty` by `owner`.
+fn replace_self(ty: &Type, owner: &str) -> Type {
+    fn replace(tokens: TokenStream, owner: &syn::Ident) -> TokenStream {
+        tokens
+            .into_iter()
+            .map(|token| match token {
+                TokenTree::Ident(ident) if ident == "Self" => TokenTree::Ident(owner.clone()),
+                TokenTree::Group(group) => {
+                    let mut replaced =
+                        Group::new(group.delimiter(), replace(group.stream(), owner));
+                    replaced.set_span(group.span());
+                    TokenTree::Group(replaced)
+                }
+                token => token,
+            })
+            .collect()
+    }
+
+    let Ok(owner) = syn::parse_str::<syn::Ident>(owner) else {
+        return ty.clone();
+    };
+    syn::parse2(replace(ty.to_token_stream(), &owner)).unwrap_or_else(|_| ty.clone())
+}
+
+/// Name of the type whose methods are called on a value of type `ty`,
+/// looking through references and smart pointers.
+fn nominal(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Reference(reference) => nominal(&reference.elem),
+        Type::Paren(paren) => nominal(&paren.elem),
+        Type::Group(group) => nominal(&group.elem),
+        Type::Path(path) => {
+            let segment = path.path.segments.last()?;
+            let name = segment.ident.to_string();
+            if POINTER_TYPES.contains(&name.as_str()) {
+                nominal(&first_type_argument(segment)?)
+            } else {
+                Some(name)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The `T` of an `Option<T>` or `Result<T, E>`, as produced by `?`,
+/// `unwrap()` or `expect()`.
+fn unwrap_fallible(ty: &Type) -> Option<Type> {
+    match ty {
+        Type::Reference(reference) => unwrap_fallible(&reference.elem),
+        Type::Path(path) => {
+            let segment = path.path.segments.last()?;
+            if segment.ident == "Option" || segment.ident == "Result" {
+                first_type_argument(segment)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn first_type_argument(segment: &syn::PathSegment) -> Option<Type> {
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) => {
+            arguments.args.iter().find_map(|argument| match argument {
+                syn::GenericArgument::Type(ty) => Some(ty.clone()),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
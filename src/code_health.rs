@@ -0,0 +1,436 @@
+//! Code health audit for the AI Context Generator.
+//!
+//! This module collects the technical debt signals scattered through Rust
+//! source: `TODO`/`FIXME`/`HACK`/`XXX` comments, `unsafe` blocks and
+//! `unsafe impl`s, and call sites that can panic (`unwrap()`, `expect()`,
+//! `panic!`, `todo!`, `unimplemented!`). The findings are aggregated per file
+//! into a compact listing that survives when the source itself is truncated.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Attribute, Expr, ExprMethodCall, ExprUnsafe, ImplItemFn, ItemFn, ItemImpl, ItemMod};
+
+use crate::parser::{is_cfg_test, ParsedSources, RustParser};
+use crate::scanner::{FileType, ScanResult};
+use crate::source_transform::{LineOffsets, SourceTransformer};
+
+/// Comment tags reported as markers.
+const MARKER_TAGS: &[&str] = &["TODO", "FIXME", "HACK", "XXX"];
+
+/// Longest marker text kept; longer comments are cut at a word boundary.
+const MAX_MARKER_TEXT_LEN: usize = 100;
+
+/// Code health findings for every Rust file of a scan result.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::code_health::CodeHealth;
+/// use ai_context_gen::{Config, RepositoryScanner};
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// let scan_result = RepositoryScanner::new(Config::default()).scan().await?;
+///
+/// for file in CodeHealth::audit(&scan_result).files {
+///     println!("{}: {} markers", file.path.display(), file.markers.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CodeHealth {
+    /// Files with at least one finding, in scan order.
+    pub files: Vec<FileHealth>,
+}
+
+/// Code health findings for a single file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileHealth {
+    /// Path of the file relative to the repository root.
+    pub path: PathBuf,
+
+    /// `TODO`, `FIXME`, `HACK` and `XXX` comments.
+    pub markers: Vec<Marker>,
+
+    /// `unsafe` blocks and `unsafe impl`s.
+    pub unsafe_sites: Vec<UnsafeSite>,
+
+    /// Call sites that can panic.
+    pub panic_sites: Vec<PanicSite>,
+}
+
+/// A `TODO`-style comment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Marker {
+    /// Tag of the marker (e.g., "FIXME").
+    pub tag: String,
+
+    /// Line of the comment (1-based).
+    pub line: usize,
+
+    /// Comment text following the tag, without the leading `:`.
+    pub text: String,
+}
+
+/// An `unsafe` block or `unsafe impl`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsafeSite {
+    /// Kind of unsafe code.
+    pub kind: UnsafeKind,
+
+    /// Line of the `unsafe` keyword (1-based).
+    pub line: usize,
+
+    /// Header of the impl (e.g., "unsafe impl Send for Handle"); empty for
+    /// blocks.
+    pub detail: String,
+}
+
+/// Kinds of unsafe code reported by the audit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnsafeKind {
+    /// `unsafe { ... }` block.
+    Block,
+    /// `unsafe impl` of an unsafe trait.
+    Impl,
+}
+
+/// A call site that can panic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PanicSite {
+    /// Kind of call.
+    pub kind: PanicKind,
+
+    /// Line of the call (1-based).
+    pub line: usize,
+}
+
+/// Kinds of panicking calls reported by the audit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum PanicKind {
+    /// `.unwrap()`
+    Unwrap,
+    /// `.expect(..)`
+    Expect,
+    /// `panic!(..)`
+    Panic,
+    /// `todo!(..)`
+    Todo,
+    /// `unimplemented!(..)`
+    Unimplemented,
+}
+
+impl PanicKind {
+    /// How the call appears in source, e.g. `unwrap()` or `panic!`.
+    pub fn label(self) -> &'static str {
+        match self {
+            PanicKind::Unwrap => "unwrap()",
+            PanicKind::Expect => "expect()",
+            PanicKind::Panic => "panic!",
+            PanicKind::Todo => "todo!",
+            PanicKind::Unimplemented => "unimplemented!",
+        }
+    }
+}
+
+impl CodeHealth {
+    /// Audits every Rust file of `scan_result`.
+    ///
+    /// Markers are collected everywhere. Unsafe and panic sites are only
+    /// collected in non-test code: `#[cfg(test)]` modules, `#[test]`
+    /// functions and files under `tests/` or `benches/` directories are
+    /// skipped, since unwrapping is idiomatic there.
+    pub fn audit(scan_result: &ScanResult) -> Self {
+        Self::from_sources(scan_result, &ParsedSources::parse(scan_result))
+    }
+
+    /// Audits every Rust file of `scan_result`, reading unsafe and panic
+    /// sites from the syntax trees in `sources`.
+    pub fn from_sources(scan_result: &ScanResult, sources: &ParsedSources) -> Self {
+        let files = scan_result
+            .files
+            .iter()
+            .filter(|file| matches!(file.file_type, FileType::Rust))
+            .map(|file| {
+                let is_test_file = file.relative_path.components().any(|component| {
+                    matches!(component, Component::Normal(name) if name == "tests" || name == "benches")
+                });
+                let syntax_tree = sources
+                    .syntax_tree(&file.relative_path)
+                    .filter(|_| !is_test_file);
+                Self::audit_syntax(&file.relative_path, &file.content, syntax_tree)
+            })
+            .filter(|health| !health.is_empty())
+            .collect();
+
+        CodeHealth { files }
+    }
+
+    /// Audits a single Rust source file. When `is_test_file` is true only
+    /// markers are collected.
+    ///
+    /// Files that do not parse are audited for the items that can be
+    /// recovered (see [`RustParser::parse_file_recovering`]).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ai_context_gen::code_health::{CodeHealth, PanicKind, UnsafeKind};
+    /// use std::path::Path;
+    ///
+    /// let source = r#"
+    /// // TODO: support IPv6
+    /// pub fn connect(addr: &str) -> u16 {
+    ///     let port = addr.parse().expect("valid port");
+    ///     unsafe { libc_call() };
+    ///     port
+    /// }
+    ///
+    /// unsafe impl Send for Handle {}
+    ///
+    /// #[cfg(test)]
+    /// mod tests {
+    ///     #[test]
+    ///     fn parses() { "1".parse::<u16>().unwrap(); }
+    /// }
+    /// "#;
+    ///
+    /// let health = CodeHealth::audit_file(Path::new("src/net.rs"), source, false);
+    /// assert_eq!(health.markers[0].tag, "TODO");
+    /// assert_eq!(health.markers[0].text, "support IPv6");
+    /// assert_eq!(health.markers[0].line, 2);
+    /// assert_eq!(health.unsafe_sites[0].kind, UnsafeKind::Block);
+    /// assert_eq!(health.unsafe_sites[1].detail, "unsafe impl Send for Handle");
+    /// assert_eq!(health.panic_sites.len(), 1);
+    /// assert_eq!(health.panic_sites[0].kind, PanicKind::Expect);
+    /// assert_eq!(health.panic_sites[0].line, 4);
+    /// ```
+    pub fn audit_file(path: &Path, content: &str, is_test_file: bool) -> FileHealth {
+        let syntax_tree = (!is_test_file).then(|| RustParser::parse_file_recovering(content).0);
+        Self::audit_syntax(path, content, syntax_tree.as_ref())
+    }
+
+    /// Collects the markers of `content`, and the unsafe and panic sites of
+    /// `syntax_tree` when there is one.
+    fn audit_syntax(path: &Path, content: &str, syntax_tree: Option<&syn::File>) -> FileHealth {
+        let mut health = FileHealth {
+            path: path.to_path_buf(),
+            markers: Self::markers(content),
+            ..FileHealth::default()
+        };
+
+        if let Some(syntax_tree) = syntax_tree {
+            let mut collector = SiteCollector::default();
+            collector.visit_file(syntax_tree);
+            health.unsafe_sites = collector.unsafe_sites;
+            health.panic_sites = collector.panic_sites;
+        }
+
+        health
+    }
+
+    /// Finds `TODO`-style tags in the comments of `content`, at most one per
+    /// comment line. A tag counts when it opens the comment text or is
+    /// followed by `:` or `(`, so prose merely mentioning "TODO" is skipped.
+    /// In doc comments only the first form counts, since they often quote
+    /// code and comments from examples.
+    fn markers(content: &str) -> Vec<Marker> {
+        let mut markers = Vec::new();
+        let offsets = LineOffsets::new(content);
+
+        for comment in SourceTransformer::comments(content) {
+            let first_line = offsets.line(comment.range.start);
+            for (offset, line) in content[comment.range].lines().enumerate() {
+                let text = if comment.is_doc {
+                    let line = line.trim_start();
+                    ["///", "//!", "/**", "/*!", "*"]
+                        .iter()
+                        .find_map(|prefix| line.strip_prefix(prefix))
+                        .unwrap_or(line)
+                        .trim_start()
+                } else {
+                    line.trim_start_matches(['/', '*', '!', ' ', '\t'])
+                };
+                let Some((tag, after_tag)) = Self::find_tag(text, !comment.is_doc) else {
+                    continue;
+                };
+
+                markers.push(Marker {
+                    tag: tag.to_string(),
+                    line: first_line + offset,
+                    text: Self::marker_text(after_tag),
+                });
+            }
+        }
+
+        markers
+    }
+
+    /// Finds a marker tag in one line of comment text, returning it with the
+    /// text that follows. Unless `anywhere` is set, the tag must open the
+    /// text.
+    fn find_tag(text: &str, anywhere: bool) -> Option<(&'static str, &str)> {
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        MARKER_TAGS.iter().find_map(|&tag| {
+            text.match_indices(tag).find_map(|(index, _)| {
+                let after = &text[index + tag.len()..];
+                let starts_word = !text[..index].ends_with(is_word);
+                let opens_text = index == 0 && !after.starts_with(is_word);
+                let labelled = anywhere && after.starts_with([':', '(']);
+                (starts_word && (opens_text || labelled)).then_some((tag, after))
+            })
+        })
+    }
+
+    /// Cleans the comment text after a tag: drops an `(owner)` and `:`
+    /// prefix, comment closers and excess length.
+    fn marker_text(after_tag: &str) -> String {
+        let mut text = after_tag.trim_start();
+        if text.starts_with('(') {
+            if let Some(end) = text.find(')') {
+                text = text[end + 1..].trim_start();
+            }
+        }
+        let text = text
+            .trim_start_matches([':', '-'])
+            .trim()
+            .trim_end_matches("*/")
+            .trim_end();
+
+        if text.chars().count() <= MAX_MARKER_TEXT_LEN {
+            return text.to_string();
+        }
+        let cut: String = text.chars().take(MAX_MARKER_TEXT_LEN).collect();
+        let cut = cut.rsplit_once(' ').map_or(cut.as_str(), |(head, _)| head);
+        format!("{cut}…")
+    }
+}
+
+impl FileHealth {
+    /// Whether the file has no findings at all.
+    pub fn is_empty(&self) -> bool {
+        self.markers.is_empty() && self.unsafe_sites.is_empty() && self.panic_sites.is_empty()
+    }
+}
+
+/// Collects unsafe and panic sites, skipping test code.
+#[derive(Default)]
+struct SiteCollector {
+    unsafe_sites: Vec<UnsafeSite>,
+    panic_sites: Vec<PanicSite>,
+}
+
+impl<'ast> Visit<'ast> for SiteCollector {
+    fn visit_item_mod(&mut self, item: &'ast ItemMod) {
+        if !item.attrs.iter().any(is_cfg_test) {
+            visit::visit_item_mod(self, item);
+        }
+    }
+
+    fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+        if !is_test_function(&item.attrs) {
+            visit::visit_item_fn(self, item);
+        }
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast ImplItemFn) {
+        if !is_test_function(&item.attrs) {
+            visit::visit_impl_item_fn(self, item);
+        }
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast ItemImpl) {
+        if let Some(unsafe_token) = &item.unsafety {
+            self.unsafe_sites.push(UnsafeSite {
+                kind: UnsafeKind::Impl,
+                line: unsafe_token.span.start().line,
+                detail: impl_header(item),
+            });
+        }
+        visit::visit_item_impl(self, item);
+    }
+
+    fn visit_expr_unsafe(&mut self, expr: &'ast ExprUnsafe) {
+        self.unsafe_sites.push(UnsafeSite {
+            kind: UnsafeKind::Block,
+            line: expr.unsafe_token.span.start().line,
+            detail: String::new(),
+        });
+        visit::visit_expr_unsafe(self, expr);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        let kind = match (call.method.to_string().as_str(), call.args.len()) {
+            ("unwrap", 0) => Some(PanicKind::Unwrap),
+            ("expect", 1) => Some(PanicKind::Expect),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            self.panic_sites.push(PanicSite {
+                kind,
+                line: call.method.span().start().line,
+            });
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let name = mac
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string());
+        let kind = match name.as_deref() {
+            Some("panic") => Some(PanicKind::Panic),
+            Some("todo") => Some(PanicKind::Todo),
+            Some("unimplemented") => Some(PanicKind::Unimplemented),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            self.panic_sites.push(PanicSite {
+                kind,
+                line: mac.path.span().start().line,
+            });
+        }
+
+        // Arguments of `format!`, `assert!`, `vec!`... are plain expressions
+        // that syn leaves as tokens; audit them as well.
+        if let Ok(args) = mac.parse_body_with(Punctuated::<Expr, syn::Token![,]>::parse_terminated)
+        {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+}
+
+/// Whether the attributes mark a test function: `#[test]` or a runtime's
+/// variant such as `#[tokio::test]`.
+fn is_test_function(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "test")
+    })
+}
+
+/// Formats the header of an impl block on one line, without its items.
+fn impl_header(item: &ItemImpl) -> String {
+    let header = ItemImpl {
+        attrs: Vec::new(),
+        items: Vec::new(),
+        ..item.clone()
+    };
+    let formatted = prettyplease::unparse(&syn::File {
+        shebang: None,
+        attrs: Vec::new(),
+        items: vec![syn::Item::Impl(header)],
+    });
+    let formatted = formatted.trim_end();
+    let formatted = formatted.strip_suffix("{}").unwrap_or(formatted);
+    RustParser::inline_signature(formatted.trim_end())
+}
@@ -28,6 +28,18 @@ use std::path::PathBuf;
 ///     output_file: "custom_context.md".to_string(),
 ///     include_hidden: true,
 ///     include_deps: false,
+///     include_patterns: vec!["crates/core/**".to_string()],
+///     exclude_patterns: vec!["**/tests/**".to_string()],
+///     hide_private_fields: false,
+///     public_api: false,
+///     compaction: Default::default(),
+///     prioritize_complex_code: false,
+///     code_health: false,
+///     call_graph: false,
+///     type_graph: false,
+///     import_graph: false,
+///     test_inventory: false,
+///     mermaid: false,
 /// };
 /// ```
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -60,8 +72,152 @@ pub struct Config {
     /// Whether to include external dependency analysis.
     ///
     /// When `true`, the generator will attempt to analyze and include
-    /// information about external dependencies from `Cargo.toml`.
+    /// information about external dependencies from `Cargo.toml`. Direct
+    /// dependencies are resolved against `Cargo.lock` to report their exact
+    /// versions, sources and transitive dependency trees, and the public
+    /// items the project imports from them are summarized from their sources
+    /// in the local cargo registry or `vendor/` directory.
     pub include_deps: bool,
+
+    /// Glob patterns selecting which files are scanned.
+    ///
+    /// Patterns are matched against paths relative to [`Config::repo_path`]
+    /// using `/` as separator (e.g. `crates/core/**`, `src/*.rs`). When the list
+    /// is non-empty, only files matching at least one pattern are included.
+    /// A pattern prefixed with `!` excludes matching files instead.
+    #[serde(default)]
+    pub include_patterns: Vec<String>,
+
+    /// Glob patterns for files and directories to leave out of the scan.
+    ///
+    /// Matching directories are skipped entirely. Exclusions always win over
+    /// [`Config::include_patterns`]. A leading `!` is accepted and ignored, so
+    /// `!**/tests/**` and `**/tests/**` are equivalent here.
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
+
+    /// Whether to leave private fields out of struct listings.
+    ///
+    /// When `true`, the Rust analysis lists only fields with a visibility
+    /// modifier (`pub`, `pub(crate)`, ...) and notes how many were hidden.
+    /// Useful when the context is meant to describe a crate's public API.
+    #[serde(default)]
+    pub hide_private_fields: bool,
+
+    /// Whether to describe library crates by their public API only.
+    ///
+    /// When `true`, the per-file Rust analysis and source code sections are
+    /// replaced by one stub per library crate, listing the items reachable
+    /// from the crate root through `pub` modules and `pub use` re-exports,
+    /// with their signatures and docs but no bodies. Projects without a
+    /// library target are analyzed as usual.
+    #[serde(default)]
+    pub public_api: bool,
+
+    /// How Rust source is compacted before it is counted and included.
+    #[serde(default)]
+    pub compaction: CompactionConfig,
+
+    /// Whether to order source sections by the total cyclomatic complexity
+    /// of their file, most complex first, instead of by crate and path.
+    ///
+    /// When the token budget runs out, the complex files are then the ones
+    /// kept whole, while simpler files fall back to elided bodies or are
+    /// truncated. See [`FunctionMetrics`](crate::parser::FunctionMetrics).
+    #[serde(default)]
+    pub prioritize_complex_code: bool,
+
+    /// Whether to add a code health section listing `TODO`/`FIXME`/`HACK`/
+    /// `XXX` comments, `unsafe` code and panicking call sites per file.
+    ///
+    /// The section is compact and kept at high priority, so this technical
+    /// debt stays visible when source sections are truncated.
+    #[serde(default)]
+    pub code_health: bool,
+
+    /// Whether to add a call graph section listing, for each crate-local
+    /// function, the functions it calls and the ones it is used by.
+    ///
+    /// Calls are resolved on a best-effort basis; see
+    /// [`CallGraph`](crate::call_graph::CallGraph).
+    #[serde(default)]
+    pub call_graph: bool,
+
+    /// Whether to add a type graph section summarizing, for each crate-local
+    /// type, the traits it implements, the types it contains and the
+    /// conversions it supports.
+    #[serde(default)]
+    pub type_graph: bool,
+
+    /// Whether to add an import graph section showing which modules each
+    /// module imports from, the external crates it uses, the resulting
+    /// layering and any dependency cycles.
+    #[serde(default)]
+    pub import_graph: bool,
+
+    /// Whether to add a test inventory section listing unit tests,
+    /// integration tests, benchmarks and doc tests, with the crate-local
+    /// functions each test exercises.
+    #[serde(default)]
+    pub test_inventory: bool,
+
+    /// Whether to add Mermaid diagrams of the enabled graphs: a class
+    /// diagram for [`type_graph`](Self::type_graph) and flowcharts for
+    /// [`import_graph`](Self::import_graph) and
+    /// [`call_graph`](Self::call_graph).
+    #[serde(default)]
+    pub mermaid: bool,
+}
+
+/// Options for compacting Rust source sections.
+///
+/// Every step is disabled by default, so source is included verbatim. Token
+/// savings are reported per file in the generated context.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::config::CompactionConfig;
+///
+/// let compaction = CompactionConfig::standard();
+/// assert!(compaction.strip_comments && !compaction.strip_doc_comments);
+/// assert!(!CompactionConfig::default().is_enabled());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionConfig {
+    /// Remove regular `//` and `/* */` comments, such as license headers.
+    pub strip_comments: bool,
+
+    /// Remove doc comments (`///`, `//!`, `/** */`, `/*! */`) too. They are
+    /// already summarized in the Rust analysis sections.
+    pub strip_doc_comments: bool,
+
+    /// Trim trailing whitespace and keep at most one blank line in a row.
+    pub collapse_blank_lines: bool,
+
+    /// Drop `#[cfg(test)]` modules.
+    pub strip_test_modules: bool,
+}
+
+impl CompactionConfig {
+    /// Strips regular comments, collapses blank lines and drops test modules,
+    /// keeping doc comments.
+    pub fn standard() -> Self {
+        Self {
+            strip_comments: true,
+            strip_doc_comments: false,
+            collapse_blank_lines: true,
+            strip_test_modules: true,
+        }
+    }
+
+    /// Whether any compaction step is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.strip_comments
+            || self.strip_doc_comments
+            || self.collapse_blank_lines
+            || self.strip_test_modules
+    }
 }
 
 impl Default for Config {
@@ -74,6 +230,18 @@ impl Default for Config {
     /// - `output_file`: `"repo_context.md"`
     /// - `include_hidden`: `false`
     /// - `include_deps`: `false`
+    /// - `include_patterns`: empty (all supported files)
+    /// - `exclude_patterns`: empty
+    /// - `hide_private_fields`: `false`
+    /// - `public_api`: `false`
+    /// - `compaction`: all steps disabled
+    /// - `prioritize_complex_code`: `false`
+    /// - `code_health`: `false`
+    /// - `call_graph`: `false`
+    /// - `type_graph`: `false`
+    /// - `import_graph`: `false`
+    /// - `test_inventory`: `false`
+    /// - `mermaid`: `false`
     ///
     /// # Examples
     ///
@@ -91,6 +259,18 @@ impl Default for Config {
             output_file: "repo_context.md".to_string(),
             include_hidden: false,
             include_deps: false,
+            include_patterns: Vec::new(),
+            exclude_patterns: Vec::new(),
+            hide_private_fields: false,
+            public_api: false,
+            compaction: CompactionConfig::default(),
+            prioritize_complex_code: false,
+            code_health: false,
+            call_graph: false,
+            type_graph: false,
+            import_graph: false,
+            test_inventory: false,
+            mermaid: false,
         }
     }
 }
@@ -113,3 +293,10 @@ pub const IGNORED_DIRS: &[&str] = &["target", "node_modules", ".git", ".vscode",
 /// These files are typically metadata, configuration, or system files
 /// that don't contribute meaningful content to the context.
 pub const IGNORED_FILES: &[&str] = &["Cargo.lock", ".gitignore", ".DS_Store"];
+
+/// Name of the project-specific ignore file honored during scanning.
+///
+/// It uses the same syntax as `.gitignore` (negation, anchoring and
+/// directory-only patterns) and can appear in any directory of the repository.
+/// Rules in this file take precedence over `.gitignore` and `.ignore`.
+pub const CUSTOM_IGNORE_FILE: &str = ".aicontextignore";
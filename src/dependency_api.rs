@@ -0,0 +1,495 @@
+//! Dependency API analysis module for the AI Context Generator.
+//!
+//! This module locates the sources of direct dependencies on the local machine
+//! (the cargo registry cache, a `vendor/` directory or a path dependency),
+//! parses them with [`RustParser`] and keeps only the public items that the
+//! scanned code actually imports, producing a compact API summary.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+use crate::manifest::{CargoManifest, DependencyInfo, DependencyKind, TargetKind};
+use crate::parser::{FunctionInfo, ParsedSources, RustParser};
+use crate::scanner::{ProjectMetadata, ScanResult};
+
+/// Maximum number of public items reported per dependency.
+pub const MAX_ITEMS_PER_DEPENDENCY: usize = 25;
+
+/// Maximum number of methods listed for each reported type.
+const MAX_METHODS_PER_TYPE: usize = 8;
+
+/// Maximum number of source files parsed per dependency.
+const MAX_FILES_PER_DEPENDENCY: usize = 300;
+
+/// Files larger than this are skipped when parsing dependency sources.
+const MAX_FILE_SIZE: u64 = 512 * 1024;
+
+/// Public API of one dependency, restricted to the items the project imports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyApi {
+    /// Dependency name as declared in `Cargo.toml`.
+    pub name: String,
+
+    /// Version of the located sources, if known.
+    pub version: Option<String>,
+
+    /// Directory the sources were read from.
+    pub source_dir: PathBuf,
+
+    /// Names the project imports from this dependency.
+    pub imported: Vec<String>,
+
+    /// Public items matching the imported names.
+    pub items: Vec<ApiItem>,
+}
+
+/// Kind of a public API item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ApiItemKind {
+    /// A free function.
+    Function,
+
+    /// A struct, with its public inherent methods.
+    Struct,
+
+    /// An enum, with its variants and public inherent methods.
+    Enum,
+}
+
+/// A public item from a dependency's API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiItem {
+    /// Item name.
+    pub name: String,
+
+    /// What kind of item this is.
+    pub kind: ApiItemKind,
+
+    /// One-line signature, e.g. `pub fn parse_file(content: &str) -> Result<File>`.
+    pub signature: String,
+
+    /// First line of the item's documentation.
+    pub summary: Option<String>,
+
+    /// Public inherent method signatures, for types.
+    pub methods: Vec<String>,
+}
+
+/// Builds API summaries for the direct dependencies of the scanned project.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ai_context_gen::dependency_api::DependencyApiAnalyzer;
+/// use ai_context_gen::{Config, RepositoryScanner};
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// let config = Config {
+///     include_deps: true,
+///     ..Config::default()
+/// };
+/// let scan_result = RepositoryScanner::new(config.clone()).scan().await?;
+///
+/// let analyzer = DependencyApiAnalyzer::new(config.repo_path.clone());
+/// for api in analyzer.analyze(&scan_result) {
+///     println!("{}: {} items", api.name, api.items.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct DependencyApiAnalyzer {
+    repo_path: PathBuf,
+    registry_src: Option<PathBuf>,
+}
+
+impl DependencyApiAnalyzer {
+    /// Creates an analyzer for the repository at `repo_path`.
+    ///
+    /// The cargo registry is looked up in `$CARGO_HOME/registry/src`, falling
+    /// back to `~/.cargo/registry/src`.
+    pub fn new(repo_path: PathBuf) -> Self {
+        let cargo_home = env::var_os("CARGO_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cargo")));
+
+        Self {
+            repo_path,
+            registry_src: cargo_home.map(|home| home.join("registry").join("src")),
+        }
+    }
+
+    /// Analyzes every normal dependency whose sources can be found locally.
+    ///
+    /// Workspace members are skipped since their code is already part of the
+    /// context. Dependencies that are not imported anywhere produce no entry.
+    pub fn analyze(&self, scan_result: &ScanResult) -> Vec<DependencyApi> {
+        self.analyze_sources(scan_result, &ParsedSources::parse(scan_result))
+    }
+
+    /// Like [`Self::analyze`], reading what the project imports from already
+    /// parsed sources.
+    pub fn analyze_sources(
+        &self,
+        scan_result: &ScanResult,
+        sources: &ParsedSources,
+    ) -> Vec<DependencyApi> {
+        let packages: Vec<(&ProjectMetadata, PathBuf)> = if scan_result.crates.is_empty() {
+            vec![(&scan_result.metadata, self.repo_path.clone())]
+        } else {
+            scan_result
+                .crates
+                .iter()
+                .map(|c| (&c.metadata, self.repo_path.join(&c.root)))
+                .collect()
+        };
+
+        let member_names: HashSet<&str> =
+            scan_result.crates.iter().map(|c| c.name.as_str()).collect();
+
+        let imports = Self::imported_names(scan_result, sources);
+        let mut seen = HashSet::new();
+        let mut apis = Vec::new();
+
+        for (metadata, package_dir) in packages {
+            for dependency in &metadata.dependencies {
+                if dependency.kind != DependencyKind::Normal
+                    || member_names.contains(dependency.package_name())
+                    || !seen.insert(dependency.name.clone())
+                {
+                    continue;
+                }
+
+                let Some(imported) = imports.get(&dependency.name.replace('-', "_")) else {
+                    continue;
+                };
+
+                let version = metadata
+                    .resolved_dependencies
+                    .iter()
+                    .find(|resolved| resolved.name == dependency.package_name())
+                    .map(|resolved| resolved.version.clone());
+
+                if let Some(source_dir) =
+                    self.locate_source(dependency, version.as_deref(), &package_dir)
+                {
+                    let items = Self::collect_items(&source_dir, imported);
+                    apis.push(DependencyApi {
+                        name: dependency.name.clone(),
+                        version: version.or_else(|| Self::version_from_dir(&source_dir)),
+                        source_dir,
+                        imported: imported.iter().cloned().collect(),
+                        items,
+                    });
+                }
+            }
+        }
+
+        apis
+    }
+
+    /// Finds the directory holding a dependency's sources.
+    ///
+    /// Path dependencies are used as-is; otherwise `vendor/<name>` and
+    /// `vendor/<name>-<version>` are tried before the cargo registry. When no
+    /// exact version is known, the highest cached version is used.
+    fn locate_source(
+        &self,
+        dependency: &DependencyInfo,
+        version: Option<&str>,
+        package_dir: &Path,
+    ) -> Option<PathBuf> {
+        if let Some(path) = &dependency.path {
+            // Paths inherited from `[workspace.dependencies]` are root-relative
+            return [package_dir.join(path), self.repo_path.join(path)]
+                .into_iter()
+                .find(|dir| dir.join("Cargo.toml").is_file());
+        }
+
+        let name = dependency.package_name();
+        let vendor = self.repo_path.join("vendor");
+        let mut candidates = vec![vendor.join(name)];
+        if let Some(version) = version {
+            candidates.push(vendor.join(format!("{name}-{version}")));
+        }
+        if let Some(found) = candidates
+            .into_iter()
+            .find(|dir| dir.join("Cargo.toml").is_file())
+        {
+            return Some(found);
+        }
+
+        let registries = fs::read_dir(self.registry_src.as_ref()?).ok()?;
+        let mut cached: Vec<PathBuf> = registries
+            .filter_map(|registry| registry.ok())
+            .filter_map(|registry| fs::read_dir(registry.path()).ok())
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|dir| {
+                let dir_name = dir.file_name().unwrap_or_default().to_string_lossy();
+                match dir_name
+                    .strip_prefix(name)
+                    .and_then(|v| v.strip_prefix('-'))
+                {
+                    Some(dir_version) => match version {
+                        Some(version) => dir_version == version,
+                        None => dir_version.starts_with(|c: char| c.is_ascii_digit()),
+                    },
+                    None => false,
+                }
+            })
+            .collect();
+
+        cached.sort_by_key(|dir| {
+            Self::version_from_dir(dir)
+                .map(|v| {
+                    v.split(|c: char| !c.is_ascii_digit())
+                        .map(|part| part.parse::<u64>().unwrap_or(0))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        });
+        cached.pop()
+    }
+
+    fn version_from_dir(dir: &Path) -> Option<String> {
+        let manifest = fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+        CargoManifest::parse(&manifest).ok()?.package?.version
+    }
+
+    /// Collects the names referenced through `<crate>::...` paths in the
+    /// scanned sources, keyed by the path's first segment.
+    ///
+    /// `use` declarations come from the expanded
+    /// [`RustAnalysis::imports`](crate::parser::RustAnalysis::imports) and
+    /// other paths from the recovered syntax trees, so files that only partly
+    /// parse still count. Dependencies are then looked up by their crate
+    /// identifier.
+    fn imported_names(
+        scan_result: &ScanResult,
+        sources: &ParsedSources,
+    ) -> HashMap<String, BTreeSet<String>> {
+        let mut collector = ImportCollector::default();
+
+        for file in &scan_result.files {
+            if let Some(analysis) = sources.get(&file.relative_path) {
+                for import in &analysis.imports {
+                    collector.add_path(import.path.split("::"));
+                }
+            }
+            if let Some(syntax_tree) = sources.syntax_tree(&file.relative_path) {
+                collector.visit_file(syntax_tree);
+            }
+        }
+
+        collector.names
+    }
+
+    /// Parses the dependency's library sources and keeps the public items
+    /// whose names are imported by the project.
+    fn collect_items(source_dir: &Path, imported: &BTreeSet<String>) -> Vec<ApiItem> {
+        let lib_root = fs::read_to_string(source_dir.join("Cargo.toml"))
+            .ok()
+            .and_then(|content| CargoManifest::parse(&content).ok())
+            .and_then(|mut manifest| {
+                manifest.discover_targets(source_dir);
+                manifest
+                    .targets
+                    .into_iter()
+                    .find(|t| t.kind == TargetKind::Lib)
+                    .and_then(|t| t.path)
+            })
+            .map(|path| source_dir.join(path))
+            .unwrap_or_else(|| source_dir.join("src/lib.rs"));
+        let src_dir = lib_root.parent().unwrap_or(source_dir).to_path_buf();
+
+        let mut files = Vec::new();
+        Self::collect_rust_files(&src_dir, &mut files);
+
+        let mut items: Vec<ApiItem> = Vec::new();
+        let mut methods: Vec<(String, FunctionInfo)> = Vec::new();
+        let mut seen = HashSet::new();
+
+        for path in files {
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let analysis =
+                RustParser::parse_rust_file_recovering(&path.to_string_lossy(), &content);
+
+            for function in analysis.functions {
+                if function.visibility == "pub"
+                    && imported.contains(&function.name)
+                    && seen.insert((ApiItemKind::Function, function.name.clone()))
+                {
+                    items.push(ApiItem {
+                        name: function.name.clone(),
+                        kind: ApiItemKind::Function,
+                        signature: Self::function_signature(&function),
+                        summary: Self::summary(&function.documentation),
+                        methods: Vec::new(),
+                    });
+                }
+            }
+
+            for struct_info in analysis.structs {
+                if struct_info.visibility == "pub"
+                    && imported.contains(&struct_info.name)
+                    && seen.insert((ApiItemKind::Struct, struct_info.name.clone()))
+                {
+                    let public_fields = struct_info
+                        .fields
+                        .iter()
+                        .filter(|f| f.visibility == "pub")
+                        .count();
+                    let mut signature = RustParser::inline_signature(&struct_info.signature);
+                    if public_fields > 0 {
+                        signature.push_str(&format!(" ({public_fields} public fields)"));
+                    }
+                    items.push(ApiItem {
+                        name: struct_info.name.clone(),
+                        kind: ApiItemKind::Struct,
+                        signature,
+                        summary: Self::summary(&struct_info.documentation),
+                        methods: Vec::new(),
+                    });
+                }
+            }
+
+            for enum_info in analysis.enums {
+                if enum_info.visibility == "pub"
+                    && imported.contains(&enum_info.name)
+                    && seen.insert((ApiItemKind::Enum, enum_info.name.clone()))
+                {
+                    items.push(ApiItem {
+                        name: enum_info.name.clone(),
+                        kind: ApiItemKind::Enum,
+                        signature: format!(
+                            "{} {{ {} }}",
+                            RustParser::inline_signature(&enum_info.signature),
+                            enum_info
+                                .variants
+                                .iter()
+                                .map(|variant| variant.name.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ),
+                        summary: Self::summary(&enum_info.documentation),
+                        methods: Vec::new(),
+                    });
+                }
+            }
+
+            for impl_info in analysis.implementations {
+                if impl_info.trait_name.is_some() {
+                    continue;
+                }
+                let target = impl_info
+                    .target
+                    .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .find(|part| !part.is_empty())
+                    .unwrap_or_default()
+                    .to_string();
+                if !imported.contains(&target) {
+                    continue;
+                }
+                methods.extend(
+                    impl_info
+                        .methods
+                        .into_iter()
+                        .filter(|m| m.visibility == "pub")
+                        .map(|m| (target.clone(), m)),
+                );
+            }
+        }
+
+        for item in &mut items {
+            if item.kind == ApiItemKind::Function {
+                continue;
+            }
+            // cfg-gated alternatives often define the same method twice
+            let mut signatures = HashSet::new();
+            item.methods = methods
+                .iter()
+                .filter(|(target, _)| *target == item.name)
+                .map(|(_, method)| Self::function_signature(method))
+                .filter(|signature| signatures.insert(signature.clone()))
+                .take(MAX_METHODS_PER_TYPE)
+                .collect();
+        }
+
+        items.truncate(MAX_ITEMS_PER_DEPENDENCY);
+        items
+    }
+
+    fn collect_rust_files(dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        paths.sort();
+
+        for path in paths {
+            if files.len() >= MAX_FILES_PER_DEPENDENCY {
+                return;
+            }
+            if path.is_dir() {
+                Self::collect_rust_files(&path, files);
+            } else if path.extension().is_some_and(|ext| ext == "rs")
+                && fs::metadata(&path).is_ok_and(|m| m.len() <= MAX_FILE_SIZE)
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    fn function_signature(function: &FunctionInfo) -> String {
+        RustParser::inline_signature(&function.signature)
+    }
+
+    fn summary(documentation: &Option<String>) -> Option<String> {
+        documentation
+            .as_deref()
+            .and_then(|doc| doc.lines().map(str::trim).find(|line| !line.is_empty()))
+            .map(str::to_string)
+    }
+}
+
+/// Collects the names used after the first segment of paths and imports,
+/// keyed by that segment (e.g. `serde` → `Serialize`).
+#[derive(Default)]
+struct ImportCollector {
+    names: HashMap<String, BTreeSet<String>>,
+}
+
+impl ImportCollector {
+    fn add_path<'a>(&mut self, segments: impl IntoIterator<Item = &'a str>) {
+        let mut segments = segments.into_iter().filter(|segment| !segment.is_empty());
+        let (Some(first), Some(second)) = (segments.next(), segments.next()) else {
+            return;
+        };
+        self.names.entry(first.to_string()).or_default().extend(
+            std::iter::once(second)
+                .chain(segments)
+                .filter(|segment| *segment != "*")
+                .map(str::to_string),
+        );
+    }
+}
+
+impl<'ast> Visit<'ast> for ImportCollector {
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        let segments: Vec<String> = path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+        self.add_path(segments.iter().map(String::as_str));
+        visit::visit_path(self, path);
+    }
+}
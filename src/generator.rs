@@ -7,11 +7,35 @@
 use anyhow::Result;
 use chrono::Utc;
 use std::fs;
+use std::path::Path;
 
+use crate::call_graph::CallGraph;
+use crate::code_health::{CodeHealth, PanicKind, UnsafeKind};
 use crate::config::Config;
-use crate::parser::RustParser;
-use crate::scanner::{FileType, ScanResult};
+use crate::dependency_api::DependencyApiAnalyzer;
+use crate::import_graph::{ImportGraph, ImportOrigin};
+use crate::lockfile::{PackageSource, ResolvedDependency};
+use crate::manifest::{DependencyInfo, DependencyKind, TargetKind};
+use crate::module_tree::{ModuleNode, ModuleTree};
+use crate::parser::{
+    FieldInfo, FunctionInfo, ModuleInfo, ParseErrorInfo, ParsedSources, RustAnalysis, RustParser,
+    SourceSpan, TestInfo, TraitInfo, UseInfo, VariantInfo, VariantKind,
+};
+use crate::public_api::PublicApi;
+use crate::scanner::{FileInfo, FileType, ProjectMetadata, ScanResult};
+use crate::source_transform::SourceTransformer;
+use crate::test_inventory::TestInventory;
 use crate::token_counter::{ContentPrioritizer, ContentSection};
+use crate::type_graph::{Conversion, TypeGraph};
+
+/// Number of levels of the resolved dependency tree rendered in the metadata section.
+const MAX_DEPENDENCY_TREE_DEPTH: usize = 3;
+
+/// Constant values longer than this are omitted from the analysis sections.
+const MAX_CONST_VALUE_LEN: usize = 40;
+
+/// Number of most complex functions listed in each file's metrics.
+const MAX_HOTSPOTS: usize = 5;
 
 /// Context generator that creates structured markdown from repository scan results.
 ///
@@ -113,17 +137,95 @@ impl ContextGenerator {
         // Project metadata section (high priority)
         sections.push(self.create_metadata_section(&scan_result));
 
+        // Workspace crate sections (high priority)
+        sections.extend(self.create_workspace_sections(&scan_result));
+
+        // Every Rust file is parsed once and shared by the sections below
+        let mut sources = ParsedSources::parse(&scan_result);
+        let module_tree = ModuleTree::from_sources(&scan_result, &sources);
+        sources.assign_module_paths(&module_tree);
+
         // Project structure section (high priority)
-        sections.push(self.create_structure_section(&scan_result));
+        sections.push(self.create_structure_section(&scan_result, &module_tree));
 
         // Markdown documentation sections (high priority)
         sections.extend(self.create_markdown_sections(&scan_result));
 
+        // Code health section (high priority)
+        if self.config.code_health {
+            sections.extend(self.create_code_health_section(&scan_result, &sources));
+        }
+
+        // Public API stubs replace the per-file analysis and source code
+        let public_api_sections = if self.config.public_api {
+            self.create_public_api_sections(&scan_result, &module_tree, &sources)
+        } else {
+            Vec::new()
+        };
+        let public_api_only = !public_api_sections.is_empty();
+        sections.extend(public_api_sections);
+
         // AST analysis sections for Rust files (medium priority)
-        sections.extend(self.create_rust_analysis_sections(&scan_result).await?);
+        if !public_api_only {
+            sections.extend(self.create_rust_analysis_sections(&scan_result, &sources)?);
+        }
+
+        // Type, import and call graph sections (medium priority)
+        if self.config.type_graph {
+            let graph = TypeGraph::from_sources(&module_tree, &sources);
+            sections.extend(self.create_type_graph_section(&graph));
+            if self.config.mermaid {
+                sections.extend(Self::create_diagram_section(
+                    "Type Diagram",
+                    graph.has_edges(),
+                    graph.to_mermaid(),
+                ));
+            }
+        }
+        if self.config.import_graph {
+            let graph = ImportGraph::from_sources(&module_tree, &sources);
+            sections.extend(self.create_import_graph_section(&graph));
+            if self.config.mermaid {
+                sections.extend(Self::create_diagram_section(
+                    "Import Graph Diagram",
+                    graph
+                        .modules
+                        .iter()
+                        .any(|module| !module.depends_on.is_empty()),
+                    graph.to_mermaid(),
+                ));
+            }
+        }
+        // The test inventory maps tests to what they call with the same graph
+        let call_graph = (self.config.call_graph || self.config.test_inventory)
+            .then(|| CallGraph::from_sources(&module_tree, &sources));
+        if let Some(graph) = call_graph.as_ref().filter(|_| self.config.call_graph) {
+            sections.extend(self.create_call_graph_section(graph));
+            if self.config.mermaid {
+                sections.extend(Self::create_diagram_section(
+                    "Call Graph Diagram",
+                    !graph.calls.is_empty(),
+                    graph.to_mermaid(),
+                ));
+            }
+        }
+
+        // Test inventory section (medium priority)
+        if let Some(graph) = call_graph.as_ref().filter(|_| self.config.test_inventory) {
+            let inventory =
+                TestInventory::from_sources(&scan_result, &module_tree, &sources, graph);
+            sections.extend(self.create_test_inventory_section(&inventory));
+        }
+
+        // Dependency API section (medium priority)
+        if self.config.include_deps {
+            sections.extend(self.create_dependency_api_section(&scan_result, &sources));
+        }
 
         // Source code sections (low priority)
-        sections.extend(self.create_source_code_sections(&scan_result));
+        if !public_api_only {
+            sections.extend(self.create_source_code_sections(&scan_result, &sources));
+        }
 
         // Prioritize and truncate content based on token limit
         let final_sections = self
@@ -146,23 +248,11 @@ impl ContextGenerator {
     fn create_metadata_section(&self, scan_result: &ScanResult) -> ContentSection {
         let mut content = String::new();
         content.push_str("# Project Metadata\n\n");
-        content.push_str(&format!("**Name:** {}\n", scan_result.metadata.name));
-
-        if let Some(description) = &scan_result.metadata.description {
-            content.push_str(&format!("**Description:** {description}\n"));
-        }
+        Self::push_package_metadata(&mut content, &scan_result.metadata, true);
 
-        if !scan_result.metadata.dependencies.is_empty() {
-            content.push_str("**Dependencies:**\n");
-            for dep in &scan_result.metadata.dependencies {
-                content.push_str(&format!("- {dep}\n"));
-            }
-        }
-
-        if let Some(rust_version) = &scan_result.metadata.rust_version {
-            content.push_str(&format!("**Version:** {rust_version}\n"));
+        if scan_result.is_workspace() {
+            content.push_str(&format!("**Crates:** {}\n", scan_result.crates.len()));
         }
-
         content.push_str(&format!(
             "**Total files:** {}\n",
             scan_result.project_structure.total_files
@@ -177,41 +267,357 @@ impl ContextGenerator {
             content,
             priority: 10,
             truncated: false,
+            fallback: None,
+        }
+    }
+
+    /// Renders package fields, targets, features and dependencies.
+    ///
+    /// The resolved dependency tree is only rendered when `dependency_tree`
+    /// is set, so the root package's tree appears once even though the
+    /// package is described both in "Project Metadata" and as a crate.
+    fn push_package_metadata(
+        content: &mut String,
+        metadata: &ProjectMetadata,
+        dependency_tree: bool,
+    ) {
+        content.push_str(&format!("**Name:** {}\n", metadata.name));
+
+        if let Some(description) = &metadata.description {
+            content.push_str(&format!("**Description:** {description}\n"));
+        }
+
+        for (label, value) in [
+            ("Version", &metadata.version),
+            ("Edition", &metadata.edition),
+            ("Rust version", &metadata.rust_version),
+            ("License", &metadata.license),
+        ] {
+            if let Some(value) = value {
+                content.push_str(&format!("**{label}:** {value}\n"));
+            }
+        }
+
+        if !metadata.targets.is_empty() {
+            content.push_str("**Targets:**\n");
+            for target in &metadata.targets {
+                let kind = match target.kind {
+                    TargetKind::Lib => "lib",
+                    TargetKind::Bin => "bin",
+                    TargetKind::Test => "test",
+                    TargetKind::Bench => "bench",
+                };
+                content.push_str(&format!("- `{}` ({kind})", target.name));
+                if let Some(path) = &target.path {
+                    content.push_str(&format!(": {path}"));
+                }
+                content.push('\n');
+            }
+        }
+
+        if !metadata.features.is_empty() {
+            content.push_str("**Features:**\n");
+            for (feature, enables) in &metadata.features {
+                if enables.is_empty() {
+                    content.push_str(&format!("- `{feature}`\n"));
+                } else {
+                    content.push_str(&format!("- `{feature}`: {}\n", enables.join(", ")));
+                }
+            }
+        }
+
+        for kind in [
+            DependencyKind::Normal,
+            DependencyKind::Dev,
+            DependencyKind::Build,
+        ] {
+            let deps: Vec<_> = metadata
+                .dependencies
+                .iter()
+                .filter(|dep| dep.kind == kind)
+                .collect();
+            if deps.is_empty() {
+                continue;
+            }
+
+            let label = match kind {
+                DependencyKind::Normal => "Dependencies",
+                DependencyKind::Dev => "Dev dependencies",
+                DependencyKind::Build => "Build dependencies",
+            };
+            content.push_str(&format!("**{label}:**\n"));
+            for dep in deps {
+                content.push_str(&format!("- {}\n", Self::format_dependency(dep)));
+            }
+        }
+
+        if dependency_tree && !metadata.resolved_dependencies.is_empty() {
+            content.push_str("**Resolved dependencies (Cargo.lock):**\n");
+            Self::push_dependency_tree(content, &metadata.resolved_dependencies, 0);
+            content.push_str("_(*) subtree already listed above_\n");
+        }
+    }
+
+    fn push_dependency_tree(content: &mut String, deps: &[ResolvedDependency], depth: usize) {
+        for dep in deps {
+            let indent = "  ".repeat(depth);
+            content.push_str(&format!("{indent}- `{}` {}", dep.name, dep.version));
+
+            // Direct dependencies always show their source; transitive ones
+            // only when it is not the default registry.
+            if depth == 0 || dep.source != PackageSource::CratesIo {
+                content.push_str(&format!(" ({})", dep.source));
+            }
+            match dep.kind {
+                Some(DependencyKind::Normal) | None => {}
+                Some(kind) => content.push_str(&format!(" [{}]", kind.table_name())),
+            }
+            if dep.repeated {
+                content.push_str(" (*)");
+            }
+            content.push('\n');
+
+            if depth + 1 < MAX_DEPENDENCY_TREE_DEPTH {
+                Self::push_dependency_tree(content, &dep.dependencies, depth + 1);
+            } else if !dep.dependencies.is_empty() {
+                content.push_str(&format!("{indent}  - … {} more\n", dep.dependencies.len()));
+            }
         }
     }
 
-    fn create_structure_section(&self, scan_result: &ScanResult) -> ContentSection {
+    /// Creates the workspace overview and one metadata section per member crate.
+    ///
+    /// Only produces sections when the repository contains more than one crate.
+    fn create_workspace_sections(&self, scan_result: &ScanResult) -> Vec<ContentSection> {
+        if !scan_result.is_workspace() {
+            return Vec::new();
+        }
+
+        let mut sections = Vec::new();
+        let crate_names: Vec<&str> = scan_result.crates.iter().map(|c| c.name.as_str()).collect();
+
         let mut content = String::new();
-        content.push_str("# Project Structure\n\n");
-        content.push_str(&scan_result.project_structure.tree);
+        content.push_str("# Workspace Crates\n\n");
+        for crate_info in &scan_result.crates {
+            let root = if crate_info.root.as_os_str().is_empty() {
+                ".".to_string()
+            } else {
+                crate_info.root.display().to_string()
+            };
+            content.push_str(&format!("- **{}** (`{root}`)", crate_info.name));
+            if let Some(description) = &crate_info.metadata.description {
+                content.push_str(&format!(": {}", description.lines().next().unwrap_or("")));
+            }
+            content.push('\n');
+        }
+        content.push('\n');
+
+        content.push_str("## Inter-crate Dependencies\n\n");
+        let mut any_internal = false;
+        for crate_info in &scan_result.crates {
+            let internal: Vec<String> = crate_info
+                .metadata
+                .dependencies
+                .iter()
+                .filter(|dep| crate_names.contains(&dep.package_name()))
+                .map(|dep| match dep.kind {
+                    DependencyKind::Normal => format!("`{}`", dep.package_name()),
+                    kind => format!("`{}` ({})", dep.package_name(), kind.table_name()),
+                })
+                .collect();
+            if !internal.is_empty() {
+                any_internal = true;
+                content.push_str(&format!(
+                    "- `{}` → {}\n",
+                    crate_info.name,
+                    internal.join(", ")
+                ));
+            }
+        }
+        if !any_internal {
+            content.push_str("No crate depends on another workspace member.\n");
+        }
         content.push('\n');
 
+        sections.push(ContentSection {
+            title: "Workspace Crates".to_string(),
+            content,
+            priority: 9,
+            truncated: false,
+            fallback: None,
+        });
+
+        for crate_info in &scan_result.crates {
+            let mut content = String::new();
+            content.push_str(&format!("# Crate: {}\n\n", crate_info.name));
+            // The root package's tree is already part of "Project Metadata"
+            let is_root = crate_info.root.as_os_str().is_empty();
+            Self::push_package_metadata(&mut content, &crate_info.metadata, !is_root);
+            content.push('\n');
+
+            sections.push(ContentSection {
+                title: format!("Crate: {}", crate_info.name),
+                content,
+                priority: 9,
+                truncated: false,
+                fallback: None,
+            });
+        }
+
+        sections
+    }
+
+    /// Returns the scanned files grouped by owning crate, in workspace order.
+    ///
+    /// Files outside every crate come last. Path order is kept within a crate.
+    fn files_by_crate<'a>(&self, scan_result: &'a ScanResult) -> Vec<&'a FileInfo> {
+        let mut files: Vec<&FileInfo> = scan_result.files.iter().collect();
+        files.sort_by_key(|file| {
+            file.crate_name
+                .as_ref()
+                .and_then(|name| scan_result.crates.iter().position(|c| &c.name == name))
+                .unwrap_or(usize::MAX)
+        });
+        files
+    }
+
+    /// Formats a file path for section titles, tagged with its crate in workspaces.
+    fn section_path(&self, scan_result: &ScanResult, file: &FileInfo) -> String {
+        match &file.crate_name {
+            Some(crate_name) if scan_result.is_workspace() => {
+                format!("[{crate_name}] {}", file.relative_path.display())
+            }
+            _ => file.relative_path.display().to_string(),
+        }
+    }
+
+    fn format_dependency(dep: &DependencyInfo) -> String {
+        let mut line = format!("`{}`", dep.name);
+
+        if let Some(package) = &dep.package {
+            line.push_str(&format!(" (package `{package}`)"));
+        }
+        if let Some(version) = &dep.version_req {
+            line.push_str(&format!(" {version}"));
+        }
+        if let Some(path) = &dep.path {
+            line.push_str(&format!(" path: {path}"));
+        }
+        if let Some(git) = &dep.git {
+            line.push_str(&format!(" git: {git}"));
+        }
+        if dep.workspace {
+            line.push_str(" (workspace)");
+        }
+
+        let mut notes = Vec::new();
+        if !dep.features.is_empty() {
+            notes.push(format!("features: {}", dep.features.join(", ")));
+        }
+        if !dep.default_features {
+            notes.push("no default features".to_string());
+        }
+        if dep.optional {
+            notes.push("optional".to_string());
+        }
+        if let Some(target) = &dep.target {
+            notes.push(format!("target: {target}"));
+        }
+        if !notes.is_empty() {
+            line.push_str(&format!(" [{}]", notes.join("; ")));
+        }
+
+        line
+    }
+
+    fn create_structure_section(
+        &self,
+        scan_result: &ScanResult,
+        module_tree: &ModuleTree,
+    ) -> ContentSection {
+        let mut content = String::new();
+        content.push_str("# Project Structure\n\n");
+
+        if module_tree.roots.is_empty() {
+            content.push_str(&scan_result.project_structure.tree);
+            content.push('\n');
+        } else {
+            content.push_str("## Module Tree\n\n");
+            for root in &module_tree.roots {
+                let package = match &root.package {
+                    Some(package) if scan_result.is_workspace() => format!("[{package}] "),
+                    _ => String::new(),
+                };
+                content.push_str(&format!("### {package}{} `{}`\n\n", root.kind, root.target));
+                Self::push_module_node(&mut content, &root.module, 0);
+                content.push('\n');
+            }
+
+            if !module_tree.orphans.is_empty() {
+                content.push_str("## Unreachable Rust Files\n\n");
+                content.push_str("Not reached from any crate root through `mod` declarations:\n\n");
+                for orphan in &module_tree.orphans {
+                    content.push_str(&format!("- {}\n", orphan.display()));
+                }
+                content.push('\n');
+            }
+
+            let other_files: Vec<&FileInfo> = scan_result
+                .files
+                .iter()
+                .filter(|file| !matches!(file.file_type, FileType::Rust))
+                .collect();
+            if !other_files.is_empty() {
+                content.push_str("## Other Files\n\n");
+                for file in other_files {
+                    content.push_str(&format!("- {}\n", file.relative_path.display()));
+                }
+                content.push('\n');
+            }
+        }
+
         ContentSection {
             title: "Project Structure".to_string(),
             content,
             priority: 9,
             truncated: false,
+            fallback: None,
+        }
+    }
+
+    fn push_module_node(content: &mut String, module: &ModuleNode, depth: usize) {
+        let location = match (&module.file, module.is_inline) {
+            (Some(file), _) => file.display().to_string(),
+            (None, true) => "inline".to_string(),
+            (None, false) => "file not found".to_string(),
+        };
+        content.push_str(&format!(
+            "{}- `{}` — {location}\n",
+            "  ".repeat(depth),
+            module.name
+        ));
+        for child in &module.children {
+            Self::push_module_node(content, child, depth + 1);
         }
     }
 
     fn create_markdown_sections(&self, scan_result: &ScanResult) -> Vec<ContentSection> {
         let mut sections = Vec::new();
 
-        for file in &scan_result.files {
+        for file in self.files_by_crate(scan_result) {
             if matches!(file.file_type, FileType::Markdown) {
+                let display_path = self.section_path(scan_result, file);
                 let mut content = String::new();
-                content.push_str(&format!(
-                    "# Documentation: {}\n\n",
-                    file.relative_path.display()
-                ));
+                content.push_str(&format!("# Documentation: {display_path}\n\n"));
                 content.push_str(&file.content);
                 content.push('\n');
 
                 sections.push(ContentSection {
-                    title: format!("Documentation: {}", file.relative_path.display()),
+                    title: format!("Documentation: {display_path}"),
                     content,
                     priority: 8,
                     truncated: false,
+                    fallback: None,
                 });
             }
         }
@@ -219,111 +625,1090 @@ impl ContextGenerator {
         sections
     }
 
-    async fn create_rust_analysis_sections(
+    fn create_rust_analysis_sections(
         &self,
         scan_result: &ScanResult,
+        sources: &ParsedSources,
     ) -> Result<Vec<ContentSection>> {
         let mut sections = Vec::new();
 
-        for file in &scan_result.files {
+        for file in self.files_by_crate(scan_result) {
             if matches!(file.file_type, FileType::Rust) {
-                match RustParser::parse_rust_file(&file.path.to_string_lossy(), &file.content) {
-                    Ok(analysis) => {
-                        let mut content = String::new();
+                let display_path = self.section_path(scan_result, file);
+                let Some(analysis) = sources.get(&file.relative_path) else {
+                    continue;
+                };
+
+                let source = file.relative_path.to_string_lossy().replace('\\', "/");
+                let mut content = String::new();
+                content.push_str(&format!("# Rust Analysis: {display_path}\n\n"));
+                match &analysis.module_path {
+                    Some(module_path) => {
+                        content.push_str(&format!("**Module:** `{module_path}`\n\n"))
+                    }
+                    None => content.push_str("**Module:** not reachable from any crate root\n\n"),
+                }
+
+                if !analysis.parse_errors.is_empty() {
+                    eprintln!(
+                        "Warning: Partially parsed {}: {} error(s), analysis covers recovered items only",
+                        file.relative_path.display(),
+                        analysis.parse_errors.len()
+                    );
+                    Self::push_parse_errors(&mut content, &source, &analysis.parse_errors);
+                }
+
+                if !analysis.modules.is_empty() {
+                    content.push_str("## Modules\n");
+                    Self::push_module_tree(&mut content, &source, &analysis.modules, 0);
+                    content.push('\n');
+                }
+
+                if !analysis.functions.is_empty() {
+                    content.push_str("## Functions\n");
+                    for function in &analysis.functions {
                         content.push_str(&format!(
-                            "# Rust Analysis: {}\n\n",
-                            file.relative_path.display()
+                            "- **{}** ({}): `{}`{}\n",
+                            function.path,
+                            Self::location(&source, &function.span),
+                            RustParser::inline_signature(&function.signature),
+                            Self::annotations(&[], &function.attributes, &function.cfg)
                         ));
+                    }
+                    content.push('\n');
+                }
 
-                        if !analysis.modules.is_empty() {
-                            content.push_str("## Modules\n");
-                            for module in &analysis.modules {
-                                content.push_str(&format!(
-                                    "- **{}**: {}\n",
-                                    module.name, module.visibility
-                                ));
-                            }
-                            content.push('\n');
-                        }
+                if !analysis.structs.is_empty() {
+                    content.push_str("## Structs\n");
+                    for struct_info in &analysis.structs {
+                        content.push_str(&format!(
+                            "- **{}** ({}): `{}` — {} fields{}\n",
+                            struct_info.path,
+                            Self::location(&source, &struct_info.span),
+                            RustParser::inline_signature(&struct_info.signature),
+                            struct_info.fields.len(),
+                            Self::annotations(
+                                &struct_info.derives,
+                                &struct_info.attributes,
+                                &struct_info.cfg
+                            )
+                        ));
+                        self.push_fields(&mut content, struct_info.kind, &struct_info.fields);
+                    }
+                    content.push('\n');
+                }
 
-                        if !analysis.functions.is_empty() {
-                            content.push_str("## Functions\n");
-                            for function in &analysis.functions {
-                                let params = function.parameters.join(", ");
-                                let return_type = function.return_type.as_deref().unwrap_or("()");
-                                content.push_str(&format!(
-                                    "- **{}**({}) -> {} ({})\n",
-                                    function.name, params, return_type, function.visibility
-                                ));
-                            }
-                            content.push('\n');
+                if !analysis.enums.is_empty() {
+                    content.push_str("## Enums\n");
+                    for enum_info in &analysis.enums {
+                        content.push_str(&format!(
+                            "- **{}** ({}): `{}` — {} variants{}\n",
+                            enum_info.path,
+                            Self::location(&source, &enum_info.span),
+                            RustParser::inline_signature(&enum_info.signature),
+                            enum_info.variants.len(),
+                            Self::annotations(
+                                &enum_info.derives,
+                                &enum_info.attributes,
+                                &enum_info.cfg
+                            )
+                        ));
+                        for variant in &enum_info.variants {
+                            Self::push_variant(&mut content, variant);
                         }
+                    }
+                    content.push('\n');
+                }
 
-                        if !analysis.structs.is_empty() {
-                            content.push_str("## Structs\n");
-                            for struct_info in &analysis.structs {
-                                content.push_str(&format!(
-                                    "- **{}**: {} fields ({})\n",
-                                    struct_info.name,
-                                    struct_info.fields.len(),
-                                    struct_info.visibility
-                                ));
-                            }
-                            content.push('\n');
-                        }
+                if !analysis.implementations.is_empty() {
+                    content.push_str("## Implementations\n");
+                    for impl_info in &analysis.implementations {
+                        let scope = if impl_info.module_path.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" in `{}`", impl_info.module_path)
+                        };
+                        content.push_str(&format!(
+                            "- `{}` ({}){}: {} methods{}\n",
+                            RustParser::inline_signature(&impl_info.signature),
+                            Self::location(&source, &impl_info.span),
+                            scope,
+                            impl_info.methods.len(),
+                            Self::annotations(&[], &impl_info.attributes, &impl_info.cfg)
+                        ));
+                    }
+                    content.push('\n');
+                }
 
-                        if !analysis.enums.is_empty() {
-                            content.push_str("## Enums\n");
-                            for enum_info in &analysis.enums {
-                                content.push_str(&format!(
-                                    "- **{}**: {} variants ({})\n",
-                                    enum_info.name,
-                                    enum_info.variants.len(),
-                                    enum_info.visibility
-                                ));
-                            }
-                            content.push('\n');
-                        }
+                if !analysis.traits.is_empty() {
+                    content.push_str("## Traits\n");
+                    for trait_info in &analysis.traits {
+                        Self::push_trait(&mut content, &source, trait_info);
+                    }
+                    content.push('\n');
+                }
 
-                        if !analysis.implementations.is_empty() {
-                            content.push_str("## Implementations\n");
-                            for impl_info in &analysis.implementations {
-                                content.push_str(&format!(
-                                    "- **impl {}**: {} methods\n",
-                                    impl_info.target,
-                                    impl_info.methods.len()
-                                ));
-                            }
-                            content.push('\n');
-                        }
+                if !analysis.type_aliases.is_empty() {
+                    content.push_str("## Type Aliases\n");
+                    for alias in &analysis.type_aliases {
+                        content.push_str(&format!(
+                            "- **{}** ({}) = `{}` ({}){}\n",
+                            alias.path,
+                            Self::location(&source, &alias.span),
+                            alias.target,
+                            alias.visibility,
+                            Self::annotations(&[], &alias.attributes, &alias.cfg)
+                        ));
+                    }
+                    content.push('\n');
+                }
 
-                        sections.push(ContentSection {
-                            title: format!("Rust Analysis: {}", file.relative_path.display()),
-                            content,
-                            priority: 6,
-                            truncated: false,
-                        });
+                if !analysis.constants.is_empty() || !analysis.statics.is_empty() {
+                    content.push_str("## Constants and Statics\n");
+                    for constant in &analysis.constants {
+                        let value = if constant.value.len() <= MAX_CONST_VALUE_LEN {
+                            format!(" = {}", constant.value)
+                        } else {
+                            String::new()
+                        };
+                        content.push_str(&format!(
+                            "- **const {}** ({}): `{}{}` ({}){}\n",
+                            constant.path,
+                            Self::location(&source, &constant.span),
+                            constant.const_type,
+                            value,
+                            constant.visibility,
+                            Self::annotations(&[], &constant.attributes, &constant.cfg)
+                        ));
                     }
-                    Err(e) => {
-                        eprintln!(
-                            "Warning: Failed to parse {}: {}",
-                            file.relative_path.display(),
-                            e
-                        );
+                    for static_info in &analysis.statics {
+                        let keyword = if static_info.is_mutable {
+                            "static mut"
+                        } else {
+                            "static"
+                        };
+                        content.push_str(&format!(
+                            "- **{} {}** ({}): `{}` ({}){}\n",
+                            keyword,
+                            static_info.path,
+                            Self::location(&source, &static_info.span),
+                            static_info.static_type,
+                            static_info.visibility,
+                            Self::annotations(&[], &static_info.attributes, &static_info.cfg)
+                        ));
                     }
+                    content.push('\n');
                 }
+
+                if !analysis.macros.is_empty() {
+                    content.push_str("## Macros\n");
+                    for macro_info in &analysis.macros {
+                        content.push_str(&format!(
+                            "- **{}!** ({}): {} rules{}{}\n",
+                            macro_info.path,
+                            Self::location(&source, &macro_info.span),
+                            macro_info.rules,
+                            if macro_info.is_exported {
+                                " (#[macro_export])"
+                            } else {
+                                ""
+                            },
+                            Self::annotations(&[], &macro_info.attributes, &macro_info.cfg)
+                        ));
+                    }
+                    content.push('\n');
+                }
+
+                Self::push_imports(&mut content, &analysis.imports);
+                Self::push_metrics(&mut content, &source, analysis);
+
+                sections.push(ContentSection {
+                    title: format!("Rust Analysis: {display_path}"),
+                    content,
+                    priority: 6,
+                    truncated: false,
+                    fallback: None,
+                });
             }
         }
 
         Ok(sections)
     }
 
-    fn create_source_code_sections(&self, scan_result: &ScanResult) -> Vec<ContentSection> {
+    /// Lists the file's metric totals and its most complex functions.
+    fn push_metrics(content: &mut String, source: &str, analysis: &RustAnalysis) {
+        let metrics = &analysis.metrics;
+        if metrics.functions == 0 {
+            return;
+        }
+
+        content.push_str("## Metrics\n");
+        content.push_str(&format!(
+            "- **Totals:** {} lines, {} functions, complexity {} (max {}), nesting up to {}, {} match arms\n",
+            metrics.lines,
+            metrics.functions,
+            metrics.total_complexity,
+            metrics.max_complexity,
+            metrics.max_nesting,
+            metrics.match_arms
+        ));
+        if metrics.async_functions + metrics.unsafe_functions + metrics.unsafe_blocks > 0 {
+            content.push_str(&format!(
+                "- **Async/unsafe:** {} async functions, {} `.await`, {} unsafe functions, {} `unsafe` blocks\n",
+                metrics.async_functions,
+                metrics.await_points,
+                metrics.unsafe_functions,
+                metrics.unsafe_blocks
+            ));
+        }
+
+        let mut hotspots: Vec<&FunctionInfo> = analysis
+            .functions
+            .iter()
+            .chain(analysis.implementations.iter().flat_map(|imp| &imp.methods))
+            .chain(
+                analysis
+                    .traits
+                    .iter()
+                    .flat_map(|trait_info| &trait_info.methods)
+                    .map(|method| &method.function),
+            )
+            .filter(|function| function.metrics.cyclomatic_complexity > 1)
+            .collect();
+        hotspots.sort_by_key(|function| std::cmp::Reverse(function.metrics.cyclomatic_complexity));
+        if !hotspots.is_empty() {
+            content.push_str("- **Most complex:**\n");
+        }
+        for function in hotspots.into_iter().take(MAX_HOTSPOTS) {
+            let metrics = &function.metrics;
+            content.push_str(&format!(
+                "  - **{}** ({}): complexity {}, nesting {}, {} lines, {} parameters, {} match arms\n",
+                function.path,
+                Self::location(source, &function.span),
+                metrics.cyclomatic_complexity,
+                metrics.max_nesting,
+                metrics.lines,
+                metrics.parameters,
+                metrics.match_arms
+            ));
+        }
+        content.push('\n');
+    }
+
+    fn push_module_tree(content: &mut String, source: &str, modules: &[ModuleInfo], depth: usize) {
+        for module in modules {
+            let body = if module.is_inline {
+                format!("{} items", module.items_count)
+            } else {
+                "separate file".to_string()
+            };
+            content.push_str(&format!(
+                "{}- **{}** ({}): {} ({body}){}\n",
+                "  ".repeat(depth),
+                module.name,
+                Self::location(source, &module.span),
+                module.visibility,
+                Self::annotations(&[], &module.attributes, &module.cfg)
+            ));
+            Self::push_module_tree(content, source, &module.submodules, depth + 1);
+        }
+    }
+
+    /// Formats an item's location as a `path:line` reference.
+    fn location(source: &str, span: &SourceSpan) -> String {
+        format!("`{source}:{}`", span.start_line)
+    }
+
+    /// Formats derives, notable attributes and the cfg gate of an item as a
+    /// suffix for its list entry, e.g. `` — `#[derive(Debug)]` `cfg(test)` ``.
+    fn annotations(derives: &[String], attributes: &[String], cfg: &Option<String>) -> String {
+        let mut parts = Vec::new();
+        if !derives.is_empty() {
+            parts.push(format!("`#[derive({})]`", derives.join(", ")));
+        }
+        parts.extend(attributes.iter().map(|attribute| format!("`{attribute}`")));
+        if let Some(cfg) = cfg {
+            parts.push(format!("`cfg({cfg})`"));
+        }
+
+        if parts.is_empty() {
+            String::new()
+        } else {
+            format!(" — {}", parts.join(" "))
+        }
+    }
+
+    /// Lists struct fields with their visibility, type and doc summary,
+    /// leaving out private ones when `hide_private_fields` is set.
+    fn push_fields(&self, content: &mut String, kind: VariantKind, fields: &[FieldInfo]) {
+        let mut hidden = 0;
+        for (i, field) in fields.iter().enumerate() {
+            // Tuple fields are shown by index, the way they are accessed
+            let name = if kind == VariantKind::Tuple {
+                i.to_string()
+            } else {
+                field.name.clone()
+            };
+            if field.visibility == "private" {
+                if self.config.hide_private_fields {
+                    hidden += 1;
+                    continue;
+                }
+                content.push_str(&format!("  - `{name}: {}`", field.field_type));
+            } else {
+                content.push_str(&format!(
+                    "  - `{} {name}: {}`",
+                    field.visibility, field.field_type
+                ));
+            }
+            content.push_str(&Self::doc_summary(&field.documentation));
+            content.push('\n');
+        }
+
+        if hidden > 0 {
+            content.push_str(&format!("  - *{hidden} private fields hidden*\n"));
+        }
+    }
+
+    /// First non-empty line of a doc comment, as a ` — ` suffix.
+    fn doc_summary(documentation: &Option<String>) -> String {
+        documentation
+            .as_deref()
+            .and_then(|doc| doc.lines().map(str::trim).find(|line| !line.is_empty()))
+            .map(|line| format!(" — {line}"))
+            .unwrap_or_default()
+    }
+
+    fn push_variant(content: &mut String, variant: &VariantInfo) {
+        let mut shape = String::new();
+        for attribute in &variant.attributes {
+            shape.push_str(attribute);
+            shape.push(' ');
+        }
+        shape.push_str(&variant.name);
+
+        match variant.kind {
+            VariantKind::Unit => {}
+            VariantKind::Tuple => {
+                let types: Vec<&str> = variant
+                    .fields
+                    .iter()
+                    .map(|field| field.field_type.as_str())
+                    .collect();
+                shape.push_str(&format!("({})", types.join(", ")));
+            }
+            VariantKind::Struct => {
+                let fields: Vec<String> = variant
+                    .fields
+                    .iter()
+                    .map(|field| format!("{}: {}", field.name, field.field_type))
+                    .collect();
+                shape.push_str(&format!(" {{ {} }}", fields.join(", ")));
+            }
+        }
+
+        if let Some(discriminant) = &variant.discriminant {
+            shape.push_str(&format!(" = {discriminant}"));
+        }
+
+        content.push_str(&format!(
+            "  - `{shape}`{}\n",
+            Self::doc_summary(&variant.documentation)
+        ));
+    }
+
+    fn push_trait(content: &mut String, source: &str, trait_info: &TraitInfo) {
+        let unsafety = if trait_info.is_unsafe { "unsafe " } else { "" };
+        let supertraits = if trait_info.supertraits.is_empty() {
+            String::new()
+        } else {
+            format!(": {}", trait_info.supertraits.join(" + "))
+        };
+        content.push_str(&format!(
+            "- **{}trait {}** ({}){} ({}){}\n",
+            unsafety,
+            trait_info.path,
+            Self::location(source, &trait_info.span),
+            supertraits,
+            trait_info.visibility,
+            Self::annotations(&[], &trait_info.attributes, &trait_info.cfg)
+        ));
+
+        for ty in &trait_info.associated_types {
+            let bounds = if ty.bounds.is_empty() {
+                String::new()
+            } else {
+                format!(": {}", ty.bounds.join(" + "))
+            };
+            let default = ty
+                .default
+                .as_ref()
+                .map(|default| format!(" = {default}"))
+                .unwrap_or_default();
+            content.push_str(&format!("  - `type {}{bounds}{default}`\n", ty.name));
+        }
+        for constant in &trait_info.associated_consts {
+            let default = if constant.has_default {
+                " (default)"
+            } else {
+                ""
+            };
+            content.push_str(&format!(
+                "  - `const {}: {}`{default}\n",
+                constant.name, constant.const_type
+            ));
+        }
+        for method in &trait_info.methods {
+            let default = if method.has_default { " (default)" } else { "" };
+            // The trait's own cfg applies to every method; only show attributes
+            content.push_str(&format!(
+                "  - `{}`{default}{}\n",
+                RustParser::inline_signature(&method.function.signature),
+                Self::annotations(&[], &method.function.attributes, &None)
+            ));
+        }
+    }
+
+    /// Lists the regions of a file that failed to parse, with the declarations
+    /// they contain, so items missing from the analysis are visible.
+    fn push_parse_errors(content: &mut String, source: &str, errors: &[ParseErrorInfo]) {
+        content.push_str("## Parse Errors\n");
+        content.push_str(
+            "⚠️ The file could not be parsed as a whole; only recovered items are listed below.\n\n",
+        );
+        for error in errors {
+            content.push_str(&format!(
+                "- `{source}:{}:{}`: {}\n",
+                error.line, error.column, error.message
+            ));
+            if !error.skipped_items.is_empty() {
+                content.push_str(&format!(
+                    "  - Not analyzed: {}\n",
+                    error.skipped_items.join(", ")
+                ));
+            }
+        }
+        content.push('\n');
+    }
+
+    /// Lists re-exports individually and folds plain imports into one line.
+    fn push_imports(content: &mut String, imports: &[UseInfo]) {
+        let format_import = |import: &UseInfo| {
+            let path = match &import.alias {
+                Some(alias) => format!("`{} as {alias}`", import.path),
+                None => format!("`{}`", import.path),
+            };
+            if import.module_path.is_empty() {
+                path
+            } else {
+                format!("{path} in `{}`", import.module_path)
+            }
+        };
+
+        let (reexports, private): (Vec<&UseInfo>, Vec<&UseInfo>) = imports
+            .iter()
+            .partition(|import| import.visibility != "private");
+
+        if !reexports.is_empty() {
+            content.push_str("## Re-exports\n");
+            for import in reexports {
+                content.push_str(&format!(
+                    "- {} ({})\n",
+                    format_import(import),
+                    import.visibility
+                ));
+            }
+            content.push('\n');
+        }
+
+        if !private.is_empty() {
+            content.push_str("## Imports\n");
+            let paths: Vec<String> = private.into_iter().map(format_import).collect();
+            content.push_str(&paths.join(", "));
+            content.push_str("\n\n");
+        }
+    }
+
+    fn create_public_api_sections(
+        &self,
+        scan_result: &ScanResult,
+        module_tree: &ModuleTree,
+        sources: &ParsedSources,
+    ) -> Vec<ContentSection> {
+        let public_api = PublicApi::from_sources(module_tree, sources);
+        if public_api.crates.is_empty() {
+            eprintln!("Warning: No library crate found, falling back to the full analysis");
+        }
+
+        public_api
+            .crates
+            .into_iter()
+            .map(|api| {
+                let name = match &api.package {
+                    Some(package) if scan_result.is_workspace() => {
+                        format!("[{package}] {}", api.target)
+                    }
+                    _ => api.target.clone(),
+                };
+                ContentSection {
+                    title: format!("Public API: {name}"),
+                    content: format!("# Public API: {name}\n\n```rust\n{}```\n", api.stub),
+                    priority: 6,
+                    truncated: false,
+                    fallback: None,
+                }
+            })
+            .collect()
+    }
+
+    fn create_type_graph_section(&self, graph: &TypeGraph) -> Option<ContentSection> {
+        let mut content = String::new();
+        content.push_str("# Type Graph\n\n");
+        content.push_str(
+            "Traits implemented by, types contained in and conversions supported by \
+             crate-local types, matched by name.\n",
+        );
+
+        let mut current_target = None;
+        let mut listed = false;
+        for node in &graph.types {
+            let summary = |items: &[String]| {
+                let items: Vec<String> = items.iter().map(|item| format!("`{item}`")).collect();
+                items.join(", ")
+            };
+            let conversions = |conversions: &[Conversion]| {
+                let conversions: Vec<String> = conversions
+                    .iter()
+                    .map(|conversion| {
+                        let fallible = if conversion.fallible {
+                            " (fallible)"
+                        } else {
+                            ""
+                        };
+                        format!("`{}`{fallible}", conversion.other)
+                    })
+                    .collect();
+                conversions.join(", ")
+            };
+
+            let mut lines = Vec::new();
+            if !node.derives.is_empty() {
+                lines.push(format!("derives: {}", summary(&node.derives)));
+            }
+            if !node.implements.is_empty() {
+                lines.push(format!("implements: {}", summary(&node.implements)));
+            }
+            let contains: Vec<String> = node
+                .contains
+                .iter()
+                .map(|&contained| graph.display_path(contained, Some(&node.target)))
+                .collect();
+            if !contains.is_empty() {
+                lines.push(format!("contains: {}", summary(&contains)));
+            }
+            if !node.converts_from.is_empty() {
+                lines.push(format!(
+                    "converts from: {}",
+                    conversions(&node.converts_from)
+                ));
+            }
+            if !node.converts_into.is_empty() {
+                lines.push(format!(
+                    "converts into: {}",
+                    conversions(&node.converts_into)
+                ));
+            }
+            let implementors: Vec<String> = node
+                .implementors
+                .iter()
+                .map(|&implementor| graph.display_path(implementor, Some(&node.target)))
+                .collect();
+            if !implementors.is_empty() {
+                lines.push(format!("implemented by: {}", summary(&implementors)));
+            }
+            if lines.is_empty() {
+                continue;
+            }
+
+            if current_target != Some(&node.target) {
+                current_target = Some(&node.target);
+                content.push_str(&format!("\n## {} ({})\n", node.target, node.kind));
+            }
+            let source = node.file.to_string_lossy().replace('\\', "/");
+            let error = if node.is_error { ", error type" } else { "" };
+            content.push_str(&format!(
+                "- **{}** ({}{error}, `{source}:{}`)\n",
+                node.short_path(),
+                node.category.label(),
+                node.line
+            ));
+            for line in lines {
+                content.push_str(&format!("  - {line}\n"));
+            }
+            listed = true;
+        }
+        if !listed {
+            return None;
+        }
+
+        Some(ContentSection {
+            title: "Type Graph".to_string(),
+            content,
+            priority: 6,
+            truncated: false,
+            fallback: None,
+        })
+    }
+
+    fn create_import_graph_section(&self, graph: &ImportGraph) -> Option<ContentSection> {
+        if graph.modules.iter().all(|module| module.imports.is_empty()) {
+            return None;
+        }
+
+        let mut content = String::new();
+        content.push_str("# Import Graph\n\n");
+        content.push_str(
+            "Modules and crates each module imports from through `use`, by layer: a module \
+             only imports from lower layers, except within cycles.\n",
+        );
+
+        // Modules are grouped by crate root; order each group by layer
+        let mut order: Vec<usize> = (0..graph.modules.len()).collect();
+        order.sort_by_key(|&index| {
+            let module = &graph.modules[index];
+            let group = graph
+                .modules
+                .iter()
+                .position(|other| other.target == module.target);
+            (group, module.layer)
+        });
+
+        let mut current_target = None;
+        for index in order {
+            let module = &graph.modules[index];
+            if module.imports.is_empty() {
+                continue;
+            }
+            if current_target != Some(&module.target) {
+                current_target = Some(&module.target);
+                content.push_str(&format!("\n## {} ({})\n", module.target, module.kind));
+            }
+
+            let source = module.file.to_string_lossy().replace('\\', "/");
+            content.push_str(&format!(
+                "- **{}** (`{source}`, layer {})\n",
+                module.short_path(),
+                module.layer
+            ));
+            let dependencies: Vec<String> = module
+                .depends_on
+                .iter()
+                .map(|&dependency| {
+                    format!("`{}`", graph.display_path(dependency, Some(&module.target)))
+                })
+                .collect();
+            if !dependencies.is_empty() {
+                content.push_str(&format!("  - modules: {}\n", dependencies.join(", ")));
+            }
+            let mut std_modules: Vec<String> = module
+                .imports
+                .iter()
+                .filter(|import| import.origin == ImportOrigin::Std)
+                .map(|import| {
+                    let mut segments = import.path.split("::").take(2);
+                    let module = segments.next().unwrap_or_default();
+                    match segments.next() {
+                        Some(item) => format!("`{module}::{item}`"),
+                        None => format!("`{module}`"),
+                    }
+                })
+                .collect();
+            std_modules.sort();
+            std_modules.dedup();
+            if !std_modules.is_empty() {
+                content.push_str(&format!("  - std: {}\n", std_modules.join(", ")));
+            }
+            if !module.external_crates.is_empty() {
+                let crates: Vec<String> = module
+                    .external_crates
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect();
+                content.push_str(&format!("  - external: {}\n", crates.join(", ")));
+            }
+        }
+
+        if !graph.cycles.is_empty() {
+            content.push_str("\n## Cycles\n\n");
+            for cycle in &graph.cycles {
+                let modules: Vec<String> = cycle
+                    .iter()
+                    .map(|&index| format!("`{}`", graph.display_path(index, None)))
+                    .collect();
+                content.push_str(&format!("- {} depend on each other\n", modules.join(", ")));
+            }
+        }
+
+        Some(ContentSection {
+            title: "Import Graph".to_string(),
+            content,
+            priority: 6,
+            truncated: false,
+            fallback: None,
+        })
+    }
+
+    fn create_diagram_section(
+        title: &str,
+        has_edges: bool,
+        mermaid: String,
+    ) -> Option<ContentSection> {
+        if !has_edges {
+            return None;
+        }
+
+        Some(ContentSection {
+            title: title.to_string(),
+            content: format!("# {title}\n\n```mermaid\n{mermaid}```\n"),
+            priority: 5,
+            truncated: false,
+            fallback: None,
+        })
+    }
+
+    fn create_call_graph_section(&self, graph: &CallGraph) -> Option<ContentSection> {
+        if graph.calls.is_empty() {
+            return None;
+        }
+
+        let mut content = String::new();
+        content.push_str("# Call Graph\n\n");
+        content.push_str(
+            "Best-effort calls between crate-local functions, resolved from paths, imports, \
+             `self` and the types of parameters, locals and fields.\n",
+        );
+
+        let mut current_target = None;
+        for (index, function) in graph.functions.iter().enumerate() {
+            let callees: Vec<String> = graph
+                .callees(index)
+                .map(|callee| format!("`{}`", graph.display_path(callee, Some(&function.target))))
+                .collect();
+            let callers: Vec<String> = graph
+                .callers(index)
+                .map(|caller| format!("`{}`", graph.display_path(caller, Some(&function.target))))
+                .collect();
+            if callees.is_empty() && callers.is_empty() {
+                continue;
+            }
+
+            if current_target != Some(&function.target) {
+                current_target = Some(&function.target);
+                content.push_str(&format!("\n## {} ({})\n", function.target, function.kind));
+            }
+            let source = function.file.to_string_lossy().replace('\\', "/");
+            content.push_str(&format!(
+                "- **{}** (`{source}:{}`)\n",
+                function.short_path(),
+                function.line
+            ));
+            if !callees.is_empty() {
+                content.push_str(&format!("  - calls: {}\n", callees.join(", ")));
+            }
+            if !callers.is_empty() {
+                content.push_str(&format!("  - used by: {}\n", callers.join(", ")));
+            }
+        }
+
+        Some(ContentSection {
+            title: "Call Graph".to_string(),
+            content,
+            priority: 6,
+            truncated: false,
+            fallback: None,
+        })
+    }
+
+    fn create_test_inventory_section(&self, inventory: &TestInventory) -> Option<ContentSection> {
+        if inventory.tests.is_empty()
+            && inventory.doc_tests.is_empty()
+            && inventory.test_files.is_empty()
+        {
+            return None;
+        }
+
+        let count = |flag: fn(&TestInfo) -> bool| {
+            inventory
+                .tests
+                .iter()
+                .filter(|test| flag(&test.test))
+                .count()
+        };
+        let mut content = String::new();
+        content.push_str("# Test Inventory\n\n");
+        content.push_str(&format!(
+            "{} test functions ({} ignored, {} should panic), {} doc tests, {} test and bench crates. \
+             Each test lists the crate-local functions it calls, directly or through helpers in its file, or is named after.\n",
+            inventory.tests.len(),
+            count(|test| test.ignored),
+            count(|test| test.should_panic),
+            inventory.doc_tests.len(),
+            inventory.test_files.len()
+        ));
+
+        let mut current_target = None;
+        for test in &inventory.tests {
+            if current_target != Some(&test.target) {
+                current_target = Some(&test.target);
+                content.push_str(&format!("\n## {} ({})\n", test.target, test.kind));
+            }
+
+            let mut flags = vec![format!("`#[{}]`", test.test.harness)];
+            if test.test.should_panic {
+                flags.push("should panic".to_string());
+            }
+            if test.test.ignored {
+                flags.push("ignored".to_string());
+            }
+            let source = test.file.to_string_lossy().replace('\\', "/");
+            content.push_str(&format!(
+                "- **{}** (`{source}:{}`) — {}\n",
+                test.short_path(),
+                test.line,
+                flags.join(", ")
+            ));
+            if !test.exercises.is_empty() {
+                let exercises: Vec<String> = test
+                    .exercises
+                    .iter()
+                    .map(|function| format!("`{function}`"))
+                    .collect();
+                content.push_str(&format!("  - exercises: {}\n", exercises.join(", ")));
+            }
+        }
+
+        if !inventory.doc_tests.is_empty() {
+            content.push_str("\n## Doc Tests\n");
+
+            // Consecutive blocks of one item are counted together
+            let mut files: Vec<(&Path, Vec<(&str, usize)>)> = Vec::new();
+            for doc_test in &inventory.doc_tests {
+                let item = doc_test
+                    .item
+                    .strip_prefix("crate::")
+                    .unwrap_or(&doc_test.item);
+                match files.last_mut() {
+                    Some((file, items)) if *file == doc_test.file => match items.last_mut() {
+                        Some((last, blocks)) if *last == item => *blocks += 1,
+                        _ => items.push((item, 1)),
+                    },
+                    _ => files.push((&doc_test.file, vec![(item, 1)])),
+                }
+            }
+            for (file, items) in files {
+                let items: Vec<String> = items
+                    .into_iter()
+                    .map(|(item, blocks)| match blocks {
+                        1 => format!("`{item}`"),
+                        _ => format!("`{item}` ({blocks})"),
+                    })
+                    .collect();
+                let source = file.to_string_lossy().replace('\\', "/");
+                content.push_str(&format!("- `{source}`: {}\n", items.join(", ")));
+            }
+        }
+
+        if !inventory.test_files.is_empty() {
+            content.push_str("\n## Test and Bench Crates\n");
+            for test_file in &inventory.test_files {
+                let source = test_file.file.to_string_lossy().replace('\\', "/");
+                let tests = if test_file.harness {
+                    format!("{} test functions", test_file.tests)
+                } else {
+                    "custom harness".to_string()
+                };
+                content.push_str(&format!(
+                    "- `{source}` ({} `{}`): {tests}\n",
+                    test_file.kind, test_file.target
+                ));
+            }
+        }
+
+        Some(ContentSection {
+            title: "Test Inventory".to_string(),
+            content,
+            priority: 6,
+            truncated: false,
+            fallback: None,
+        })
+    }
+
+    fn create_dependency_api_section(
+        &self,
+        scan_result: &ScanResult,
+        sources: &ParsedSources,
+    ) -> Option<ContentSection> {
+        let analyzer = DependencyApiAnalyzer::new(self.config.repo_path.clone());
+        let apis = analyzer.analyze_sources(scan_result, sources);
+        if apis.is_empty() {
+            return None;
+        }
+
+        let mut content = String::new();
+        content.push_str("# Dependency API\n\n");
+        content.push_str("Public items of direct dependencies that this project imports.\n\n");
+
+        for api in &apis {
+            match &api.version {
+                Some(version) => content.push_str(&format!("## {} {version}\n\n", api.name)),
+                None => content.push_str(&format!("## {}\n\n", api.name)),
+            }
+            content.push_str(&format!("**Imports:** {}\n\n", api.imported.join(", ")));
+
+            for item in &api.items {
+                content.push_str(&format!("- `{}`", item.signature));
+                if let Some(summary) = &item.summary {
+                    content.push_str(&format!(" — {summary}"));
+                }
+                content.push('\n');
+                for method in &item.methods {
+                    content.push_str(&format!("  - `{method}`\n"));
+                }
+            }
+            content.push('\n');
+        }
+
+        Some(ContentSection {
+            title: "Dependency API".to_string(),
+            content,
+            priority: 5,
+            truncated: false,
+            fallback: None,
+        })
+    }
+
+    fn create_code_health_section(
+        &self,
+        scan_result: &ScanResult,
+        sources: &ParsedSources,
+    ) -> Option<ContentSection> {
+        let health = CodeHealth::from_sources(scan_result, sources);
+        if health.files.is_empty() {
+            return None;
+        }
+
+        let markers: usize = health.files.iter().map(|file| file.markers.len()).sum();
+        let unsafe_sites: usize = health
+            .files
+            .iter()
+            .map(|file| file.unsafe_sites.len())
+            .sum();
+        let panic_sites: usize = health.files.iter().map(|file| file.panic_sites.len()).sum();
+
+        let mut content = String::new();
+        content.push_str("# Code Health\n\n");
+        content.push_str(&format!(
+            "**{markers}** markers, **{unsafe_sites}** unsafe sites and **{panic_sites}** panic sites in {} files. \
+             Unsafe and panic sites are not collected in test code.\n\n",
+            health.files.len()
+        ));
+
+        for file in &health.files {
+            let source = file.path.to_string_lossy().replace('\\', "/");
+            content.push_str(&format!("## {source}\n"));
+
+            for marker in &file.markers {
+                content.push_str(&format!(
+                    "- **{}** (`{source}:{}`): {}\n",
+                    marker.tag, marker.line, marker.text
+                ));
+            }
+
+            for site in &file.unsafe_sites {
+                match site.kind {
+                    UnsafeKind::Block => content
+                        .push_str(&format!("- **unsafe block** (`{source}:{}`)\n", site.line)),
+                    UnsafeKind::Impl => content.push_str(&format!(
+                        "- **unsafe impl** (`{source}:{}`): `{}`\n",
+                        site.line, site.detail
+                    )),
+                }
+            }
+
+            let mut panic_kinds: Vec<PanicKind> =
+                file.panic_sites.iter().map(|site| site.kind).collect();
+            panic_kinds.sort_unstable();
+            panic_kinds.dedup();
+            for kind in panic_kinds {
+                let lines: Vec<String> = file
+                    .panic_sites
+                    .iter()
+                    .filter(|site| site.kind == kind)
+                    .map(|site| site.line.to_string())
+                    .collect();
+                content.push_str(&format!(
+                    "- `{}` ×{}: {} {}\n",
+                    kind.label(),
+                    lines.len(),
+                    if lines.len() == 1 { "line" } else { "lines" },
+                    lines.join(", ")
+                ));
+            }
+            content.push('\n');
+        }
+
+        Some(ContentSection {
+            title: "Code Health".to_string(),
+            content,
+            priority: 9,
+            truncated: false,
+            fallback: None,
+        })
+    }
+
+    fn create_source_code_sections(
+        &self,
+        scan_result: &ScanResult,
+        sources: &ParsedSources,
+    ) -> Vec<ContentSection> {
         let mut sections = Vec::new();
+        let compaction = &self.config.compaction;
+        let token_counter = self.prioritizer.token_counter();
+        let (mut tokens_before, mut tokens_after) = (0, 0);
 
-        for file in &scan_result.files {
+        let mut files = self.files_by_crate(scan_result);
+        if self.config.prioritize_complex_code {
+            files.sort_by_key(|file| {
+                let complexity = sources
+                    .get(&file.relative_path)
+                    .map_or(0, |analysis| analysis.metrics.total_complexity);
+                std::cmp::Reverse(complexity)
+            });
+        }
+
+        for file in files {
+            let display_path = self.section_path(scan_result, file);
             let mut content = String::new();
-            content.push_str(&format!("# Source: {}\n\n", file.relative_path.display()));
+            content.push_str(&format!("# Source: {display_path}\n\n"));
+
+            let syntax_tree = sources.syntax_tree(&file.relative_path);
+            let source = match syntax_tree {
+                Some(syntax_tree) if compaction.is_enabled() => {
+                    let compacted =
+                        SourceTransformer::compact_parsed(&file.content, syntax_tree, compaction);
+                    let before = token_counter.count_tokens(&file.content);
+                    let after = token_counter.count_tokens(&compacted);
+                    tokens_before += before;
+                    tokens_after += after;
+                    content.push_str(&format!(
+                        "*Compacted: {before} → {after} tokens ({}% saved)*\n\n",
+                        Self::percent_saved(before, after)
+                    ));
+                    compacted
+                }
+                _ => file.content.clone(),
+            };
+
             content.push_str("```");
 
             match file.file_type {
@@ -332,20 +1717,63 @@ impl ContextGenerator {
             }
 
             content.push('\n');
-            content.push_str(&file.content);
+            content.push_str(&source);
             content.push_str("\n```\n\n");
 
+            // Without room for the whole file, keep its shape: signatures,
+            // types and docs with function bodies elided
+            let fallback = syntax_tree
+                .map(|syntax_tree| {
+                    SourceTransformer::elide_and_compact(&file.content, syntax_tree, compaction)
+                })
+                .filter(|elided| elided.len() < source.len())
+                .map(|elided| {
+                    Box::new(ContentSection {
+                        title: format!("Source (bodies elided): {display_path}"),
+                        content: format!(
+                            "# Source: {display_path} (function bodies elided)\n\n```rust\n{elided}\n```\n\n"
+                        ),
+                        priority: 3,
+                        truncated: false,
+                        fallback: None,
+                    })
+                });
+
             sections.push(ContentSection {
-                title: format!("Source: {}", file.relative_path.display()),
+                title: format!("Source: {display_path}"),
                 content,
                 priority: 3,
                 truncated: false,
+                fallback,
             });
         }
 
+        // Ahead of the sources, which share its priority
+        if tokens_before > 0 {
+            sections.insert(
+                0,
+                ContentSection {
+                    title: "Source Compaction".to_string(),
+                    content: format!(
+                        "# Source Compaction\n\n**Rust sources:** {tokens_before} → {tokens_after} tokens ({}% saved)\n\n",
+                        Self::percent_saved(tokens_before, tokens_after)
+                    ),
+                    priority: 3,
+                    truncated: false,
+                    fallback: None,
+                },
+            );
+        }
+
         sections
     }
 
+    fn percent_saved(before: usize, after: usize) -> usize {
+        (before.saturating_sub(after) * 100)
+            .checked_div(before)
+            .unwrap_or(0)
+    }
+
     fn format_context(&self, sections: Vec<ContentSection>) -> String {
         let mut context = String::new();
 
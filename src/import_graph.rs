@@ -0,0 +1,428 @@
+//! Import graph for the AI Context Generator.
+//!
+//! This module resolves every `use` declaration to a fully qualified path,
+//! classifies it as crate-internal, standard library or external crate, and
+//! aggregates the crate-internal ones into dependencies between modules.
+//! Dependencies are tracked between file-backed modules: imports in inline
+//! modules such as `mod tests` count for the file that contains them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::module_tree::{qualify, ModuleTree, PathResolver, ResolvedPath, RootKind};
+use crate::parser::ParsedSources;
+use crate::scanner::ScanResult;
+
+/// Module dependencies of every crate root in a project, derived from `use`
+/// declarations.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::import_graph::{ImportGraph, ImportOrigin};
+/// use ai_context_gen::module_tree::ModuleTree;
+/// use ai_context_gen::{Config, RepositoryScanner};
+/// use std::fs;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let dir = tempfile::tempdir()?;
+/// fs::create_dir(dir.path().join("src"))?;
+/// fs::write(
+///     dir.path().join("src/lib.rs"),
+///     "mod parser;\nmod scanner;\npub use scanner::Scanner;\n",
+/// )?;
+/// fs::write(
+///     dir.path().join("src/parser.rs"),
+///     "use crate::Scanner;\nuse std::collections::HashMap;\nuse serde::{Deserialize, Serialize};\n",
+/// )?;
+/// fs::write(
+///     dir.path().join("src/scanner.rs"),
+///     "use super::parser::*;\npub struct Scanner;\n",
+/// )?;
+///
+/// let config = Config { repo_path: dir.path().to_path_buf(), ..Config::default() };
+/// let scan_result = RepositoryScanner::new(config).scan().await?;
+/// let graph = ImportGraph::build(&scan_result, &ModuleTree::resolve(&scan_result));
+///
+/// let parser = &graph.modules[graph.find("crate::parser").unwrap()];
+/// let scanner = graph.find("crate::scanner").unwrap();
+/// assert_eq!(parser.imports[0].path, "crate::Scanner");
+/// assert_eq!(parser.imports[1].origin, ImportOrigin::Std);
+/// assert_eq!(parser.external_crates, ["serde"]);
+///
+/// // `crate::Scanner` is a re-export of `crate::scanner::Scanner`
+/// assert_eq!(parser.depends_on, [scanner]);
+/// assert_eq!(graph.cycles.len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportGraph {
+    /// File-backed modules, grouped by crate root in module tree order.
+    pub modules: Vec<ModuleImports>,
+
+    /// Groups of modules that depend on each other, directly or through
+    /// other modules, as indices into [`Self::modules`].
+    pub cycles: Vec<Vec<usize>>,
+}
+
+/// The imports of a file-backed module and what they depend on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleImports {
+    /// Target name of the crate root the module belongs to.
+    pub target: String,
+
+    /// Kind of that crate root.
+    pub kind: RootKind,
+
+    /// Fully qualified module path (e.g., "crate::generator").
+    pub path: String,
+
+    /// File implementing the module, relative to the repository root.
+    pub file: PathBuf,
+
+    /// Every `use` in the file, including those of its inline modules.
+    pub imports: Vec<Import>,
+
+    /// Crate-local modules imported from, as indices into
+    /// [`ImportGraph::modules`]. Library modules imported by binaries,
+    /// tests and examples are included.
+    pub depends_on: Vec<usize>,
+
+    /// External crates imported from, sorted by name.
+    pub external_crates: Vec<String>,
+
+    /// Position in the dependency layering: 0 for modules depending on no
+    /// other module, otherwise one more than the highest layer depended on.
+    /// Modules in a cycle share a layer.
+    pub layer: usize,
+}
+
+/// A single imported path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Import {
+    /// Fully qualified path (e.g., "crate::parser::RustParser" for
+    /// `use super::parser::RustParser`, or "std::fmt" for `use std::fmt`).
+    /// Glob imports end with `*`.
+    pub path: String,
+
+    /// Where the imported item comes from.
+    pub origin: ImportOrigin,
+
+    /// Crate the path starts in: the target name for crate-internal imports,
+    /// `std`/`core`/`alloc`/... or the external crate name otherwise.
+    pub crate_name: String,
+
+    /// Whether the `use` is visible outside its module (a re-export).
+    pub is_reexport: bool,
+
+    /// Line of the `use` declaration.
+    pub line: usize,
+}
+
+/// Where an imported path comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportOrigin {
+    /// The same crate, or a library of the same project.
+    Crate,
+    /// The standard library (`std`, `core`, `alloc`, ...).
+    Std,
+    /// A dependency.
+    External,
+}
+
+impl ModuleImports {
+    /// Module path without the leading `crate::`.
+    pub fn short_path(&self) -> &str {
+        self.path.strip_prefix("crate::").unwrap_or(&self.path)
+    }
+}
+
+impl ImportGraph {
+    /// Builds the import graph of every crate root in `module_tree`.
+    ///
+    /// A file reachable from several roots is attributed to the first one,
+    /// usually the library. Files that do not parse contribute the imports
+    /// that can be recovered.
+    pub fn build(scan_result: &ScanResult, module_tree: &ModuleTree) -> Self {
+        Self::from_sources(module_tree, &ParsedSources::parse(scan_result))
+    }
+
+    /// Builds the import graph of every crate root in `module_tree` from
+    /// already parsed sources.
+    pub fn from_sources(module_tree: &ModuleTree, sources: &ParsedSources) -> Self {
+        let mut paths = PathResolver::new(module_tree);
+
+        let mut graph = ImportGraph::default();
+        let mut nodes = HashMap::new();
+        let mut targets = Vec::new();
+        for (root, module) in module_tree.module_files() {
+            let Some(file) = &module.file else {
+                continue;
+            };
+            let Some(analysis) = sources.get(file) else {
+                continue;
+            };
+
+            // Types declared in the file, so `use Kind::*` is not taken for a crate
+            for declared in analysis
+                .structs
+                .iter()
+                .map(|info| &info.path)
+                .chain(analysis.enums.iter().map(|info| &info.path))
+                .chain(analysis.traits.iter().map(|info| &info.path))
+            {
+                paths.declare_item(root, qualify(&module.path, declared));
+            }
+
+            let crate_root = &module_tree.roots[root];
+            let mut imports = Vec::new();
+            let mut module_targets = Vec::new();
+            for import in &analysis.imports {
+                let scope = qualify(&module.path, &import.module_path);
+                let segments: Vec<String> = import.path.split("::").map(str::to_string).collect();
+                let is_reexport = import.visibility != "private";
+                // Paths that name nothing, like `super` above the crate root
+                let Some(resolved) = paths.absolute(root, &scope, &segments) else {
+                    continue;
+                };
+                let (origin, crate_name, path) = match resolved {
+                    ResolvedPath::Crate(target_root, target) => {
+                        let name = &module_tree.roots[target_root].target;
+                        let path = if target_root == root {
+                            target.clone()
+                        } else {
+                            target.replacen("crate", &name.replace('-', "_"), 1)
+                        };
+                        if !import.is_glob {
+                            let alias = import.alias.as_ref().or(segments.last());
+                            if let Some(alias) = alias.filter(|alias| *alias != "self") {
+                                paths.add_import(
+                                    root,
+                                    scope,
+                                    alias.clone(),
+                                    (target_root, target.clone()),
+                                );
+                            }
+                        }
+                        module_targets.push((target_root, target));
+                        (ImportOrigin::Crate, name.clone(), path)
+                    }
+                    ResolvedPath::Std(name) => (ImportOrigin::Std, name, import.path.clone()),
+                    ResolvedPath::External(name) => {
+                        (ImportOrigin::External, name, import.path.clone())
+                    }
+                };
+                imports.push(Import {
+                    path: path.trim_start_matches("::").to_string(),
+                    origin,
+                    crate_name,
+                    is_reexport,
+                    line: import.span.start_line,
+                });
+            }
+
+            let mut external_crates: Vec<String> = imports
+                .iter()
+                .filter(|import| import.origin == ImportOrigin::External)
+                .map(|import| import.crate_name.clone())
+                .collect();
+            external_crates.sort();
+            external_crates.dedup();
+
+            nodes.insert((root, module.path.clone()), graph.modules.len());
+            targets.push(module_targets);
+            graph.modules.push(ModuleImports {
+                target: crate_root.target.clone(),
+                kind: crate_root.kind,
+                path: module.path.clone(),
+                file: file.clone(),
+                imports,
+                depends_on: Vec::new(),
+                external_crates,
+                layer: 0,
+            });
+        }
+
+        for (index, module_targets) in targets.into_iter().enumerate() {
+            let mut depends_on = Vec::new();
+            for (target_root, target) in module_targets {
+                let (target_root, target) = paths.follow_reexports(target_root, &target);
+                let dependency = ancestors(&target)
+                    .find_map(|module| nodes.get(&(target_root, module.to_string())).copied());
+                if let Some(dependency) = dependency {
+                    if dependency != index && !depends_on.contains(&dependency) {
+                        depends_on.push(dependency);
+                    }
+                }
+            }
+            graph.modules[index].depends_on = depends_on;
+        }
+
+        // Components come out dependencies first, so layers can be assigned in order
+        let edges: Vec<Vec<usize>> = graph
+            .modules
+            .iter()
+            .map(|module| module.depends_on.clone())
+            .collect();
+        for mut component in strongly_connected(&edges) {
+            let layer = component
+                .iter()
+                .flat_map(|&module| &edges[module])
+                .filter(|dependency| !component.contains(dependency))
+                .map(|&dependency| graph.modules[dependency].layer + 1)
+                .max()
+                .unwrap_or(0);
+            for &module in &component {
+                graph.modules[module].layer = layer;
+            }
+            if component.len() > 1 {
+                component.sort_unstable();
+                graph.cycles.push(component);
+            }
+        }
+        graph.cycles.sort();
+
+        graph
+    }
+
+    /// Finds a module by full path, or by path without `crate::`.
+    pub fn find(&self, path: &str) -> Option<usize> {
+        self.modules
+            .iter()
+            .position(|module| module.path == path || module.short_path() == path)
+    }
+
+    /// Modules depending on module `index`.
+    pub fn dependents(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.modules
+            .iter()
+            .enumerate()
+            .filter(move |(_, module)| module.depends_on.contains(&index))
+            .map(|(dependent, _)| dependent)
+    }
+
+    /// Path of module `index` as seen from crate root `from`: prefixed with
+    /// its target name when it lives in another crate.
+    pub fn display_path(&self, index: usize, from: Option<&str>) -> String {
+        let module = &self.modules[index];
+        match from {
+            Some(target) if target != module.target => match module.short_path() {
+                "crate" => module.target.clone(),
+                path => format!("{}::{path}", module.target),
+            },
+            _ => module.short_path().to_string(),
+        }
+    }
+
+    /// Renders the module dependencies as a Mermaid flowchart, one subgraph
+    /// per crate root.
+    ///
+    /// ```rust
+    /// use ai_context_gen::import_graph::ImportGraph;
+    ///
+    /// assert_eq!(ImportGraph::default().to_mermaid(), "flowchart TD\n");
+    /// ```
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart TD\n");
+        let mut current_target = None;
+        for (index, module) in self.modules.iter().enumerate() {
+            if current_target != Some(&module.target) {
+                if current_target.is_some() {
+                    mermaid.push_str("    end\n");
+                }
+                current_target = Some(&module.target);
+                mermaid.push_str(&format!(
+                    "    subgraph t{index}[\"{} ({})\"]\n",
+                    module.target, module.kind
+                ));
+            }
+            mermaid.push_str(&format!("        m{index}[\"{}\"]\n", module.short_path()));
+        }
+        if current_target.is_some() {
+            mermaid.push_str("    end\n");
+        }
+        for (index, module) in self.modules.iter().enumerate() {
+            for dependency in &module.depends_on {
+                mermaid.push_str(&format!("    m{index} --> m{dependency}\n"));
+            }
+        }
+        mermaid
+    }
+}
+
+/// `path` and its parents, longest first: `crate::a::B`, `crate::a`, `crate`.
+fn ancestors(path: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(Some(path), |path| {
+        path.rsplit_once("::").map(|(parent, _)| parent)
+    })
+}
+
+/// Strongly connected components of a directed graph, in an order where
+/// every component comes after the components it has edges to (Tarjan's
+/// algorithm).
+fn strongly_connected(edges: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    struct Tarjan<'a> {
+        edges: &'a [Vec<usize>],
+        index: Vec<Option<usize>>,
+        low: Vec<usize>,
+        stack: Vec<usize>,
+        on_stack: Vec<bool>,
+        next: usize,
+        components: Vec<Vec<usize>>,
+    }
+
+    impl Tarjan<'_> {
+        fn visit(&mut self, node: usize) {
+            self.index[node] = Some(self.next);
+            self.low[node] = self.next;
+            self.next += 1;
+            self.stack.push(node);
+            self.on_stack[node] = true;
+
+            let edges = self.edges;
+            for &next in &edges[node] {
+                match self.index[next] {
+                    None => {
+                        self.visit(next);
+                        self.low[node] = self.low[node].min(self.low[next]);
+                    }
+                    Some(index) if self.on_stack[next] => {
+                        self.low[node] = self.low[node].min(index);
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            if Some(self.low[node]) == self.index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        edges,
+        index: vec![None; edges.len()],
+        low: vec![0; edges.len()],
+        stack: Vec::new(),
+        on_stack: vec![false; edges.len()],
+        next: 0,
+        components: Vec::new(),
+    };
+    for node in 0..edges.len() {
+        if tarjan.index[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+    tarjan.components
+}
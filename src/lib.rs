@@ -18,8 +18,14 @@
 //! - 🔍 **Complete Scanning**: Analyzes all `.rs` and `.md` files in repositories
 //! - 🌳 **AST Analysis**: Extracts structures, functions, enums and implementations
 //! - 📊 **Token Control**: Respects limits and prioritizes important content
-//! - 📁 **Project Structure**: Generates file tree visualizations
+//! - 📁 **Project Structure**: Resolves the crate module tree and flags unreachable files
 //! - 📖 **Documentation**: Includes markdown files and code documentation
+//! - 🔌 **Public API Mode**: Renders a library's reachable public items as a stub file
+//! - 🩺 **Code Health**: Lists TODO/FIXME markers, `unsafe` code and panic sites per file
+//! - 🕸️ **Call Graph**: Links crate-local functions to the ones they call and are used by
+//! - 🧬 **Type Graph**: Shows which types contain, implement and convert to which, optionally as Mermaid diagrams
+//! - 🧭 **Import Graph**: Layers modules by their `use` dependencies, with external crates and cycles
+//! - 🧪 **Test Inventory**: Lists unit, integration and doc tests with the functions each test exercises
 //! - ⚡ **Async Processing**: Non-blocking, high-performance analysis
 //!
 //! ## Quick Start
@@ -54,6 +60,7 @@
 //!         output_file: "detailed_context.md".to_string(),
 //!         include_hidden: true,
 //!         include_deps: true,
+//!         ..Default::default()
 //!     };
 //!
 //!     // Step-by-step process for more control
@@ -83,6 +90,7 @@
 //!         output_file: "analysis.md".to_string(),
 //!         include_hidden: false,
 //!         include_deps: true,
+//!         ..Default::default()
 //!     };
 //!
 //!     generate_context_with_config(config).await?;
@@ -114,6 +122,7 @@
 //!         output_file: format!("/tmp/analysis_{}.md", chrono::Utc::now().timestamp()),
 //!         include_hidden: false,
 //!         include_deps: false,
+//!         ..Default::default()
 //!     };
 //!     
 //!     generate_context_with_config(config.clone()).await?;
@@ -134,6 +143,7 @@
 //!         output_file: "temp_analysis.md".to_string(),
 //!         include_hidden: true,
 //!         include_deps: true,
+//!         ..Default::default()
 //!     };
 //!
 //!     // Scan first
@@ -155,17 +165,31 @@
 
 use std::path::PathBuf;
 
+pub mod call_graph;
+pub mod code_health;
 pub mod config;
+pub mod dependency_api;
 pub mod generator;
+pub mod import_graph;
+pub mod lockfile;
+pub mod manifest;
+pub mod module_tree;
 pub mod parser;
+pub mod public_api;
 pub mod scanner;
+pub mod source_transform;
+pub mod test_inventory;
 pub mod token_counter;
+pub mod type_graph;
 
 // Re-export main structs for easier usage
 pub use config::Config;
 pub use generator::ContextGenerator;
-pub use parser::{EnumInfo, FunctionInfo, ImplInfo, RustAnalysis, RustParser, StructInfo};
-pub use scanner::{FileInfo, FileType, RepositoryScanner, ScanResult};
+pub use parser::{
+    ConstInfo, EnumInfo, FunctionInfo, ImplInfo, MacroInfo, RustAnalysis, RustParser, SourceSpan,
+    StaticInfo, StructInfo, TraitInfo, TypeAliasInfo, UseInfo, VariantInfo,
+};
+pub use scanner::{CrateInfo, FileInfo, FileType, RepositoryScanner, ScanResult};
 pub use token_counter::{ContentPrioritizer, ContentSection, TokenCounter};
 
 /// Default Result type used by the library
@@ -199,6 +223,7 @@ pub async fn generate_context(path: PathBuf, output: String) -> Result<()> {
         output_file: output,
         include_hidden: false,
         include_deps: false,
+        ..Config::default()
     };
 
     let scanner = RepositoryScanner::new(config.clone());
@@ -229,6 +254,7 @@ pub async fn generate_context(path: PathBuf, output: String) -> Result<()> {
 ///     output_file: "detailed_context.md".to_string(),
 ///     include_hidden: true,
 ///     include_deps: true,
+///     ..Default::default()
 /// };
 ///
 /// generate_context_with_config(config).await?;
@@ -0,0 +1,340 @@
+//! `Cargo.lock` parsing module for the AI Context Generator.
+//!
+//! This module reads the lockfile locally (no network access) to find the exact
+//! version and source each dependency resolved to, and to rebuild the
+//! transitive dependency tree of a package's direct dependencies.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use toml::{Table, Value};
+
+use crate::manifest::{string_field, DependencyInfo, DependencyKind};
+
+/// Registry source string used by Cargo for crates.io.
+const CRATES_IO_SOURCE: &str = "registry+https://github.com/rust-lang/crates.io-index";
+
+/// Parsed contents of a `Cargo.lock` file.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::lockfile::{CargoLock, PackageSource};
+///
+/// let lock = CargoLock::parse(r#"
+/// [[package]]
+/// name = "app"
+/// version = "0.1.0"
+/// dependencies = ["serde"]
+///
+/// [[package]]
+/// name = "serde"
+/// version = "1.0.200"
+/// source = "registry+https://github.com/rust-lang/crates.io-index"
+/// "#).unwrap();
+///
+/// let serde = lock.find("serde", None).unwrap();
+/// assert_eq!(serde.version, "1.0.200");
+/// assert_eq!(serde.source(), PackageSource::CratesIo);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CargoLock {
+    /// Every package recorded in the lockfile.
+    pub packages: Vec<LockedPackage>,
+}
+
+/// A single `[[package]]` entry of `Cargo.lock`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockedPackage {
+    /// Package name.
+    pub name: String,
+
+    /// Exact resolved version.
+    pub version: String,
+
+    /// Raw source string, absent for path dependencies and workspace members.
+    pub source: Option<String>,
+
+    /// Dependency references as written in the lockfile
+    /// (`name`, `name version` or `name version (source)`).
+    pub dependencies: Vec<String>,
+}
+
+/// Where a locked package comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PackageSource {
+    /// The crates.io registry.
+    CratesIo,
+
+    /// Another registry, identified by its index URL.
+    Registry(String),
+
+    /// A git repository, with the locked commit when known.
+    Git {
+        /// Repository URL.
+        url: String,
+
+        /// Locked commit hash.
+        commit: Option<String>,
+    },
+
+    /// A local path dependency or workspace member.
+    Path,
+}
+
+impl std::fmt::Display for PackageSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PackageSource::CratesIo => write!(f, "crates.io"),
+            PackageSource::Registry(url) => write!(f, "registry {url}"),
+            PackageSource::Git { url, commit } => match commit {
+                Some(commit) => write!(f, "git {url}#{}", &commit[..commit.len().min(8)]),
+                None => write!(f, "git {url}"),
+            },
+            PackageSource::Path => write!(f, "path"),
+        }
+    }
+}
+
+/// A direct dependency resolved against the lockfile, with its dependency tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedDependency {
+    /// Package name.
+    pub name: String,
+
+    /// Exact resolved version.
+    pub version: String,
+
+    /// Where the package comes from.
+    pub source: PackageSource,
+
+    /// Which dependency table declared it, for direct dependencies.
+    pub kind: Option<DependencyKind>,
+
+    /// Transitive dependencies of this package.
+    ///
+    /// Empty when `repeated` is set, since the subtree was already listed.
+    pub dependencies: Vec<ResolvedDependency>,
+
+    /// Whether this package's subtree was already expanded elsewhere in the
+    /// tree (rendered like `cargo tree`'s `(*)` marker).
+    pub repeated: bool,
+}
+
+impl LockedPackage {
+    /// Classifies the raw `source` string.
+    pub fn source(&self) -> PackageSource {
+        match self.source.as_deref() {
+            None => PackageSource::Path,
+            Some(CRATES_IO_SOURCE) => PackageSource::CratesIo,
+            Some(source) => {
+                if let Some(registry) = source.strip_prefix("registry+") {
+                    PackageSource::Registry(registry.to_string())
+                } else if let Some(registry) = source.strip_prefix("sparse+") {
+                    PackageSource::Registry(registry.to_string())
+                } else if let Some(git) = source.strip_prefix("git+") {
+                    let (url, commit) = match git.split_once('#') {
+                        Some((url, commit)) => (url, Some(commit.to_string())),
+                        None => (git, None),
+                    };
+                    let url = url.split('?').next().unwrap_or(url).to_string();
+                    PackageSource::Git { url, commit }
+                } else {
+                    PackageSource::Registry(source.to_string())
+                }
+            }
+        }
+    }
+}
+
+impl CargoLock {
+    /// Parses the contents of a `Cargo.lock` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content is not valid TOML.
+    pub fn parse(content: &str) -> Result<Self> {
+        let table: Table = content.parse()?;
+
+        let packages = table
+            .get("package")
+            .and_then(Value::as_array)
+            .map(|packages| {
+                packages
+                    .iter()
+                    .filter_map(Value::as_table)
+                    .map(|package| LockedPackage {
+                        name: string_field(package, "name").unwrap_or_default(),
+                        version: string_field(package, "version").unwrap_or_default(),
+                        source: string_field(package, "source"),
+                        dependencies: package
+                            .get("dependencies")
+                            .and_then(Value::as_array)
+                            .map(|deps| {
+                                deps.iter()
+                                    .filter_map(Value::as_str)
+                                    .map(str::to_string)
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self { packages })
+    }
+
+    /// Finds a package by name and, optionally, exact version.
+    ///
+    /// When several versions are locked and none is requested, the first
+    /// entry in lockfile order is returned.
+    pub fn find(&self, name: &str, version: Option<&str>) -> Option<&LockedPackage> {
+        self.packages
+            .iter()
+            .find(|p| p.name == name && version.map_or(true, |v| p.version == v))
+    }
+
+    /// Resolves a dependency reference as written in a package's
+    /// `dependencies` list.
+    fn find_reference(&self, reference: &str) -> Option<&LockedPackage> {
+        let mut parts = reference.splitn(3, ' ');
+        let name = parts.next()?;
+        let version = parts.next();
+        let source = parts
+            .next()
+            .map(|source| source.trim_start_matches('(').trim_end_matches(')'));
+
+        self.packages.iter().find(|p| {
+            p.name == name
+                && version.map_or(true, |v| p.version == v)
+                && source.map_or(true, |s| p.source.as_deref() == Some(s))
+        })
+    }
+
+    /// Resolves the direct dependencies of a package to exact versions.
+    ///
+    /// The package itself is looked up by name (and version, when given) to
+    /// disambiguate dependencies that are locked at several versions. Each
+    /// resolved dependency carries its transitive tree. Every package's
+    /// subtree is expanded once, at its shallowest occurrence; other
+    /// occurrences are marked as `repeated` instead of being listed again.
+    ///
+    /// # Arguments
+    ///
+    /// * `package_name` - Name of the package whose dependencies are resolved
+    /// * `package_version` - Version of that package, if known
+    /// * `dependencies` - Direct dependencies declared in its manifest
+    pub fn resolve(
+        &self,
+        package_name: &str,
+        package_version: Option<&str>,
+        dependencies: &[DependencyInfo],
+    ) -> Vec<ResolvedDependency> {
+        let root = self
+            .packages
+            .iter()
+            .find(|p| {
+                p.name == package_name
+                    && p.source.is_none()
+                    && package_version.map_or(true, |v| p.version == v)
+            })
+            .or_else(|| self.find(package_name, package_version));
+
+        let direct: Vec<(&LockedPackage, DependencyKind)> = dependencies
+            .iter()
+            .filter_map(|dependency| {
+                let name = dependency.package_name();
+
+                // The root's own reference pins the exact version that was chosen
+                let locked = root
+                    .and_then(|root| {
+                        root.dependencies
+                            .iter()
+                            .filter(|reference| reference.split(' ').next() == Some(name))
+                            .find_map(|reference| self.find_reference(reference))
+                    })
+                    .or_else(|| self.find(name, None))?;
+
+                Some((locked, dependency.kind))
+            })
+            .collect();
+
+        let min_depths = self.min_depths(direct.iter().map(|(package, _)| *package));
+        let mut expanded = HashSet::new();
+
+        direct
+            .into_iter()
+            .map(|(package, kind)| {
+                let mut node = self.build_tree(package, 0, &min_depths, &mut expanded);
+                node.kind = Some(kind);
+                node
+            })
+            .collect()
+    }
+
+    /// Computes the shallowest depth at which each package appears below `roots`.
+    fn min_depths<'a>(
+        &'a self,
+        roots: impl Iterator<Item = &'a LockedPackage>,
+    ) -> HashMap<PackageKey, usize> {
+        let mut depths = HashMap::new();
+        let mut queue: VecDeque<(&LockedPackage, usize)> = roots.map(|p| (p, 0)).collect();
+
+        while let Some((package, depth)) = queue.pop_front() {
+            if depths.contains_key(&package_key(package)) {
+                continue;
+            }
+            depths.insert(package_key(package), depth);
+
+            for reference in &package.dependencies {
+                if let Some(dependency) = self.find_reference(reference) {
+                    queue.push_back((dependency, depth + 1));
+                }
+            }
+        }
+
+        depths
+    }
+
+    /// Builds the tree below `package`, expanding each package only once and
+    /// only at its shallowest depth so truncated renderings stay accurate.
+    fn build_tree(
+        &self,
+        package: &LockedPackage,
+        depth: usize,
+        min_depths: &HashMap<PackageKey, usize>,
+        expanded: &mut HashSet<PackageKey>,
+    ) -> ResolvedDependency {
+        let key = package_key(package);
+        let is_shallowest = min_depths.get(&key).map_or(true, |&min| min == depth);
+        let expand = is_shallowest && expanded.insert(key);
+
+        let dependencies = if expand {
+            package
+                .dependencies
+                .iter()
+                .filter_map(|reference| self.find_reference(reference))
+                .map(|dependency| self.build_tree(dependency, depth + 1, min_depths, expanded))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        ResolvedDependency {
+            name: package.name.clone(),
+            version: package.version.clone(),
+            source: package.source(),
+            kind: None,
+            dependencies,
+            repeated: !expand && !package.dependencies.is_empty(),
+        }
+    }
+}
+
+/// Identifies a locked package by name and version.
+type PackageKey = (String, String);
+
+fn package_key(package: &LockedPackage) -> PackageKey {
+    (package.name.clone(), package.version.clone())
+}
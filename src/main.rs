@@ -8,6 +8,7 @@ use anyhow::Result;
 use clap::Parser;
 use std::path::PathBuf;
 
+use ai_context_gen::config::CompactionConfig;
 use ai_context_gen::{Config, ContextGenerator, RepositoryScanner};
 
 /// Command-line arguments for the AI Context Generator.
@@ -45,9 +46,102 @@ struct Args {
     /// Include analysis of external dependencies
     ///
     /// When enabled, the tool will attempt to analyze and include information
-    /// about external dependencies from Cargo.toml.
+    /// about external dependencies from Cargo.toml, including the public API
+    /// of the items imported from them (read from the local cargo registry).
     #[arg(long)]
     include_deps: bool,
+
+    /// Only scan files matching this glob (repeatable)
+    ///
+    /// Patterns are relative to the repository root, e.g. `crates/core/**`.
+    /// Prefix a pattern with '!' to exclude matches instead.
+    #[arg(long = "include", value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// Skip files and directories matching this glob (repeatable)
+    ///
+    /// Patterns are relative to the repository root, e.g. `**/tests/**`.
+    /// Exclusions take precedence over `--include`.
+    #[arg(long = "exclude", value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Leave private fields out of struct listings
+    ///
+    /// Only fields with a visibility modifier are listed; the number of
+    /// hidden fields is noted under each struct.
+    #[arg(long)]
+    hide_private_fields: bool,
+
+    /// Describe library crates by their public API only
+    ///
+    /// Replaces the per-file analysis and source code with a stub of the
+    /// items reachable from the crate root through `pub` paths and `pub use`
+    /// re-exports, showing signatures and docs but no bodies.
+    #[arg(long)]
+    public_api: bool,
+
+    /// Compact Rust source before including it
+    ///
+    /// Strips regular comments, collapses blank lines and drops
+    /// `#[cfg(test)]` modules. Token savings are reported per file.
+    #[arg(long)]
+    compact: bool,
+
+    /// Strip doc comments from Rust source as well
+    ///
+    /// Doc comments are already summarized in the analysis sections.
+    #[arg(long)]
+    strip_docs: bool,
+
+    /// Give the source of the most complex files the budget first
+    ///
+    /// Orders source sections by the total cyclomatic complexity of their
+    /// file, so complex code is kept whole and simpler files are elided or
+    /// truncated when the token budget runs out.
+    #[arg(long)]
+    prioritize_complex: bool,
+
+    /// Add a code health section
+    ///
+    /// Lists TODO/FIXME/HACK/XXX comments, `unsafe` blocks and impls, and
+    /// `unwrap()`/`expect()`/`panic!`/`todo!`/`unimplemented!` call sites
+    /// per file.
+    #[arg(long)]
+    code_health: bool,
+
+    /// Add a call graph section
+    ///
+    /// Lists, for each crate-local function, the functions it calls and the
+    /// ones it is used by, resolved on a best-effort basis.
+    #[arg(long)]
+    call_graph: bool,
+
+    /// Add a type graph section
+    ///
+    /// Lists, for each crate-local type, the traits it implements, the types
+    /// it contains and the types it converts from and into.
+    #[arg(long)]
+    type_graph: bool,
+
+    /// Add an import graph section
+    ///
+    /// Lists, for each module, the modules and external crates its `use`
+    /// declarations import from, ordered by layer, and dependency cycles.
+    #[arg(long)]
+    import_graph: bool,
+
+    /// Add a test inventory section
+    ///
+    /// Lists `#[test]`/`#[bench]` functions (including `#[tokio::test]`),
+    /// with `should_panic`/`ignore` flags and the functions each one
+    /// exercises, plus doc tests per file and the `tests/` and `benches/`
+    /// crates.
+    #[arg(long)]
+    test_inventory: bool,
+
+    /// Add Mermaid diagrams of the enabled type, import and call graphs
+    #[arg(long)]
+    mermaid: bool,
 }
 
 #[tokio::main]
@@ -60,6 +154,25 @@ async fn main() -> Result<()> {
         output_file: args.output,
         include_hidden: args.include_hidden,
         include_deps: args.include_deps,
+        include_patterns: args.include,
+        exclude_patterns: args.exclude,
+        hide_private_fields: args.hide_private_fields,
+        public_api: args.public_api,
+        compaction: CompactionConfig {
+            strip_doc_comments: args.strip_docs,
+            ..if args.compact {
+                CompactionConfig::standard()
+            } else {
+                CompactionConfig::default()
+            }
+        },
+        prioritize_complex_code: args.prioritize_complex,
+        code_health: args.code_health,
+        call_graph: args.call_graph,
+        type_graph: args.type_graph,
+        import_graph: args.import_graph,
+        test_inventory: args.test_inventory,
+        mermaid: args.mermaid,
     };
 
     println!("🔍 Scanning repository...");
@@ -0,0 +1,694 @@
+//! Cargo manifest parsing module for the AI Context Generator.
+//!
+//! This module parses `Cargo.toml` files into structured package, target and
+//! dependency information using a real TOML parser, so inline tables,
+//! multi-line arrays, `[dependencies.name]` tables and target-specific
+//! dependency sections are all understood.
+
+use anyhow::Result;
+use globset::Glob;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Parsed contents of a `Cargo.toml` file.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::manifest::{CargoManifest, DependencyKind};
+///
+/// let manifest = CargoManifest::parse(r#"
+/// [package]
+/// name = "demo"
+/// version = "0.3.1"
+/// edition = "2021"
+///
+/// [dependencies]
+/// serde = { version = "1.0", features = ["derive"] }
+///
+/// [dev-dependencies.tempfile]
+/// version = "3"
+/// "#).unwrap();
+///
+/// let package = manifest.package.unwrap();
+/// assert_eq!(package.name, "demo");
+/// assert_eq!(package.version.as_deref(), Some("0.3.1"));
+/// assert_eq!(manifest.dependencies[0].features, vec!["derive"]);
+/// assert_eq!(manifest.dependencies[1].kind, DependencyKind::Dev);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CargoManifest {
+    /// The `[package]` section, absent for virtual workspace manifests.
+    pub package: Option<PackageInfo>,
+
+    /// Features declared in the `[features]` section.
+    pub features: BTreeMap<String, Vec<String>>,
+
+    /// Targets declared in the manifest.
+    ///
+    /// Targets inferred from the file layout are added by
+    /// [`CargoManifest::discover_targets`].
+    pub targets: Vec<TargetInfo>,
+
+    /// All declared dependencies, including dev, build and target-specific ones.
+    pub dependencies: Vec<DependencyInfo>,
+
+    /// Whether binaries in `src/main.rs` and `src/bin/` are discovered
+    /// automatically (`package.autobins`, enabled by default).
+    pub autobins: bool,
+
+    /// The `[workspace]` section, present in workspace root manifests.
+    pub workspace: Option<WorkspaceManifest>,
+}
+
+/// The `[workspace]` section of a root `Cargo.toml`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceManifest {
+    /// Member path globs from `members` (e.g. `crates/*`).
+    pub members: Vec<String>,
+
+    /// Path globs from `exclude`.
+    pub exclude: Vec<String>,
+
+    /// Values from `[workspace.package]` that members can inherit.
+    pub package: PackageInfo,
+
+    /// Dependencies from `[workspace.dependencies]` that members can inherit.
+    pub dependencies: Vec<DependencyInfo>,
+}
+
+/// Package-level fields from the `[package]` section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackageInfo {
+    /// Package name.
+    pub name: String,
+
+    /// Package version (e.g. `0.1.2`).
+    pub version: Option<String>,
+
+    /// Rust edition (e.g. `2021`).
+    pub edition: Option<String>,
+
+    /// Minimum supported Rust version from the `rust-version` field.
+    pub rust_version: Option<String>,
+
+    /// SPDX license expression.
+    pub license: Option<String>,
+
+    /// Package description.
+    pub description: Option<String>,
+
+    /// Fields declared as `field.workspace = true`, resolved by
+    /// [`CargoManifest::inherit_workspace`].
+    pub inherited_fields: Vec<String>,
+}
+
+/// Kind of a compilation target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TargetKind {
+    /// The library target (`[lib]` or `src/lib.rs`).
+    Lib,
+
+    /// A binary target (`[[bin]]`, `src/main.rs` or `src/bin/*.rs`).
+    Bin,
+
+    /// An integration test declared with `[[test]]`.
+    Test,
+
+    /// A benchmark declared with `[[bench]]`.
+    Bench,
+}
+
+/// A compilation target of a package.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetInfo {
+    /// Target name.
+    pub name: String,
+
+    /// Whether this is a library, a binary, a test or a benchmark.
+    pub kind: TargetKind,
+
+    /// Path to the target's root source file, relative to the manifest directory.
+    pub path: Option<String>,
+
+    /// Whether the target is built with the libtest harness; `harness = false`
+    /// targets bring their own `main`, like criterion benchmarks.
+    pub harness: bool,
+}
+
+/// Which dependency table a dependency was declared in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DependencyKind {
+    /// Declared in `[dependencies]`.
+    Normal,
+
+    /// Declared in `[dev-dependencies]`.
+    Dev,
+
+    /// Declared in `[build-dependencies]`.
+    Build,
+}
+
+impl DependencyKind {
+    /// Returns the manifest table name for this kind.
+    pub fn table_name(&self) -> &'static str {
+        match self {
+            DependencyKind::Normal => "dependencies",
+            DependencyKind::Dev => "dev-dependencies",
+            DependencyKind::Build => "build-dependencies",
+        }
+    }
+}
+
+/// A single dependency declaration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyInfo {
+    /// Name the dependency is referred to by in code (the manifest key).
+    pub name: String,
+
+    /// Actual package name when renamed with `package = "..."`.
+    pub package: Option<String>,
+
+    /// Version requirement (e.g. `1.0`, `^0.4.2`).
+    pub version_req: Option<String>,
+
+    /// Explicitly enabled features.
+    pub features: Vec<String>,
+
+    /// Whether default features are enabled.
+    pub default_features: bool,
+
+    /// Whether the dependency is optional (only enabled through a feature).
+    pub optional: bool,
+
+    /// Which dependency table the dependency was declared in.
+    pub kind: DependencyKind,
+
+    /// Target platform restriction, e.g. `cfg(unix)`, for
+    /// `[target.'...'.dependencies]` entries.
+    pub target: Option<String>,
+
+    /// Local path for path dependencies.
+    pub path: Option<String>,
+
+    /// Repository URL for git dependencies.
+    pub git: Option<String>,
+
+    /// Whether the declaration is inherited from the workspace
+    /// (`dep = { workspace = true }`).
+    pub workspace: bool,
+}
+
+impl DependencyInfo {
+    /// Returns the name of the package on the registry, accounting for renames.
+    pub fn package_name(&self) -> &str {
+        self.package.as_deref().unwrap_or(&self.name)
+    }
+}
+
+impl Default for CargoManifest {
+    /// An empty manifest without a `[package]` section, as parsed from an
+    /// empty `Cargo.toml`.
+    fn default() -> Self {
+        Self {
+            package: None,
+            features: BTreeMap::new(),
+            targets: Vec::new(),
+            dependencies: Vec::new(),
+            autobins: true,
+            workspace: None,
+        }
+    }
+}
+
+impl CargoManifest {
+    /// Parses the contents of a `Cargo.toml` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content is not valid TOML.
+    pub fn parse(content: &str) -> Result<Self> {
+        let table: Table = content.parse()?;
+
+        let package = table
+            .get("package")
+            .and_then(Value::as_table)
+            .map(Self::parse_package);
+
+        let features = table
+            .get("features")
+            .and_then(Value::as_table)
+            .map(|features| {
+                features
+                    .iter()
+                    .map(|(name, enables)| (name.clone(), string_array(enables)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut dependencies = Self::parse_dependency_tables(&table, None);
+        if let Some(targets) = table.get("target").and_then(Value::as_table) {
+            for (platform, target_table) in targets {
+                if let Some(target_table) = target_table.as_table() {
+                    dependencies.extend(Self::parse_dependency_tables(
+                        target_table,
+                        Some(platform.as_str()),
+                    ));
+                }
+            }
+        }
+
+        let workspace = table
+            .get("workspace")
+            .and_then(Value::as_table)
+            .map(Self::parse_workspace);
+
+        let autobins = table
+            .get("package")
+            .and_then(|package| package.get("autobins"))
+            .and_then(Value::as_bool)
+            .unwrap_or(true);
+
+        Ok(Self {
+            package,
+            features,
+            targets: Self::parse_targets(&table),
+            dependencies,
+            autobins,
+            workspace,
+        })
+    }
+
+    /// Resolves `field.workspace = true` and `dep = { workspace = true }`
+    /// declarations against the workspace root manifest.
+    ///
+    /// Inherited dependencies take their source and version requirement from
+    /// `[workspace.dependencies]`; features listed by the member are added to
+    /// the inherited ones, as Cargo does.
+    pub fn inherit_workspace(&mut self, workspace: &WorkspaceManifest) {
+        if let Some(package) = &mut self.package {
+            let shared = &workspace.package;
+            for field in &package.inherited_fields {
+                let (target, value) = match field.as_str() {
+                    "version" => (&mut package.version, &shared.version),
+                    "edition" => (&mut package.edition, &shared.edition),
+                    "rust-version" => (&mut package.rust_version, &shared.rust_version),
+                    "license" => (&mut package.license, &shared.license),
+                    "description" => (&mut package.description, &shared.description),
+                    _ => continue,
+                };
+                if target.is_none() {
+                    target.clone_from(value);
+                }
+            }
+        }
+
+        for dependency in self.dependencies.iter_mut().filter(|d| d.workspace) {
+            let Some(shared) = workspace
+                .dependencies
+                .iter()
+                .find(|shared| shared.name == dependency.name)
+            else {
+                continue;
+            };
+
+            dependency.package = dependency.package.take().or(shared.package.clone());
+            dependency.version_req = shared.version_req.clone();
+            dependency.path = shared.path.clone();
+            dependency.git = shared.git.clone();
+            dependency.default_features &= shared.default_features;
+            for feature in &shared.features {
+                if !dependency.features.contains(feature) {
+                    dependency.features.push(feature.clone());
+                }
+            }
+        }
+    }
+
+    /// Resolves target paths and adds targets that Cargo infers from the file layout.
+    ///
+    /// Mirrors Cargo's conventions: declared targets without a `path` get their
+    /// default location, `src/lib.rs` becomes the library target, and unless
+    /// `autobins = false`, `src/main.rs` and `src/bin/*.rs` become binaries.
+    ///
+    /// # Arguments
+    ///
+    /// * `manifest_dir` - Directory containing the `Cargo.toml`
+    pub fn discover_targets(&mut self, manifest_dir: &Path) {
+        let package_name = match &self.package {
+            Some(package) => package.name.clone(),
+            None => return,
+        };
+
+        for target in self.targets.iter_mut().filter(|t| t.path.is_none()) {
+            let path = match target.kind {
+                TargetKind::Lib => "src/lib.rs".to_string(),
+                TargetKind::Bin
+                    if target.name == package_name
+                        && manifest_dir.join("src/main.rs").is_file() =>
+                {
+                    "src/main.rs".to_string()
+                }
+                TargetKind::Bin if manifest_dir.join("src/bin").join(&target.name).is_dir() => {
+                    format!("src/bin/{}/main.rs", target.name)
+                }
+                TargetKind::Bin => format!("src/bin/{}.rs", target.name),
+                TargetKind::Test | TargetKind::Bench => {
+                    let dir = if target.kind == TargetKind::Test {
+                        "tests"
+                    } else {
+                        "benches"
+                    };
+                    if manifest_dir.join(dir).join(&target.name).is_dir() {
+                        format!("{dir}/{}/main.rs", target.name)
+                    } else {
+                        format!("{dir}/{}.rs", target.name)
+                    }
+                }
+            };
+            target.path = Some(path);
+        }
+
+        let has_lib = self.targets.iter().any(|t| t.kind == TargetKind::Lib);
+        if !has_lib && manifest_dir.join("src/lib.rs").is_file() {
+            self.targets.push(TargetInfo {
+                name: package_name.replace('-', "_"),
+                kind: TargetKind::Lib,
+                path: Some("src/lib.rs".to_string()),
+                harness: true,
+            });
+        }
+
+        if !self.autobins {
+            return;
+        }
+
+        let mut candidates = Vec::new();
+        if manifest_dir.join("src/main.rs").is_file() {
+            candidates.push((package_name, "src/main.rs".to_string()));
+        }
+
+        if let Ok(entries) = fs::read_dir(manifest_dir.join("src/bin")) {
+            let mut bins: Vec<_> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+                .collect();
+            bins.sort();
+
+            for bin in bins {
+                let name = bin.file_stem().unwrap_or_default().to_string_lossy();
+                candidates.push((name.to_string(), format!("src/bin/{name}.rs")));
+            }
+        }
+
+        for (name, path) in candidates {
+            let declared = self
+                .targets
+                .iter()
+                .any(|t| t.path.as_deref() == Some(path.as_str()));
+            if !declared {
+                self.targets.push(TargetInfo {
+                    name,
+                    kind: TargetKind::Bin,
+                    path: Some(path),
+                    harness: true,
+                });
+            }
+        }
+    }
+
+    fn parse_package(package: &Table) -> PackageInfo {
+        let inherited_fields = package
+            .iter()
+            .filter(|(_, value)| {
+                value
+                    .get("workspace")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false)
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        PackageInfo {
+            name: string_field(package, "name").unwrap_or_default(),
+            version: string_field(package, "version"),
+            edition: string_field(package, "edition"),
+            rust_version: string_field(package, "rust-version"),
+            license: string_field(package, "license"),
+            description: string_field(package, "description")
+                .map(|description| description.trim().to_string()),
+            inherited_fields,
+        }
+    }
+
+    fn parse_workspace(workspace: &Table) -> WorkspaceManifest {
+        let dependencies = workspace
+            .get("dependencies")
+            .and_then(Value::as_table)
+            .map(|deps| {
+                deps.iter()
+                    .map(|(name, spec)| {
+                        Self::parse_dependency(name, spec, DependencyKind::Normal, None)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        WorkspaceManifest {
+            members: workspace
+                .get("members")
+                .map(string_array)
+                .unwrap_or_default(),
+            exclude: workspace
+                .get("exclude")
+                .map(string_array)
+                .unwrap_or_default(),
+            package: workspace
+                .get("package")
+                .and_then(Value::as_table)
+                .map(Self::parse_package)
+                .unwrap_or_default(),
+            dependencies,
+        }
+    }
+
+    fn parse_targets(table: &Table) -> Vec<TargetInfo> {
+        let mut targets = Vec::new();
+
+        if let Some(lib) = table.get("lib").and_then(Value::as_table) {
+            let default_name = table
+                .get("package")
+                .and_then(|package| package.get("name"))
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .replace('-', "_");
+
+            targets.push(TargetInfo {
+                name: string_field(lib, "name").unwrap_or(default_name),
+                kind: TargetKind::Lib,
+                path: string_field(lib, "path"),
+                harness: harness(lib),
+            });
+        }
+
+        for (key, kind) in [
+            ("bin", TargetKind::Bin),
+            ("test", TargetKind::Test),
+            ("bench", TargetKind::Bench),
+        ] {
+            let Some(entries) = table.get(key).and_then(Value::as_array) else {
+                continue;
+            };
+            for entry in entries.iter().filter_map(Value::as_table) {
+                targets.push(TargetInfo {
+                    name: string_field(entry, "name").unwrap_or_default(),
+                    kind,
+                    path: string_field(entry, "path"),
+                    harness: harness(entry),
+                });
+            }
+        }
+
+        targets
+    }
+
+    fn parse_dependency_tables(table: &Table, target: Option<&str>) -> Vec<DependencyInfo> {
+        // Cargo still accepts the legacy underscore spellings
+        const TABLES: &[(&str, DependencyKind)] = &[
+            ("dependencies", DependencyKind::Normal),
+            ("dev-dependencies", DependencyKind::Dev),
+            ("dev_dependencies", DependencyKind::Dev),
+            ("build-dependencies", DependencyKind::Build),
+            ("build_dependencies", DependencyKind::Build),
+        ];
+
+        let mut dependencies = Vec::new();
+        for (key, kind) in TABLES {
+            if let Some(deps) = table.get(*key).and_then(Value::as_table) {
+                for (name, spec) in deps {
+                    dependencies.push(Self::parse_dependency(name, spec, *kind, target));
+                }
+            }
+        }
+
+        dependencies
+    }
+
+    fn parse_dependency(
+        name: &str,
+        spec: &Value,
+        kind: DependencyKind,
+        target: Option<&str>,
+    ) -> DependencyInfo {
+        let mut dependency = DependencyInfo {
+            name: name.to_string(),
+            package: None,
+            version_req: None,
+            features: Vec::new(),
+            default_features: true,
+            optional: false,
+            kind,
+            target: target.map(str::to_string),
+            path: None,
+            git: None,
+            workspace: false,
+        };
+
+        match spec {
+            Value::String(version) => dependency.version_req = Some(version.clone()),
+            Value::Table(spec) => {
+                dependency.package = string_field(spec, "package");
+                dependency.version_req = string_field(spec, "version");
+                dependency.features = spec.get("features").map(string_array).unwrap_or_default();
+                dependency.default_features = spec
+                    .get("default-features")
+                    .or_else(|| spec.get("default_features"))
+                    .and_then(Value::as_bool)
+                    .unwrap_or(true);
+                dependency.optional = spec
+                    .get("optional")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                dependency.path = string_field(spec, "path");
+                dependency.git = string_field(spec, "git");
+                dependency.workspace = spec
+                    .get("workspace")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+            }
+            _ => {}
+        }
+
+        dependency
+    }
+}
+
+impl WorkspaceManifest {
+    /// Expands the `members` globs into member directories.
+    ///
+    /// Only directories containing a `Cargo.toml` are returned, excluding
+    /// those matched by `exclude`. Paths are relative to `root` and sorted.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Directory containing the workspace root manifest
+    pub fn resolve_members(&self, root: &Path) -> Vec<PathBuf> {
+        let excluded: Vec<PathBuf> = self
+            .exclude
+            .iter()
+            .flat_map(|pattern| expand_dir_pattern(root, pattern))
+            .collect();
+
+        let mut members: Vec<PathBuf> = self
+            .members
+            .iter()
+            .flat_map(|pattern| expand_dir_pattern(root, pattern))
+            .filter(|member| root.join(member).join("Cargo.toml").is_file())
+            .filter(|member| !excluded.iter().any(|ex| member.starts_with(ex)))
+            .collect();
+
+        members.sort();
+        members.dedup();
+        members
+    }
+}
+
+/// Expands a directory glob such as `crates/*` relative to `root`.
+///
+/// Each path component is matched separately against the directory entries,
+/// so `*` never crosses a `/`.
+fn expand_dir_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut matches = vec![PathBuf::new()];
+
+    for component in pattern.trim_end_matches('/').split('/') {
+        if component.is_empty() || component == "." {
+            continue;
+        }
+
+        let is_glob = component.contains(['*', '?', '[', '{']);
+        let matcher = if is_glob {
+            match Glob::new(component) {
+                Ok(glob) => Some(glob.compile_matcher()),
+                Err(_) => return Vec::new(),
+            }
+        } else {
+            None
+        };
+
+        matches = matches
+            .into_iter()
+            .flat_map(|base| -> Vec<PathBuf> {
+                match &matcher {
+                    None => vec![base.join(component)],
+                    Some(matcher) => {
+                        let mut children: Vec<PathBuf> = fs::read_dir(root.join(&base))
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|entry| entry.ok())
+                            .filter(|entry| entry.path().is_dir())
+                            .filter(|entry| matcher.is_match(entry.file_name()))
+                            .map(|entry| base.join(entry.file_name()))
+                            .collect();
+                        children.sort();
+                        children
+                    }
+                }
+            })
+            .collect();
+    }
+
+    matches
+        .into_iter()
+        .filter(|path| root.join(path).is_dir())
+        .collect()
+}
+
+/// The string value of `key` in `table`, if present.
+pub(crate) fn string_field(table: &Table, key: &str) -> Option<String> {
+    table.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+/// The `harness` flag of a target table, `true` unless disabled.
+fn harness(target: &Table) -> bool {
+    target
+        .get("harness")
+        .and_then(Value::as_bool)
+        .unwrap_or(true)
+}
+
+fn string_array(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,664 @@
+//! Module tree resolution for the AI Context Generator.
+//!
+//! This module follows `mod` declarations from each crate root (library,
+//! binaries, build script, integration tests, examples and benches) to the
+//! files that implement them, the same way rustc does: `foo.rs` or
+//! `foo/mod.rs` next to the declaring module, or an explicit `#[path]`.
+//! Only files present in the scan result are considered, so nothing is read
+//! from disk.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use syn::{Item, ItemMod};
+
+use crate::manifest::TargetKind;
+use crate::parser::ParsedSources;
+use crate::scanner::{FileInfo, FileType, ScanResult};
+
+/// Crates shipped with the Rust toolchain.
+const STD_CRATES: [&str; 5] = ["std", "core", "alloc", "proc_macro", "test"];
+
+/// Longest chain of `use` re-exports followed while resolving a path.
+const MAX_REEXPORT_DEPTH: usize = 8;
+
+/// Resolved module hierarchy of every crate root in a scan result.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::module_tree::ModuleTree;
+/// use ai_context_gen::{Config, RepositoryScanner};
+/// use std::path::Path;
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// let scan_result = RepositoryScanner::new(Config::default()).scan().await?;
+/// let tree = ModuleTree::resolve(&scan_result);
+///
+/// for root in &tree.roots {
+///     println!("{} {}", root.kind, root.target);
+/// }
+/// println!("{:?}", tree.module_path(Path::new("src/scanner.rs")));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModuleTree {
+    /// One entry per crate root, in declaration order.
+    pub roots: Vec<CrateRoot>,
+
+    /// Module path of every reachable file, keyed by path relative to the
+    /// repository root. A file reachable from several roots keeps the path
+    /// from the first one (the library, when there is one).
+    pub files: BTreeMap<PathBuf, String>,
+
+    /// Rust files that no crate root reaches through `mod` declarations.
+    pub orphans: Vec<PathBuf>,
+}
+
+/// A crate root and the modules reachable from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrateRoot {
+    /// Name of the package the root belongs to, if it lies inside one.
+    pub package: Option<String>,
+
+    /// Target name (e.g., the binary or integration test name).
+    pub target: String,
+
+    /// What kind of target this root compiles to.
+    pub kind: RootKind,
+
+    /// The root module (`crate`), backed by the root file.
+    pub module: ModuleNode,
+}
+
+/// Kind of crate root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RootKind {
+    /// A library target.
+    Lib,
+
+    /// A binary target.
+    Bin,
+
+    /// The `build.rs` build script.
+    BuildScript,
+
+    /// An integration test under `tests/`.
+    Test,
+
+    /// An example under `examples/`.
+    Example,
+
+    /// A benchmark under `benches/`.
+    Bench,
+}
+
+impl fmt::Display for RootKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RootKind::Lib => "lib",
+            RootKind::Bin => "bin",
+            RootKind::BuildScript => "build script",
+            RootKind::Test => "test",
+            RootKind::Example => "example",
+            RootKind::Bench => "bench",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A module in the resolved tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleNode {
+    /// Module name (`crate` for the root).
+    pub name: String,
+
+    /// Fully qualified module path (e.g., "crate::scanner").
+    pub path: String,
+
+    /// File implementing the module, relative to the repository root.
+    ///
+    /// `None` for inline modules and for `mod foo;` declarations whose file
+    /// was not found among the scanned files.
+    pub file: Option<PathBuf>,
+
+    /// Whether the module body is inline (`mod foo { ... }`).
+    pub is_inline: bool,
+
+    /// Child modules, in declaration order.
+    pub children: Vec<ModuleNode>,
+}
+
+/// Directories whose top-level `.rs` files (and `*/main.rs`) are crate roots.
+const AUTO_ROOT_DIRS: [(&str, RootKind); 3] = [
+    ("tests", RootKind::Test),
+    ("examples", RootKind::Example),
+    ("benches", RootKind::Bench),
+];
+
+impl ModuleTree {
+    /// Resolves the module tree of every package in `scan_result`.
+    ///
+    /// Roots are the library and binary targets from `Cargo.toml`, plus the
+    /// ones Cargo discovers by convention (`build.rs`, `tests/`, `examples/`,
+    /// `benches/`). Without a manifest, `src/lib.rs` and `src/main.rs` are
+    /// used when present.
+    pub fn resolve(scan_result: &ScanResult) -> Self {
+        Self::from_sources(scan_result, &ParsedSources::parse(scan_result))
+    }
+
+    /// Resolves the module tree of every package in `scan_result`, reading
+    /// `mod` declarations from the syntax trees in `sources`.
+    pub fn from_sources(scan_result: &ScanResult, sources: &ParsedSources) -> Self {
+        let rust_files: HashMap<&Path, &FileInfo> = scan_result
+            .files
+            .iter()
+            .filter(|file| matches!(file.file_type, FileType::Rust))
+            .map(|file| (file.relative_path.as_path(), file))
+            .collect();
+
+        let mut resolver = Resolver {
+            rust_files: &rust_files,
+            sources,
+            files: BTreeMap::new(),
+        };
+
+        let mut roots = Vec::new();
+        if scan_result.crates.is_empty() {
+            for (target, kind, path) in Self::conventional_roots(Path::new(""), &rust_files) {
+                roots.extend(resolver.resolve_root(None, target, kind, path));
+            }
+        }
+
+        for crate_info in &scan_result.crates {
+            let mut declared = HashSet::new();
+            let mut candidates = Vec::new();
+
+            for target in &crate_info.metadata.targets {
+                let Some(path) = &target.path else {
+                    continue;
+                };
+                let path = normalize(&crate_info.root.join(path));
+                let kind = match target.kind {
+                    TargetKind::Lib => RootKind::Lib,
+                    TargetKind::Bin => RootKind::Bin,
+                    TargetKind::Test => RootKind::Test,
+                    TargetKind::Bench => RootKind::Bench,
+                };
+                declared.insert(path.clone());
+                candidates.push((target.name.clone(), kind, path));
+            }
+
+            for (target, kind, path) in Self::conventional_roots(&crate_info.root, &rust_files) {
+                // `src/lib.rs` and `src/main.rs` come from the manifest targets
+                if matches!(kind, RootKind::Lib | RootKind::Bin) || declared.contains(&path) {
+                    continue;
+                }
+                candidates.push((target, kind, path));
+            }
+
+            // Resolve the library first so files shared with binaries get its paths
+            candidates.sort_by_key(|(_, kind, _)| *kind != RootKind::Lib);
+
+            for (target, kind, path) in candidates {
+                roots.extend(resolver.resolve_root(
+                    Some(crate_info.name.clone()),
+                    target,
+                    kind,
+                    path,
+                ));
+            }
+        }
+
+        let mut orphans: Vec<PathBuf> = rust_files
+            .keys()
+            .filter(|path| !resolver.files.contains_key(**path))
+            .map(|path| path.to_path_buf())
+            .collect();
+        orphans.sort();
+
+        Self {
+            roots,
+            files: resolver.files,
+            orphans,
+        }
+    }
+
+    /// Returns the module path of a file, relative to the repository root.
+    pub fn module_path(&self, file: &Path) -> Option<&str> {
+        self.files.get(file).map(String::as_str)
+    }
+
+    /// File-backed modules of every root in declaration order, with the
+    /// index of their root in [`Self::roots`].
+    ///
+    /// Each file appears once: a file reachable from several roots belongs
+    /// to the first one, like in [`Self::files`].
+    pub fn module_files(&self) -> Vec<(usize, &ModuleNode)> {
+        let mut claimed = HashSet::new();
+        let mut modules = Vec::new();
+
+        for (root, crate_root) in self.roots.iter().enumerate() {
+            let mut stack = vec![&crate_root.module];
+            while let Some(module) = stack.pop() {
+                if let Some(file) = &module.file {
+                    if claimed.insert(file) {
+                        modules.push((root, module));
+                    }
+                }
+                stack.extend(module.children.iter().rev());
+            }
+        }
+
+        modules
+    }
+
+    /// Crate roots Cargo would discover under `package_root` without any
+    /// target declarations.
+    fn conventional_roots(
+        package_root: &Path,
+        rust_files: &HashMap<&Path, &FileInfo>,
+    ) -> Vec<(String, RootKind, PathBuf)> {
+        let exists = |path: &Path| rust_files.contains_key(path);
+        let mut roots = Vec::new();
+
+        for (file, kind) in [
+            ("src/lib.rs", RootKind::Lib),
+            ("src/main.rs", RootKind::Bin),
+        ] {
+            let path = package_root.join(file);
+            if exists(&path) {
+                roots.push((kind.to_string(), kind, path));
+            }
+        }
+
+        let build_script = package_root.join("build.rs");
+        if exists(&build_script) {
+            roots.push((
+                "build-script".to_string(),
+                RootKind::BuildScript,
+                build_script,
+            ));
+        }
+
+        for (dir, kind) in AUTO_ROOT_DIRS {
+            let dir = package_root.join(dir);
+            let mut found: Vec<(String, PathBuf)> = rust_files
+                .keys()
+                .filter_map(|path| {
+                    let relative = path.strip_prefix(&dir).ok()?;
+                    let components: Vec<_> = relative.components().collect();
+                    match components.as_slice() {
+                        [file] => {
+                            let name = Path::new(file).file_stem()?.to_string_lossy();
+                            Some((name.to_string(), path.to_path_buf()))
+                        }
+                        [target, main] if Path::new(main) == Path::new("main.rs") => Some((
+                            Path::new(target).to_string_lossy().to_string(),
+                            path.to_path_buf(),
+                        )),
+                        _ => None,
+                    }
+                })
+                .collect();
+            found.sort();
+            roots.extend(found.into_iter().map(|(name, path)| (name, kind, path)));
+        }
+
+        roots
+    }
+}
+
+/// Outcome of resolving a path written in Rust source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ResolvedPath {
+    /// A crate-local path: crate root index and full path.
+    Crate(usize, String),
+    /// A path into a standard library crate, with its name.
+    Std(String),
+    /// A path into an external crate, with its name.
+    External(String),
+}
+
+/// Resolves paths written in Rust source against a [`ModuleTree`]. Crates
+/// are identified by their index in [`ModuleTree::roots`].
+///
+/// Modules and libraries come from the tree; the items and `use` imports
+/// of each module are registered by the caller as files are read.
+#[derive(Debug, Default)]
+pub(crate) struct PathResolver {
+    /// Library crates by the name other crates use for them.
+    libraries: HashMap<String, usize>,
+    /// Library crates in module tree order.
+    library_roots: Vec<usize>,
+    /// Every module path, per crate.
+    modules: HashSet<(usize, String)>,
+    /// Declared items by full path, per crate, so `use Kind::*` is not taken
+    /// for a crate.
+    items: HashSet<(usize, String)>,
+    /// Crate-local names brought into each module by `use`.
+    imports: HashMap<(usize, String), HashMap<String, (usize, String)>>,
+}
+
+impl PathResolver {
+    /// Collects the libraries and modules of every root in `module_tree`.
+    pub(crate) fn new(module_tree: &ModuleTree) -> Self {
+        let mut resolver = Self::default();
+        for (root, crate_root) in module_tree.roots.iter().enumerate() {
+            if crate_root.kind == RootKind::Lib {
+                resolver
+                    .libraries
+                    .insert(crate_root.target.replace('-', "_"), root);
+                resolver.library_roots.push(root);
+            }
+            let mut stack = vec![&crate_root.module];
+            while let Some(module) = stack.pop() {
+                resolver.modules.insert((root, module.path.clone()));
+                stack.extend(&module.children);
+            }
+        }
+        resolver
+    }
+
+    /// Library crates in module tree order.
+    pub(crate) fn libraries(&self) -> &[usize] {
+        &self.library_roots
+    }
+
+    /// Records an item declared at full path `path`.
+    pub(crate) fn declare_item(&mut self, root: usize, path: String) {
+        self.items.insert((root, path));
+    }
+
+    /// Records that `use` brings `name` into `module`, pointing at `target`.
+    pub(crate) fn add_import(
+        &mut self,
+        root: usize,
+        module: String,
+        name: String,
+        target: (usize, String),
+    ) {
+        self.imports
+            .entry((root, module))
+            .or_default()
+            .insert(name, target);
+    }
+
+    /// Target of the name `name` imported into `module`.
+    pub(crate) fn import(&self, root: usize, module: &str, name: &str) -> Option<&(usize, String)> {
+        self.imports
+            .get(&(root, module.to_string()))
+            .and_then(|imports| imports.get(name))
+    }
+
+    /// Turns a path written in `module` into a full path. Returns `None`
+    /// for paths that cannot name anything, like `super` above the crate
+    /// root.
+    pub(crate) fn absolute(
+        &self,
+        root: usize,
+        module: &str,
+        segments: &[String],
+    ) -> Option<ResolvedPath> {
+        let (first, rest) = segments.split_first()?;
+        match first.as_str() {
+            "crate" => Some(ResolvedPath::Crate(root, segments.join("::"))),
+            "self" => Some(ResolvedPath::Crate(root, join(module, rest))),
+            "super" => {
+                let mut module = module;
+                let mut rest = segments;
+                while let Some((segment, tail)) = rest.split_first() {
+                    if segment != "super" {
+                        break;
+                    }
+                    module = module.rsplit_once("::")?.0;
+                    rest = tail;
+                }
+                Some(ResolvedPath::Crate(root, join(module, rest)))
+            }
+            "" => {
+                let (name, rest) = rest.split_first()?;
+                Some(self.external(name, rest))
+            }
+            _ if self.modules.contains(&(root, qualify(module, first)))
+                || self.items.contains(&(root, qualify(module, first))) =>
+            {
+                Some(ResolvedPath::Crate(root, join(module, segments)))
+            }
+            _ => Some(self.external(first, rest)),
+        }
+    }
+
+    /// Resolves a path starting with a crate name.
+    fn external(&self, name: &str, rest: &[String]) -> ResolvedPath {
+        if let Some(&library) = self.libraries.get(name) {
+            ResolvedPath::Crate(library, join("crate", rest))
+        } else if STD_CRATES.contains(&name) {
+            ResolvedPath::Std(name.to_string())
+        } else {
+            ResolvedPath::External(name.to_string())
+        }
+    }
+
+    /// Rewrites a path whose prefix goes through a `use`, so `crate::Config`
+    /// becomes `crate::config::Config` when the crate root imports it.
+    pub(crate) fn follow_reexports(&self, mut root: usize, path: &str) -> (usize, String) {
+        let mut segments: Vec<String> = path.split("::").map(str::to_string).collect();
+
+        'rewrite: for _ in 0..MAX_REEXPORT_DEPTH {
+            for index in 1..segments.len() {
+                let prefix = segments[..index].join("::");
+                if let Some((target_root, target)) = self.import(root, &prefix, &segments[index]) {
+                    let mut rewritten: Vec<String> =
+                        target.split("::").map(str::to_string).collect();
+                    rewritten.extend(segments.drain(index + 1..));
+                    segments = rewritten;
+                    root = *target_root;
+                    continue 'rewrite;
+                }
+                if !self
+                    .modules
+                    .contains(&(root, qualify(&prefix, &segments[index])))
+                {
+                    break;
+                }
+            }
+            break;
+        }
+
+        (root, segments.join("::"))
+    }
+}
+
+/// Walks `mod` declarations, recording which module path each file gets.
+struct Resolver<'a> {
+    rust_files: &'a HashMap<&'a Path, &'a FileInfo>,
+    sources: &'a ParsedSources,
+    files: BTreeMap<PathBuf, String>,
+}
+
+impl Resolver<'_> {
+    fn resolve_root(
+        &mut self,
+        package: Option<String>,
+        target: String,
+        kind: RootKind,
+        path: PathBuf,
+    ) -> Option<CrateRoot> {
+        if !self.rust_files.contains_key(path.as_path()) {
+            return None;
+        }
+
+        let mut stack = Vec::new();
+        let module = self.resolve_file("crate", "crate".to_string(), path, true, &mut stack);
+
+        Some(CrateRoot {
+            package,
+            target,
+            kind,
+            module,
+        })
+    }
+
+    /// Resolves the module implemented by `file`.
+    ///
+    /// `owns_directory` is true for crate roots, `mod.rs` files and files
+    /// loaded through `#[path]`: their child modules live next to them rather
+    /// than in a directory named after the file.
+    fn resolve_file(
+        &mut self,
+        name: &str,
+        module_path: String,
+        file: PathBuf,
+        owns_directory: bool,
+        stack: &mut Vec<PathBuf>,
+    ) -> ModuleNode {
+        self.files
+            .entry(file.clone())
+            .or_insert_with(|| module_path.clone());
+
+        let mut children = Vec::new();
+        // `#[path]` can form cycles; never descend into a file twice on one branch
+        if !stack.contains(&file) {
+            if let Some(parsed) = self.sources.syntax_tree(&file) {
+                let parent = file.parent().unwrap_or(Path::new("")).to_path_buf();
+                let module_dir = if owns_directory {
+                    parent.clone()
+                } else {
+                    parent.join(file.file_stem().unwrap_or_default())
+                };
+
+                stack.push(file.clone());
+                children =
+                    self.resolve_items(&parsed.items, &module_path, &module_dir, &parent, stack);
+                stack.pop();
+            }
+        }
+
+        ModuleNode {
+            name: name.to_string(),
+            path: module_path,
+            file: Some(file),
+            is_inline: false,
+            children,
+        }
+    }
+
+    /// Resolves the `mod` items of one module body.
+    ///
+    /// `module_dir` is where `mod foo;` looks for `foo.rs`/`foo/mod.rs`;
+    /// `path_base` is what `#[path]` is relative to, which is the declaring
+    /// file's directory outside inline modules and `module_dir` inside them.
+    fn resolve_items(
+        &mut self,
+        items: &[Item],
+        module_path: &str,
+        module_dir: &Path,
+        path_base: &Path,
+        stack: &mut Vec<PathBuf>,
+    ) -> Vec<ModuleNode> {
+        let mut children = Vec::new();
+
+        for item in items {
+            let Item::Mod(item_mod) = item else {
+                continue;
+            };
+            let name = item_mod.ident.to_string();
+            let child_path = format!("{module_path}::{name}");
+            let path_attr = path_attribute(item_mod);
+
+            if let Some((_, items)) = &item_mod.content {
+                let dir = module_dir.join(path_attr.as_deref().unwrap_or(&name));
+                let grandchildren = self.resolve_items(items, &child_path, &dir, &dir, stack);
+                children.push(ModuleNode {
+                    name,
+                    path: child_path,
+                    file: None,
+                    is_inline: true,
+                    children: grandchildren,
+                });
+                continue;
+            }
+
+            let candidates = match &path_attr {
+                Some(path) => vec![(normalize(&path_base.join(path)), true)],
+                None => vec![
+                    (module_dir.join(format!("{name}.rs")), false),
+                    (module_dir.join(&name).join("mod.rs"), true),
+                ],
+            };
+
+            let found = candidates
+                .into_iter()
+                .find(|(path, _)| self.rust_files.contains_key(path.as_path()));
+
+            children.push(match found {
+                Some((file, owns_directory)) => {
+                    self.resolve_file(&name, child_path, file, owns_directory, stack)
+                }
+                None => ModuleNode {
+                    name,
+                    path: child_path,
+                    file: None,
+                    is_inline: false,
+                    children: Vec::new(),
+                },
+            });
+        }
+
+        children
+    }
+}
+
+/// Returns the value of a `#[path = "..."]` attribute.
+fn path_attribute(item: &ItemMod) -> Option<String> {
+    item.attrs.iter().find_map(|attr| {
+        if !attr.path().is_ident("path") {
+            return None;
+        }
+        match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(path),
+                        ..
+                    }),
+                ..
+            }) => Some(path.value()),
+            _ => None,
+        }
+    })
+}
+
+/// Lexically resolves `.` and `..` components so paths match scanned files.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Joins a path and a name with `::`, either of which may be empty.
+pub(crate) fn qualify(path: &str, name: &str) -> String {
+    match (path.is_empty(), name.is_empty()) {
+        (_, true) => path.to_string(),
+        (true, false) => name.to_string(),
+        (false, false) => format!("{path}::{name}"),
+    }
+}
+
+/// Appends `segments` to `path`.
+pub(crate) fn join(path: &str, segments: &[String]) -> String {
+    segments
+        .iter()
+        .fold(path.to_string(), |path, segment| qualify(&path, segment))
+}
@@ -1,24 +1,70 @@
 //! Rust AST parsing module for the AI Context Generator.
 //!
 //! This module provides functionality to parse Rust source code and extract
-//! structural information such as modules, functions, structs, enums, and
-//! implementations using the `syn` crate.
+//! structural information such as modules, functions, structs, enums, traits,
+//! implementations, type aliases, constants, statics, `macro_rules!` macros and
+//! `use` declarations using the `syn` crate.
 
 use anyhow::Result;
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
 use quote::ToTokens;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use syn::{parse_file, Item, ItemEnum, ItemFn, ItemImpl, ItemMod, ItemStruct, Signature};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{
+    parse_file, Attribute, Block, GenericParam, Item, ItemConst, ItemEnum, ItemFn, ItemImpl,
+    ItemMacro, ItemMod, ItemStatic, ItemStruct, ItemTrait, ItemType, ItemUse, Meta, Signature,
+    Token, UseTree, Visibility,
+};
+
+use crate::module_tree::ModuleTree;
+use crate::scanner::{FileType, ScanResult};
 
 /// Complete analysis result for a single Rust source file.
 ///
 /// Contains all structural information extracted from parsing the file's AST,
-/// including modules, functions, structs, enums, and implementations.
+/// including modules, functions, structs, enums, traits, implementations and
+/// the remaining top-level items (type aliases, constants, statics, macros and
+/// imports).
+///
+/// # Attributes and `cfg`
+///
+/// Each item records a subset of its attributes, rendered as written (e.g.
+/// `#[must_use]` or `#[repr(C)]`): `deprecated`, `must_use`, `repr`,
+/// `non_exhaustive`, the test harness attributes (`test`, `bench`, `ignore`,
+/// `should_panic`), `track_caller`, the linkage attributes `no_mangle` and
+/// `export_name`, the `proc_macro*` attributes, `cfg_attr` and
+/// `async_trait`, plus runtime entry points such as `#[tokio::main]`. Doc
+/// comments, derives and `cfg` are reported separately.
+///
+/// The `cfg` of an item is the conjunction of its own `#[cfg(...)]`
+/// predicates and those of every enclosing module, impl, trait and function,
+/// so a helper in `#[cfg(test)] mod tests` behind `#[cfg(unix)]` has the
+/// `cfg` `all(test, unix)`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RustAnalysis {
     /// Path to the analyzed file.
     pub file_path: String,
 
-    /// List of modules defined in the file.
+    /// Module path of the file within its crate (e.g., "crate::scanner").
+    ///
+    /// The parser only sees one file, so this is `None` until assigned from a
+    /// resolved [`ModuleTree`] (see [`ParsedSources::assign_module_paths`]),
+    /// and stays `None` for files no crate root reaches.
+    #[serde(default)]
+    pub module_path: Option<String>,
+
+    /// Inner documentation of the file (`//!` comments), if present.
+    #[serde(default)]
+    pub documentation: Option<String>,
+
+    /// Top-level modules defined in the file; inline modules carry their
+    /// nested modules in [`ModuleInfo::submodules`].
     pub modules: Vec<ModuleInfo>,
 
     /// List of functions defined in the file.
@@ -33,21 +79,231 @@ pub struct RustAnalysis {
     /// List of impl blocks defined in the file.
     pub implementations: Vec<ImplInfo>,
 
+    /// List of traits defined in the file.
+    pub traits: Vec<TraitInfo>,
+
+    /// List of type aliases defined in the file.
+    pub type_aliases: Vec<TypeAliasInfo>,
+
+    /// List of constants defined in the file.
+    pub constants: Vec<ConstInfo>,
+
+    /// List of statics defined in the file.
+    pub statics: Vec<StaticInfo>,
+
+    /// List of `macro_rules!` macros defined in the file.
+    pub macros: Vec<MacroInfo>,
+
+    /// List of imported paths, one entry per name brought into scope.
+    pub imports: Vec<UseInfo>,
+
     /// Summary of the AST structure.
     pub ast_summary: String,
+
+    /// Errors hit while parsing the file. Empty when the whole file parsed;
+    /// otherwise the analysis only covers the items that could be recovered
+    /// (see [`RustParser::parse_rust_file_recovering`]).
+    #[serde(default)]
+    pub parse_errors: Vec<ParseErrorInfo>,
+
+    /// Totals of the function metrics in the file.
+    #[serde(default)]
+    pub metrics: FileMetrics,
+
+    /// Code blocks in doc comments that rustdoc runs as tests, in source
+    /// order.
+    #[serde(default)]
+    pub doc_tests: Vec<DocTestInfo>,
+}
+
+/// A code block in a doc comment that rustdoc compiles and runs as a test.
+///
+/// Blocks fenced without a language, or with `rust` and rustdoc attributes
+/// only, are doc tests; `text`, `toml`, `sh` and other languages are not.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::parser::RustParser;
+///
+/// let source = r#"
+/// /// Loads the configuration.
+/// ///
+/// /// ```
+/// /// let config = load();
+/// /// ```
+/// ///
+/// /// ```no_run
+/// /// load().save();
+/// /// ```
+/// ///
+/// /// ```toml
+/// /// max_tokens = 1000
+/// /// ```
+/// pub fn load() -> Config { Config }
+/// "#;
+///
+/// let analysis = RustParser::parse_rust_file("lib.rs", source).unwrap();
+/// assert_eq!(analysis.doc_tests.len(), 2);
+/// assert_eq!(analysis.doc_tests[0].item, "load");
+/// assert!(analysis.doc_tests[0].attributes.is_empty());
+/// assert_eq!(analysis.doc_tests[1].attributes, ["no_run"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocTestInfo {
+    /// Path of the documented item relative to the file, empty for the
+    /// file's inner documentation (`//!`).
+    pub item: String,
+
+    /// Line of the documented item, 1 for the file's inner documentation.
+    pub line: usize,
+
+    /// Rustdoc attributes of the block that change how it is run (e.g.,
+    /// "no_run", "should_panic", "ignore", "compile_fail").
+    pub attributes: Vec<String>,
+}
+
+/// File-level totals of [`FunctionMetrics`], over every function, method and
+/// default trait method with a body.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileMetrics {
+    /// Number of lines in the file.
+    pub lines: usize,
+
+    /// Number of functions with a body.
+    pub functions: usize,
+
+    /// Sum of the cyclomatic complexity of every function.
+    pub total_complexity: usize,
+
+    /// Highest cyclomatic complexity of a single function.
+    pub max_complexity: usize,
+
+    /// Deepest nesting of control flow in a single function.
+    pub max_nesting: usize,
+
+    /// Number of `match` arms.
+    pub match_arms: usize,
+
+    /// Number of `async` functions.
+    pub async_functions: usize,
+
+    /// Number of `.await` expressions.
+    pub await_points: usize,
+
+    /// Number of `unsafe` functions.
+    pub unsafe_functions: usize,
+
+    /// Number of `unsafe` blocks.
+    pub unsafe_blocks: usize,
+}
+
+/// A region of a source file that could not be parsed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseErrorInfo {
+    /// Error message reported by the tokenizer or `syn`.
+    pub message: String,
+
+    /// Line where the error was reported (1-based).
+    pub line: usize,
+
+    /// Column where the error was reported (1-based).
+    pub column: usize,
+
+    /// Declarations found by a line-based outline of the region that was
+    /// skipped, e.g. `fn parse (line 12)`, so they are not lost silently.
+    pub skipped_items: Vec<String>,
 }
 
 /// Information about a module definition.
+///
+/// Items declared inside an inline module are recorded in the flat lists of
+/// [`RustAnalysis`] with a qualified `path`; the module itself only keeps the
+/// hierarchy.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::parser::RustParser;
+///
+/// let source = r#"
+/// pub fn run(fast: bool) {
+///     struct Guard;
+///     let task = async {
+///         if fast {
+///             fn step() {}
+///         }
+///     };
+/// }
+///
+/// pub trait Plugin {
+///     fn load(&self) {
+///         let _ = || {
+///             enum State { Idle }
+///         };
+///     }
+/// }
+///
+/// #[cfg(test)]
+/// mod tests {
+///     use super::*;
+///
+///     mod helpers {
+///         pub fn setup() {}
+///     }
+///
+///     #[test]
+///     fn runs() {}
+/// }
+/// "#;
+///
+/// let analysis = RustParser::parse_rust_file("lib.rs", source).unwrap();
+/// let tests = &analysis.modules[0];
+/// assert_eq!(tests.submodules[0].path, "tests::helpers");
+///
+/// let functions: Vec<_> = analysis.functions.iter().map(|f| f.path.as_str()).collect();
+/// assert_eq!(
+///     functions,
+///     ["run", "run::step", "tests::helpers::setup", "tests::runs"]
+/// );
+/// assert_eq!(analysis.structs[0].path, "run::Guard");
+/// assert_eq!(analysis.enums[0].path, "Plugin::load::State");
+/// assert_eq!(analysis.imports[0].module_path, "tests");
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModuleInfo {
     /// Name of the module.
     pub name: String,
 
+    /// Path of the module relative to the file (e.g., "tests::helpers").
+    pub path: String,
+
     /// Visibility modifier (pub, pub(crate), private, etc.).
     pub visibility: String,
 
     /// Number of items contained in the module.
     pub items_count: usize,
+
+    /// Whether the module body is inline (`mod foo { ... }`) rather than
+    /// declared with `mod foo;`.
+    pub is_inline: bool,
+
+    /// Modules nested inside this inline module.
+    pub submodules: Vec<ModuleInfo>,
+
+    /// [Notable attributes](RustAnalysis#attributes-and-cfg) on the `mod`
+    /// declaration.
+    pub attributes: Vec<String>,
+
+    /// `cfg` the module is compiled under, e.g. `test` for `mod tests`.
+    pub cfg: Option<String>,
+
+    /// Documentation comment on the declaration, plus the inner `//!`
+    /// comments of an inline module.
+    pub documentation: Option<String>,
+
+    /// Span of the `mod` declaration, including an inline body.
+    pub span: SourceSpan,
 }
 
 /// Information about a function definition.
@@ -56,20 +312,154 @@ pub struct FunctionInfo {
     /// Name of the function.
     pub name: String,
 
+    /// Qualified path relative to the file (e.g., "tests::helper").
+    pub path: String,
+
     /// Visibility modifier (pub, pub(crate), private, etc.).
     pub visibility: String,
 
     /// Whether the function is async.
     pub is_async: bool,
 
-    /// List of parameter types as strings.
+    /// Whether the function is `const`.
+    pub is_const: bool,
+
+    /// Whether the function is `unsafe`.
+    pub is_unsafe: bool,
+
+    /// ABI of an `extern` function (e.g., "C"), empty for `extern` without one.
+    pub abi: Option<String>,
+
+    /// Generic parameters and `where` clause.
+    pub generics: GenericsInfo,
+
+    /// Parameters as written, including the receiver (e.g., "&mut self",
+    /// "self: Box<Self>", "path: &Path").
     pub parameters: Vec<String>,
 
     /// Return type as a string, if any.
     pub return_type: Option<String>,
 
+    /// Complete signature formatted like rustfmt would, without the body.
+    ///
+    /// Long signatures and `where` clauses span several lines.
+    pub signature: String,
+
+    /// [Notable attributes](RustAnalysis#attributes-and-cfg) such as
+    /// `#[must_use]`, `#[track_caller]` or `#[test]`.
+    pub attributes: Vec<String>,
+
+    /// `cfg` the function is compiled under; functions in a
+    /// `#[cfg(test)] mod tests` get `test`.
+    pub cfg: Option<String>,
+
     /// Documentation comment, if present.
     pub documentation: Option<String>,
+
+    /// Span from the qualifiers or `fn` to the end of the body.
+    pub span: SourceSpan,
+
+    /// Size and complexity of the function.
+    #[serde(default)]
+    pub metrics: FunctionMetrics,
+
+    /// How the function is run as a test or benchmark, if it is one.
+    #[serde(default)]
+    pub test: Option<TestInfo>,
+}
+
+/// Test harness attributes of a test or benchmark function.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::parser::RustParser;
+///
+/// let source = r#"
+/// #[tokio::test]
+/// #[should_panic(expected = "empty")]
+/// #[ignore = "needs network"]
+/// async fn rejects_empty_body() {}
+/// "#;
+///
+/// let analysis = RustParser::parse_rust_file("lib.rs", source).unwrap();
+/// let test = analysis.functions[0].test.as_ref().unwrap();
+/// assert_eq!(test.harness, "tokio::test");
+/// assert!(test.should_panic);
+/// assert!(test.ignored);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestInfo {
+    /// Attribute registering the function, without `#[...]` (e.g., "test",
+    /// "tokio::test" or "bench").
+    pub harness: String,
+
+    /// Whether the test is expected to panic (`#[should_panic]`).
+    pub should_panic: bool,
+
+    /// Whether the test is skipped unless asked for (`#[ignore]`).
+    pub ignored: bool,
+}
+
+/// Size and complexity figures of a function, for spotting hotspots.
+///
+/// Items nested in the body (inner functions, impls, ...) are measured on
+/// their own; closures count as part of the function.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::parser::RustParser;
+///
+/// let source = r#"
+/// pub async fn load(path: &str, retries: u32) -> Result<String, Error> {
+///     for _ in 0..retries {
+///         match fetch(path).await {
+///             Ok(body) if !body.is_empty() => return Ok(body),
+///             Ok(_) => continue,
+///             Err(error) => log(error)?,
+///         }
+///     }
+///     Err(Error::Exhausted)
+/// }
+/// "#;
+///
+/// let analysis = RustParser::parse_rust_file("lib.rs", source).unwrap();
+/// let metrics = &analysis.functions[0].metrics;
+/// assert_eq!(metrics.lines, 10);
+/// assert_eq!(metrics.parameters, 2);
+/// // 1 + `for` + 2 arms beyond the first + guard + `?`
+/// assert_eq!(metrics.cyclomatic_complexity, 6);
+/// assert_eq!(metrics.max_nesting, 2);
+/// assert_eq!(metrics.match_arms, 3);
+/// assert_eq!(metrics.await_points, 1);
+/// assert_eq!(analysis.metrics.total_complexity, 6);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionMetrics {
+    /// Number of lines from the signature to the end of the body.
+    pub lines: usize,
+
+    /// McCabe cyclomatic complexity: 1, plus one per `if`, `while`, `for`,
+    /// `let ... else`, match arm beyond the first, match guard, `&&`, `||`
+    /// and `?`. 0 for trait methods without a default body.
+    pub cyclomatic_complexity: usize,
+
+    /// Deepest nesting of `if`, `match` and loop bodies, 0 for straight-line
+    /// code.
+    pub max_nesting: usize,
+
+    /// Number of parameters, not counting the receiver.
+    pub parameters: usize,
+
+    /// Number of `match` arms.
+    pub match_arms: usize,
+
+    /// Number of `.await` expressions.
+    pub await_points: usize,
+
+    /// Number of `unsafe` blocks.
+    pub unsafe_blocks: usize,
 }
 
 /// Information about a struct definition.
@@ -78,14 +468,40 @@ pub struct StructInfo {
     /// Name of the struct.
     pub name: String,
 
+    /// Qualified path relative to the file (e.g., "tests::helper").
+    pub path: String,
+
     /// Visibility modifier (pub, pub(crate), private, etc.).
     pub visibility: String,
 
-    /// List of fields in the struct.
+    /// Generic parameters and `where` clause.
+    pub generics: GenericsInfo,
+
+    /// Whether the struct is a unit, tuple or named-field struct.
+    pub kind: VariantKind,
+
+    /// List of fields in the struct; tuple fields are named `field_0`,
+    /// `field_1`, ...
     pub fields: Vec<FieldInfo>,
 
+    /// Declaration without the fields (e.g., "pub struct Wrapper<T: Clone>").
+    pub signature: String,
+
+    /// Traits listed in `#[derive(...)]` attributes.
+    pub derives: Vec<String>,
+
+    /// `#[repr(...)]`, `#[non_exhaustive]` and the other
+    /// [notable attributes](RustAnalysis#attributes-and-cfg), besides derives.
+    pub attributes: Vec<String>,
+
+    /// `cfg` the struct is compiled under (e.g., `feature = "serde"`).
+    pub cfg: Option<String>,
+
     /// Documentation comment, if present.
     pub documentation: Option<String>,
+
+    /// Span of the struct, fields included.
+    pub span: SourceSpan,
 }
 
 /// Information about a struct field.
@@ -99,6 +515,9 @@ pub struct FieldInfo {
 
     /// Visibility modifier for the field.
     pub visibility: String,
+
+    /// Documentation comment, if present.
+    pub documentation: Option<String>,
 }
 
 /// Information about an enum definition.
@@ -107,14 +526,99 @@ pub struct EnumInfo {
     /// Name of the enum.
     pub name: String,
 
+    /// Qualified path relative to the file (e.g., "tests::helper").
+    pub path: String,
+
     /// Visibility modifier (pub, pub(crate), private, etc.).
     pub visibility: String,
 
-    /// List of variant names.
-    pub variants: Vec<String>,
+    /// Generic parameters and `where` clause.
+    pub generics: GenericsInfo,
+
+    /// Variants in declaration order.
+    pub variants: Vec<VariantInfo>,
+
+    /// Declaration without the variants (e.g., "pub enum Either<L, R>").
+    pub signature: String,
+
+    /// Traits listed in `#[derive(...)]` attributes.
+    pub derives: Vec<String>,
+
+    /// `#[repr(u8)]`, `#[non_exhaustive]` and the other
+    /// [notable attributes](RustAnalysis#attributes-and-cfg), besides derives.
+    pub attributes: Vec<String>,
+
+    /// `cfg` the enum is compiled under.
+    pub cfg: Option<String>,
 
     /// Documentation comment, if present.
     pub documentation: Option<String>,
+
+    /// Span of the enum, variants included.
+    pub span: SourceSpan,
+}
+
+/// Information about an enum variant.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::parser::{RustParser, VariantKind};
+///
+/// let source = r#"
+/// pub enum Event {
+/// /// A mouse click.
+///     Click { x: i32, y: i32 },
+///     Key(char),
+///     #[default]
+///     Quit = 3,
+/// }
+/// "#;
+///
+/// let analysis = RustParser::parse_rust_file("event.rs", source).unwrap();
+/// let [click, key, quit] = &analysis.enums[0].variants[..] else { panic!() };
+/// assert_eq!(click.kind, VariantKind::Struct);
+/// assert_eq!(click.fields[1].name, "y");
+/// assert_eq!(click.documentation.as_deref(), Some("A mouse click."));
+/// assert_eq!(key.fields[0].field_type, "char");
+/// assert_eq!(quit.discriminant.as_deref(), Some("3"));
+/// assert_eq!(quit.attributes, ["#[default]"]);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantInfo {
+    /// Name of the variant.
+    pub name: String,
+
+    /// Whether the variant is a unit, tuple or struct variant.
+    pub kind: VariantKind,
+
+    /// Payload fields; tuple fields are named `field_0`, `field_1`, ...
+    pub fields: Vec<FieldInfo>,
+
+    /// Explicit discriminant expression (e.g., "404"), if any.
+    pub discriminant: Option<String>,
+
+    /// Attributes other than doc comments (e.g., `#[default]`, `#[error("...")]`).
+    pub attributes: Vec<String>,
+
+    /// Documentation comment, if present.
+    pub documentation: Option<String>,
+
+    /// Span of the variant, payload and discriminant included.
+    pub span: SourceSpan,
+}
+
+/// Shape of an enum variant's payload or of a struct's fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VariantKind {
+    /// No payload, e.g. `Quit`.
+    Unit,
+
+    /// Positional fields, e.g. `Key(char)`.
+    Tuple,
+
+    /// Named fields, e.g. `Click { x: i32, y: i32 }`.
+    Struct,
 }
 
 /// Information about an implementation block.
@@ -123,11 +627,575 @@ pub struct ImplInfo {
     /// The type being implemented (e.g., "MyStruct", "Vec<T>").
     pub target: String,
 
+    /// Path of the module containing the impl block, empty at the top level.
+    pub module_path: String,
+
     /// Name of the trait being implemented, if this is a trait impl.
     pub trait_name: Option<String>,
 
+    /// Generic parameters and `where` clause of the impl.
+    pub generics: GenericsInfo,
+
+    /// Impl header without the items (e.g., "impl<T: Display> fmt::Debug for Wrapper<T>").
+    pub signature: String,
+
     /// List of methods defined in the implementation.
     pub methods: Vec<FunctionInfo>,
+
+    /// [Notable attributes](RustAnalysis#attributes-and-cfg) on the impl
+    /// block, e.g. `#[async_trait]`.
+    pub attributes: Vec<String>,
+
+    /// `cfg` of the impl block, which its methods inherit.
+    pub cfg: Option<String>,
+
+    /// Span of the whole impl block.
+    pub span: SourceSpan,
+}
+
+/// Generic parameters and `where` clause of an item.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::parser::{GenericParamKind, RustParser};
+///
+/// let source = r#"
+/// pub struct Buffer<'a, T: Copy, const N: usize> { items: &'a [T; N] }
+///
+/// impl<'a, T: Copy, const N: usize> Buffer<'a, T, N> {
+///     pub const fn capacity(&self) -> usize { N }
+///     pub fn into_boxed(self: Box<Self>) -> Box<[T]> where T: Default { todo!() }
+///     pub unsafe fn take(mut self) -> T { todo!() }
+/// }
+/// "#;
+///
+/// let analysis = RustParser::parse_rust_file("buffer.rs", source).unwrap();
+/// let buffer = &analysis.structs[0];
+/// assert_eq!(buffer.signature, "pub struct Buffer<'a, T: Copy, const N: usize>");
+/// assert_eq!(buffer.generics.params[2].kind, GenericParamKind::Const);
+///
+/// let methods = &analysis.implementations[0].methods;
+/// assert!(methods[0].is_const);
+/// assert_eq!(methods[1].parameters, ["self: Box<Self>"]);
+/// assert_eq!(methods[1].generics.where_predicates, ["T: Default"]);
+/// assert_eq!(
+///     methods[1].signature,
+///     "pub fn into_boxed(self: Box<Self>) -> Box<[T]>\nwhere\n    T: Default,"
+/// );
+/// assert_eq!(methods[2].parameters, ["mut self"]);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenericsInfo {
+    /// Generic parameters in declaration order.
+    pub params: Vec<GenericParamInfo>,
+
+    /// Predicates of the `where` clause (e.g., "T: Clone + Send").
+    pub where_predicates: Vec<String>,
+}
+
+/// A single lifetime, type or const generic parameter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenericParamInfo {
+    /// Parameter name, including the `'` of lifetimes.
+    pub name: String,
+
+    /// What kind of parameter this is.
+    pub kind: GenericParamKind,
+
+    /// Inline bounds (trait bounds, or outlived lifetimes for lifetimes).
+    pub bounds: Vec<String>,
+
+    /// Default value, if any.
+    pub default: Option<String>,
+
+    /// Type of a const parameter.
+    pub const_type: Option<String>,
+}
+
+/// Kind of generic parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GenericParamKind {
+    /// A lifetime parameter, e.g. `'a`.
+    Lifetime,
+
+    /// A type parameter, e.g. `T`.
+    Type,
+
+    /// A const parameter, e.g. `const N: usize`.
+    Const,
+}
+
+/// Information about a trait definition.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::parser::RustParser;
+///
+/// let source = r#"
+/// use std::fmt::Display;
+///
+/// pub trait Shape: Display {
+///     type Unit;
+///     fn area(&self) -> f64;
+///     fn describe(&self) -> String { self.to_string() }
+/// }
+/// "#;
+///
+/// let analysis = RustParser::parse_rust_file("shapes.rs", source).unwrap();
+/// let shape = &analysis.traits[0];
+/// assert_eq!(shape.supertraits, ["Display"]);
+/// assert_eq!(shape.associated_types[0].name, "Unit");
+/// assert!(!shape.methods[0].has_default);
+/// assert!(shape.methods[1].has_default);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraitInfo {
+    /// Name of the trait.
+    pub name: String,
+
+    /// Qualified path relative to the file (e.g., "tests::helper").
+    pub path: String,
+
+    /// Visibility modifier (pub, pub(crate), private, etc.).
+    pub visibility: String,
+
+    /// Whether the trait is declared `unsafe`.
+    pub is_unsafe: bool,
+
+    /// Supertrait bounds (e.g., "Clone", "Send", "'static").
+    pub supertraits: Vec<String>,
+
+    /// Associated types declared by the trait.
+    pub associated_types: Vec<AssociatedTypeInfo>,
+
+    /// Associated constants declared by the trait.
+    pub associated_consts: Vec<AssociatedConstInfo>,
+
+    /// Methods declared by the trait.
+    pub methods: Vec<TraitMethodInfo>,
+
+    /// [Notable attributes](RustAnalysis#attributes-and-cfg) such as
+    /// `#[async_trait]` or `#[deprecated]`.
+    pub attributes: Vec<String>,
+
+    /// `cfg` the trait is compiled under.
+    pub cfg: Option<String>,
+
+    /// Documentation comment, if present.
+    pub documentation: Option<String>,
+
+    /// Span of the whole trait definition.
+    pub span: SourceSpan,
+}
+
+/// Information about an associated type of a trait.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssociatedTypeInfo {
+    /// Name of the associated type.
+    pub name: String,
+
+    /// Trait bounds the type must satisfy.
+    pub bounds: Vec<String>,
+
+    /// Default type, if one is provided.
+    pub default: Option<String>,
+}
+
+/// Information about an associated constant of a trait.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssociatedConstInfo {
+    /// Name of the constant.
+    pub name: String,
+
+    /// Type of the constant as a string.
+    pub const_type: String,
+
+    /// Whether the trait provides a default value.
+    pub has_default: bool,
+}
+
+/// Information about a method declared in a trait.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraitMethodInfo {
+    /// Signature and documentation of the method.
+    pub function: FunctionInfo,
+
+    /// Whether the trait provides a default implementation.
+    pub has_default: bool,
+}
+
+/// Information about a type alias.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeAliasInfo {
+    /// Name of the alias.
+    pub name: String,
+
+    /// Qualified path relative to the file (e.g., "tests::helper").
+    pub path: String,
+
+    /// Visibility modifier (pub, pub(crate), private, etc.).
+    pub visibility: String,
+
+    /// Aliased type as a string.
+    pub target: String,
+
+    /// Generic parameters and `where` clause of the alias.
+    pub generics: GenericsInfo,
+
+    /// Complete declaration (e.g., "pub type Result<T> = anyhow::Result<T>").
+    pub signature: String,
+
+    /// [Notable attributes](RustAnalysis#attributes-and-cfg), usually
+    /// `#[deprecated]`.
+    pub attributes: Vec<String>,
+
+    /// `cfg` the alias is defined under, e.g. `unix` for one of several
+    /// platform-specific definitions.
+    pub cfg: Option<String>,
+
+    /// Documentation comment, if present.
+    pub documentation: Option<String>,
+
+    /// Span of the `type` declaration.
+    pub span: SourceSpan,
+}
+
+/// Information about a `const` item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstInfo {
+    /// Name of the constant.
+    pub name: String,
+
+    /// Qualified path relative to the file (e.g., "tests::helper").
+    pub path: String,
+
+    /// Visibility modifier (pub, pub(crate), private, etc.).
+    pub visibility: String,
+
+    /// Type of the constant as a string.
+    pub const_type: String,
+
+    /// Value expression as a string.
+    pub value: String,
+
+    /// [Notable attributes](RustAnalysis#attributes-and-cfg), usually
+    /// `#[deprecated]`.
+    pub attributes: Vec<String>,
+
+    /// `cfg` the constant is defined under.
+    pub cfg: Option<String>,
+
+    /// Documentation comment, if present.
+    pub documentation: Option<String>,
+
+    /// Span of the `const` item, initializer included.
+    pub span: SourceSpan,
+}
+
+/// Information about a `static` item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaticInfo {
+    /// Name of the static.
+    pub name: String,
+
+    /// Qualified path relative to the file (e.g., "tests::helper").
+    pub path: String,
+
+    /// Visibility modifier (pub, pub(crate), private, etc.).
+    pub visibility: String,
+
+    /// Type of the static as a string.
+    pub static_type: String,
+
+    /// Whether the static is declared `mut`.
+    pub is_mutable: bool,
+
+    /// Linkage attributes such as `#[no_mangle]` or `#[export_name]`, and the
+    /// other [notable attributes](RustAnalysis#attributes-and-cfg).
+    pub attributes: Vec<String>,
+
+    /// `cfg` the static is defined under.
+    pub cfg: Option<String>,
+
+    /// Documentation comment, if present.
+    pub documentation: Option<String>,
+
+    /// Span of the `static` item, initializer included.
+    pub span: SourceSpan,
+}
+
+/// Information about a `macro_rules!` definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacroInfo {
+    /// Name of the macro.
+    pub name: String,
+
+    /// Qualified path relative to the file (e.g., "tests::helper").
+    pub path: String,
+
+    /// Whether the macro is annotated with `#[macro_export]`.
+    pub is_exported: bool,
+
+    /// Number of matcher arms (rules) in the macro.
+    pub rules: usize,
+
+    /// [Notable attributes](RustAnalysis#attributes-and-cfg) other than
+    /// `#[macro_export]`, which sets [`Self::is_exported`].
+    pub attributes: Vec<String>,
+
+    /// `cfg` the macro is defined under.
+    pub cfg: Option<String>,
+
+    /// Documentation comment, if present.
+    pub documentation: Option<String>,
+
+    /// Span of the `macro_rules!` definition.
+    pub span: SourceSpan,
+}
+
+/// Information about a single name imported by a `use` declaration.
+///
+/// Grouped imports such as `use std::{fs, io::Read};` produce one entry per
+/// imported name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UseInfo {
+    /// Full imported path (e.g., "std::collections::HashMap" or "super::*").
+    pub path: String,
+
+    /// Name the import is renamed to with `as`, if any.
+    pub alias: Option<String>,
+
+    /// Whether this is a glob import.
+    pub is_glob: bool,
+
+    /// Visibility modifier; anything other than "private" is a re-export.
+    pub visibility: String,
+
+    /// Path of the module containing the `use`, empty at the top level.
+    pub module_path: String,
+
+    /// Effective `cfg` predicate of the `use`, if any.
+    pub cfg: Option<String>,
+
+    /// Span of the whole `use` declaration, shared by every name it imports.
+    pub span: SourceSpan,
+}
+
+/// Line and column range of an item in its source file.
+///
+/// Every item info carries one in its `span` field. The range starts at the
+/// item itself, after any outer attributes and doc comments, and ends after
+/// its last token. Lines and columns are 1-based; the generated context
+/// refers to items as `path:start_line`.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::parser::RustParser;
+///
+/// let source = "/// Entry point.\n#[inline]\npub fn run() {\n    todo!()\n}\n";
+///
+/// let analysis = RustParser::parse_rust_file("lib.rs", source).unwrap();
+/// let span = analysis.functions[0].span;
+/// assert_eq!((span.start_line, span.start_column), (3, 1));
+/// assert_eq!((span.end_line, span.end_column), (5, 2));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceSpan {
+    /// Line of the first token.
+    pub start_line: usize,
+
+    /// Column of the first token.
+    pub start_column: usize,
+
+    /// Line of the last token.
+    pub end_line: usize,
+
+    /// Column just past the last token.
+    pub end_column: usize,
+}
+
+impl SourceSpan {
+    /// Span of `node`, skipping leading `#[...]` attributes.
+    fn of(node: &impl ToTokens) -> Self {
+        let mut tokens = node.to_token_stream().into_iter().peekable();
+        let mut first = None;
+        let mut last = None;
+
+        while let Some(token) = tokens.next() {
+            if first.is_none() {
+                let is_attribute = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '#')
+                    && matches!(
+                        tokens.peek(),
+                        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket
+                    );
+                if is_attribute {
+                    tokens.next();
+                    continue;
+                }
+                first = Some(token.span());
+            }
+            last = Some(token.span());
+        }
+
+        match (first, last) {
+            (Some(first), Some(last)) => {
+                let (start, end) = (first.start(), last.end());
+                SourceSpan {
+                    start_line: start.line,
+                    start_column: start.column + 1,
+                    end_line: end.line,
+                    end_column: end.column + 1,
+                }
+            }
+            _ => SourceSpan::default(),
+        }
+    }
+}
+
+/// Attributes surfaced in [`FunctionInfo::attributes`] and the other item
+/// infos, matched by path (listed for users in [`RustAnalysis`]). Runtime
+/// entry points like `#[tokio::main]` are recognized separately.
+const NOTABLE_ATTRIBUTES: &[&str] = &[
+    "deprecated",
+    "must_use",
+    "repr",
+    "non_exhaustive",
+    "test",
+    "bench",
+    "ignore",
+    "should_panic",
+    "track_caller",
+    "no_mangle",
+    "export_name",
+    "proc_macro",
+    "proc_macro_derive",
+    "proc_macro_attribute",
+    "cfg_attr",
+    "async_trait",
+    "async_trait::async_trait",
+];
+
+/// Where an item is declared: the parent path relative to the file and the
+/// `cfg` predicates inherited from enclosing modules, impls and functions.
+#[derive(Debug, Clone, Default)]
+struct Scope {
+    path: String,
+    cfg: Vec<String>,
+}
+
+impl Scope {
+    /// Scope for the contents of an item named `name` with attributes `attrs`.
+    fn child(&self, name: &str, attrs: &[Attribute]) -> Scope {
+        Scope {
+            path: RustParser::qualify(&self.path, name),
+            cfg: self.predicates(attrs),
+        }
+    }
+
+    /// Effective `cfg` of an item declared in this scope with attributes `attrs`.
+    fn cfg(&self, attrs: &[Attribute]) -> Option<String> {
+        let mut predicates = self.predicates(attrs);
+        match predicates.len() {
+            0 => None,
+            1 => predicates.pop(),
+            _ => Some(format!("all({})", predicates.join(", "))),
+        }
+    }
+
+    fn predicates(&self, attrs: &[Attribute]) -> Vec<String> {
+        let mut predicates = self.cfg.clone();
+        for predicate in RustParser::cfg_predicates(attrs) {
+            if !predicates.contains(&predicate) {
+                predicates.push(predicate);
+            }
+        }
+        predicates
+    }
+}
+
+/// Analyses and syntax trees of every Rust file in a scan result, parsed
+/// once and shared by the module tree, sections and graphs built from them.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::module_tree::ModuleTree;
+/// use ai_context_gen::parser::ParsedSources;
+/// use ai_context_gen::{Config, RepositoryScanner};
+/// use std::path::Path;
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// let scan_result = RepositoryScanner::new(Config::default()).scan().await?;
+/// let mut sources = ParsedSources::parse(&scan_result);
+/// let tree = ModuleTree::from_sources(&scan_result, &sources);
+/// sources.assign_module_paths(&tree);
+///
+/// if let Some(analysis) = sources.get(Path::new("src/scanner.rs")) {
+///     println!("{:?}: {} functions", analysis.module_path, analysis.functions.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct ParsedSources {
+    analyses: HashMap<PathBuf, RustAnalysis>,
+    syntax_trees: HashMap<PathBuf, syn::File>,
+}
+
+impl ParsedSources {
+    /// Parses every Rust file in `scan_result` with
+    /// [`RustParser::parse_rust_file_recovering`], keeping the recovered
+    /// syntax trees.
+    pub fn parse(scan_result: &ScanResult) -> Self {
+        let mut sources = Self::default();
+        for file in &scan_result.files {
+            if !matches!(file.file_type, FileType::Rust) {
+                continue;
+            }
+            let path = file.relative_path.to_string_lossy();
+            let (analysis, syntax_tree) = RustParser::parse_recovering(&path, &file.content);
+            sources
+                .analyses
+                .insert(file.relative_path.clone(), analysis);
+            sources
+                .syntax_trees
+                .insert(file.relative_path.clone(), syntax_tree);
+        }
+        sources
+    }
+
+    /// Returns the analysis of the file at `path`, relative to the
+    /// repository root.
+    pub fn get(&self, path: &Path) -> Option<&RustAnalysis> {
+        self.analyses.get(path)
+    }
+
+    /// Sets the [`RustAnalysis::module_path`] of every file that `module_tree`
+    /// reaches from a crate root.
+    pub fn assign_module_paths(&mut self, module_tree: &ModuleTree) {
+        for (path, analysis) in &mut self.analyses {
+            analysis.module_path = module_tree.module_path(path).map(str::to_string);
+        }
+    }
+
+    /// Returns the syntax tree of the file at `path`, relative to the
+    /// repository root. For files that do not parse as a whole, it holds the
+    /// items that could be recovered.
+    pub fn syntax_tree(&self, path: &Path) -> Option<&syn::File> {
+        self.syntax_trees.get(path)
+    }
+}
+
+// `syn::File` only implements `Debug` with syn's `extra-traits` feature
+impl fmt::Debug for ParsedSources {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParsedSources")
+            .field("analyses", &self.analyses)
+            .finish_non_exhaustive()
+    }
 }
 
 /// Rust source code parser using syn for AST analysis.
@@ -141,7 +1209,8 @@ impl RustParser {
     ///
     /// This method uses the `syn` crate to parse Rust source code into an AST
     /// and then extracts information about modules, functions, structs, enums,
-    /// and implementations.
+    /// traits, implementations, type aliases, constants, statics, macros and
+    /// imports.
     ///
     /// # Arguments
     ///
@@ -179,80 +1248,812 @@ impl RustParser {
     /// ```
     pub fn parse_rust_file(file_path: &str, content: &str) -> Result<RustAnalysis> {
         let syntax_tree = parse_file(content)?;
+        Ok(Self::analyze(
+            file_path,
+            content,
+            &syntax_tree.attrs,
+            &syntax_tree.items,
+        ))
+    }
+
+    /// Parses a Rust source file, recovering what it can when the file as a
+    /// whole is not valid `syn` syntax (nightly features, work-in-progress
+    /// code, unusual macro input).
+    ///
+    /// The file is split into top-level items at the token level and each
+    /// item is parsed on its own, so one bad item does not hide the rest. If
+    /// the file cannot even be tokenized (e.g. unbalanced delimiters), only a
+    /// line-based outline of its declarations is kept. Every failure is
+    /// recorded in [`RustAnalysis::parse_errors`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ai_context_gen::parser::RustParser;
+    ///
+    /// let source = r#"
+    /// pub struct Config;
+    ///
+    /// pub fn numbers() -> impl Iterator<Item = u32> { gen { yield 1; } }
+    ///
+    ///
+    /// pub fn load() -> Config { Config }
+    /// "#;
+    ///
+    /// let analysis = RustParser::parse_rust_file_recovering("lib.rs", source);
+    /// assert_eq!(analysis.structs[0].name, "Config");
+    /// assert_eq!(analysis.functions[0].name, "load");
+    /// assert_eq!(analysis.parse_errors.len(), 1);
+    /// assert_eq!(analysis.parse_errors[0].line, 4);
+    /// assert_eq!(analysis.parse_errors[0].skipped_items, ["fn numbers (line 4)"]);
+    ///
+    /// let unbalanced = "pub struct Config;\n\npub fn load() {\n";
+    /// let analysis = RustParser::parse_rust_file_recovering("lib.rs", unbalanced);
+    /// assert!(analysis.structs.is_empty());
+    /// assert_eq!(
+    ///     analysis.parse_errors[0].skipped_items,
+    ///     ["struct Config (line 1)", "fn load (line 3)"]
+    /// );
+    /// ```
+    pub fn parse_rust_file_recovering(file_path: &str, content: &str) -> RustAnalysis {
+        Self::parse_recovering(file_path, content).0
+    }
+
+    /// Like [`Self::parse_rust_file_recovering`], also returning the
+    /// recovered syntax tree the analysis was built from.
+    fn parse_recovering(file_path: &str, content: &str) -> (RustAnalysis, syn::File) {
+        let (syntax_tree, parse_errors) = Self::parse_file_recovering(content);
+        let mut analysis =
+            Self::analyze(file_path, content, &syntax_tree.attrs, &syntax_tree.items);
+        analysis.parse_errors = parse_errors;
+        (analysis, syntax_tree)
+    }
+
+    /// Parses `content` into a [`syn::File`] holding every top-level item that
+    /// parses on its own, along with the errors for the regions that don't.
+    ///
+    /// This is the recovery step behind [`Self::parse_rust_file_recovering`],
+    /// for callers that work on the syntax tree directly.
+    pub fn parse_file_recovering(content: &str) -> (syn::File, Vec<ParseErrorInfo>) {
+        if let Ok(syntax_tree) = parse_file(content) {
+            return (syntax_tree, Vec::new());
+        }
+
+        let mut syntax_tree = syn::File {
+            shebang: None,
+            attrs: Vec::new(),
+            items: Vec::new(),
+        };
+        let mut parse_errors = Vec::new();
+
+        let tokens = match content.parse::<TokenStream>() {
+            Ok(tokens) => tokens,
+            Err(error) => {
+                let start = error.span().start();
+                parse_errors.push(ParseErrorInfo {
+                    message: "could not tokenize the file (e.g. unbalanced delimiters)".to_string(),
+                    line: start.line.max(1),
+                    column: start.column + 1,
+                    skipped_items: Self::outline(content, 1, usize::MAX),
+                });
+                return (syntax_tree, parse_errors);
+            }
+        };
+
+        for chunk in Self::split_items(tokens) {
+            match syn::parse2::<syn::File>(chunk.clone()) {
+                Ok(file) => {
+                    syntax_tree.attrs.extend(file.attrs);
+                    syntax_tree.items.extend(file.items);
+                }
+                Err(error) => {
+                    let chunk_span = SourceSpan::of(&chunk);
+                    let start = error.span().start();
+                    let (line, column) = if start.line == 0 {
+                        (chunk_span.start_line, chunk_span.start_column)
+                    } else {
+                        (start.line, start.column + 1)
+                    };
+                    parse_errors.push(ParseErrorInfo {
+                        message: error.to_string(),
+                        line,
+                        column,
+                        skipped_items: Self::outline(
+                            content,
+                            chunk_span.start_line,
+                            chunk_span.end_line,
+                        ),
+                    });
+                }
+            }
+        }
+
+        (syntax_tree, parse_errors)
+    }
+
+    /// Builds the analysis of a file from its inner attributes and items.
+    fn analyze(
+        file_path: &str,
+        content: &str,
+        attrs: &[Attribute],
+        items: &[Item],
+    ) -> RustAnalysis {
+        let mut analysis = RustAnalysis {
+            file_path: file_path.to_string(),
+            module_path: None,
+            documentation: Self::extract_doc_comments(attrs),
+            modules: Vec::new(),
+            functions: Vec::new(),
+            structs: Vec::new(),
+            enums: Vec::new(),
+            implementations: Vec::new(),
+            traits: Vec::new(),
+            type_aliases: Vec::new(),
+            constants: Vec::new(),
+            statics: Vec::new(),
+            macros: Vec::new(),
+            imports: Vec::new(),
+            ast_summary: String::new(),
+            parse_errors: Vec::new(),
+            metrics: FileMetrics::default(),
+            doc_tests: Vec::new(),
+        };
+
+        analysis.modules = Self::parse_items(items, &Scope::default(), &mut analysis);
+        analysis.metrics = Self::file_metrics(&analysis, content);
+        analysis.doc_tests = Self::doc_tests(&analysis);
+
+        analysis.ast_summary = Self::generate_ast_summary(&analysis);
+
+        analysis
+    }
+
+    /// Splits a file's tokens into chunks of one top-level item each. An item
+    /// ends at a top-level `;`, or after the `{ ... }` block of an item that
+    /// is delimited by one (plus a directly following `;`, see
+    /// [`Self::ends_at_brace`]); inner attributes stay attached to the next
+    /// item.
+    fn split_items(tokens: TokenStream) -> Vec<TokenStream> {
+        let mut chunks = Vec::new();
+        let mut current = Vec::new();
+        let mut tokens = tokens.into_iter().peekable();
+
+        while let Some(token) = tokens.next() {
+            let ends_item = match &token {
+                TokenTree::Punct(punct) => punct.as_char() == ';',
+                TokenTree::Group(group) => {
+                    group.delimiter() == Delimiter::Brace && Self::ends_at_brace(&current)
+                }
+                _ => false,
+            };
+            current.push(token);
+
+            if ends_item {
+                if let Some(TokenTree::Punct(punct)) = tokens.peek() {
+                    if punct.as_char() == ';' {
+                        current.extend(tokens.next());
+                    }
+                }
+                chunks.push(current.drain(..).collect());
+            }
+        }
+
+        if !current.is_empty() {
+            chunks.push(current.into_iter().collect());
+        }
+        chunks
+    }
+
+    /// Whether the item whose tokens so far are `header` ends at its first
+    /// top-level `{ ... }` block. That holds for functions, type definitions,
+    /// traits, impls, modules, extern blocks and macro invocations, but not
+    /// for `const` or `static` items, whose initializers may contain blocks
+    /// followed by more tokens.
+    fn ends_at_brace(header: &[TokenTree]) -> bool {
+        let mut idents = header
+            .iter()
+            .enumerate()
+            .filter_map(|(index, token)| match token {
+                TokenTree::Ident(ident) => Some((index, ident.to_string())),
+                _ => None,
+            })
+            .peekable();
+
+        while let Some((index, ident)) = idents.next() {
+            let next = idents.peek().map(|(_, ident)| ident.as_str());
+            match ident.as_str() {
+                "pub" | "default" | "async" | "unsafe" | "safe" | "auto" => {}
+                "const" if matches!(next, Some("fn" | "unsafe" | "async" | "extern")) => {}
+                "extern" => return next != Some("crate"),
+                "fn" | "struct" | "enum" | "union" | "trait" | "impl" | "mod" | "macro_rules" => {
+                    return true
+                }
+                "const" | "static" | "type" | "use" => return false,
+                // A macro invocation: a path followed by `!`
+                _ => return header[index + 1..]
+                    .iter()
+                    .find(|token| match token {
+                        TokenTree::Ident(_) => false,
+                        TokenTree::Punct(punct) => punct.as_char() != ':',
+                        _ => true,
+                    })
+                    .is_some_and(
+                        |token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == '!'),
+                    ),
+            }
+        }
+        false
+    }
+
+    /// Lists the declarations on lines `first..=last` of `content` by keyword,
+    /// without parsing, e.g. `struct Config (line 3)`.
+    fn outline(content: &str, first: usize, last: usize) -> Vec<String> {
+        static PATTERNS: OnceLock<(Regex, Regex)> = OnceLock::new();
+        let (declaration, implementation) = PATTERNS.get_or_init(|| {
+            let declaration = Regex::new(
+                r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:(?:default|async|const|unsafe|extern\s+\x22[^\x22]*\x22)\s+)*(fn|struct|enum|union|trait|type|const|static|mod|macro_rules!)\s+(?:mut\s+)?([A-Za-z_][A-Za-z0-9_]*)",
+            )
+            .expect("valid outline pattern");
+            let implementation = Regex::new(r"^\s*(?:unsafe\s+)?impl\b\s*([^{]*)")
+                .expect("valid outline pattern");
+            (declaration, implementation)
+        });
+
+        content
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line))
+            .filter(|(number, _)| (first..=last).contains(number))
+            .filter_map(|(number, line)| {
+                if let Some(captures) = declaration.captures(line) {
+                    Some(format!("{} {} (line {number})", &captures[1], &captures[2]))
+                } else {
+                    implementation
+                        .captures(line)
+                        .map(|captures| format!("impl {} (line {number})", captures[1].trim()))
+                }
+            })
+            .collect()
+    }
+
+    /// Analyzes `items` declared in `scope` (a path relative to the file, empty
+    /// at the top level), recording them in `analysis`, and returns the
+    /// modules among them.
+    fn parse_items<'a>(
+        items: impl IntoIterator<Item = &'a Item>,
+        scope: &Scope,
+        analysis: &mut RustAnalysis,
+    ) -> Vec<ModuleInfo> {
+        let mut modules = Vec::new();
+
+        for item in items {
+            match item {
+                Item::Mod(item_mod) => {
+                    modules.push(Self::parse_module(item_mod, scope, analysis));
+                }
+                Item::Fn(item_fn) => {
+                    analysis
+                        .functions
+                        .push(Self::parse_function(item_fn, scope));
+                    let body_scope = scope.child(&item_fn.sig.ident.to_string(), &item_fn.attrs);
+                    modules.extend(Self::parse_block_items(
+                        &item_fn.block,
+                        &body_scope,
+                        analysis,
+                    ));
+                }
+                Item::Struct(item_struct) => {
+                    analysis
+                        .structs
+                        .push(Self::parse_struct(item_struct, scope));
+                }
+                Item::Enum(item_enum) => {
+                    analysis.enums.push(Self::parse_enum(item_enum, scope));
+                }
+                Item::Impl(item_impl) => {
+                    analysis
+                        .implementations
+                        .push(Self::parse_impl(item_impl, scope));
+                    let impl_scope =
+                        scope.child(&Self::type_name(&item_impl.self_ty), &item_impl.attrs);
+                    for impl_item in &item_impl.items {
+                        if let syn::ImplItem::Fn(method) = impl_item {
+                            let body_scope =
+                                impl_scope.child(&method.sig.ident.to_string(), &method.attrs);
+                            modules.extend(Self::parse_block_items(
+                                &method.block,
+                                &body_scope,
+                                analysis,
+                            ));
+                        }
+                    }
+                }
+                Item::Trait(item_trait) => {
+                    analysis.traits.push(Self::parse_trait(item_trait, scope));
+                    let trait_scope = scope.child(&item_trait.ident.to_string(), &item_trait.attrs);
+                    for trait_item in &item_trait.items {
+                        if let syn::TraitItem::Fn(method) = trait_item {
+                            if let Some(block) = &method.default {
+                                let body_scope =
+                                    trait_scope.child(&method.sig.ident.to_string(), &method.attrs);
+                                modules.extend(Self::parse_block_items(
+                                    block,
+                                    &body_scope,
+                                    analysis,
+                                ));
+                            }
+                        }
+                    }
+                }
+                Item::Type(item_type) => {
+                    analysis
+                        .type_aliases
+                        .push(Self::parse_type_alias(item_type, scope));
+                }
+                Item::Const(item_const) => {
+                    analysis
+                        .constants
+                        .push(Self::parse_const(item_const, scope));
+                }
+                Item::Static(item_static) => {
+                    analysis
+                        .statics
+                        .push(Self::parse_static(item_static, scope));
+                }
+                Item::Macro(item_macro) => {
+                    analysis.macros.extend(Self::parse_macro(item_macro, scope));
+                }
+                Item::Use(item_use) => {
+                    analysis.imports.extend(Self::parse_use(item_use, scope));
+                }
+                _ => {}
+            }
+        }
+
+        modules
+    }
+
+    /// Analyzes items declared anywhere inside a function body, including
+    /// nested blocks, `if`/`match` arms, closures and async blocks. They are
+    /// scoped under the function's path, so `fn run() { struct Guard; }`
+    /// yields `run::Guard`.
+    fn parse_block_items(
+        block: &syn::Block,
+        scope: &Scope,
+        analysis: &mut RustAnalysis,
+    ) -> Vec<ModuleInfo> {
+        let mut collector = BlockItems::default();
+        collector.visit_block(block);
+        Self::parse_items(collector.items, scope, analysis)
+    }
+
+    fn parse_module(item: &ItemMod, scope: &Scope, analysis: &mut RustAnalysis) -> ModuleInfo {
+        let name = item.ident.to_string();
+        let module_scope = scope.child(&name, &item.attrs);
+
+        let (items_count, submodules) = match &item.content {
+            Some((_, items)) => (
+                items.len(),
+                Self::parse_items(items, &module_scope, analysis),
+            ),
+            None => (0, Vec::new()),
+        };
+
+        ModuleInfo {
+            name,
+            path: module_scope.path,
+            visibility: Self::parse_visibility(&item.vis),
+            items_count,
+            is_inline: item.content.is_some(),
+            submodules,
+            attributes: Self::notable_attributes(&item.attrs),
+            cfg: scope.cfg(&item.attrs),
+            documentation: Self::extract_doc_comments(&item.attrs),
+            span: SourceSpan::of(item),
+        }
+    }
+
+    fn parse_function(item: &ItemFn, scope: &Scope) -> FunctionInfo {
+        Self::parse_signature(
+            &item.sig,
+            Some(&item.block),
+            &item.vis,
+            &item.attrs,
+            scope,
+            SourceSpan::of(item),
+        )
+    }
+
+    fn parse_signature(
+        sig: &Signature,
+        body: Option<&Block>,
+        vis: &Visibility,
+        attrs: &[Attribute],
+        scope: &Scope,
+        span: SourceSpan,
+    ) -> FunctionInfo {
+        let name = sig.ident.to_string();
+
+        FunctionInfo {
+            path: Self::qualify(&scope.path, &name),
+            name,
+            visibility: Self::parse_visibility(vis),
+            is_async: sig.asyncness.is_some(),
+            is_const: sig.constness.is_some(),
+            is_unsafe: sig.unsafety.is_some(),
+            abi: sig.abi.as_ref().map(|abi| {
+                abi.name
+                    .as_ref()
+                    .map(|name| name.value())
+                    .unwrap_or_default()
+            }),
+            generics: Self::parse_generics(&sig.generics),
+            parameters: Self::parse_parameters(sig),
+            return_type: Self::parse_return_type(sig),
+            signature: Self::format_signature(sig, vis),
+            attributes: Self::notable_attributes(attrs),
+            cfg: scope.cfg(attrs),
+            documentation: Self::extract_doc_comments(attrs),
+            span,
+            metrics: Self::function_metrics(sig, body, span),
+            test: Self::test_info(attrs),
+        }
+    }
+
+    /// Test harness attributes, for functions marked `#[test]`, `#[bench]`
+    /// or a runtime's test macro such as `#[tokio::test]`.
+    fn test_info(attrs: &[Attribute]) -> Option<TestInfo> {
+        let harness = attrs.iter().map(Attribute::path).find(|path| {
+            path.is_ident("test")
+                || path.is_ident("bench")
+                || (path.segments.len() > 1
+                    && path
+                        .segments
+                        .last()
+                        .is_some_and(|segment| segment.ident == "test"))
+        })?;
+
+        Some(TestInfo {
+            harness: harness.to_token_stream().to_string().replace(' ', ""),
+            should_panic: attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("should_panic")),
+            ignored: attrs.iter().any(|attr| attr.path().is_ident("ignore")),
+        })
+    }
+
+    fn function_metrics(
+        sig: &Signature,
+        body: Option<&Block>,
+        span: SourceSpan,
+    ) -> FunctionMetrics {
+        let mut visitor = MetricsVisitor::default();
+        if let Some(body) = body {
+            visitor.metrics.cyclomatic_complexity = 1;
+            visitor.visit_block(body);
+        }
+
+        FunctionMetrics {
+            lines: span.end_line + 1 - span.start_line,
+            parameters: sig
+                .inputs
+                .iter()
+                .filter(|input| matches!(input, syn::FnArg::Typed(_)))
+                .count(),
+            ..visitor.metrics
+        }
+    }
+
+    /// Doc tests in the documentation of every item of the file.
+    fn doc_tests(analysis: &RustAnalysis) -> Vec<DocTestInfo> {
+        fn push_modules<'a>(
+            modules: &'a [ModuleInfo],
+            documented: &mut Vec<(&'a str, usize, &'a Option<String>)>,
+        ) {
+            for module in modules {
+                documented.push((&module.path, module.span.start_line, &module.documentation));
+                push_modules(&module.submodules, documented);
+            }
+        }
+
+        let mut documented = vec![("", 1, &analysis.documentation)];
+        push_modules(&analysis.modules, &mut documented);
+        let functions = analysis
+            .functions
+            .iter()
+            .chain(analysis.implementations.iter().flat_map(|imp| &imp.methods))
+            .chain(
+                analysis
+                    .traits
+                    .iter()
+                    .flat_map(|trait_info| &trait_info.methods)
+                    .map(|method| &method.function),
+            );
+        for function in functions {
+            documented.push((
+                &function.path,
+                function.span.start_line,
+                &function.documentation,
+            ));
+        }
+        for info in &analysis.structs {
+            documented.push((&info.path, info.span.start_line, &info.documentation));
+        }
+        for info in &analysis.enums {
+            documented.push((&info.path, info.span.start_line, &info.documentation));
+        }
+        for info in &analysis.traits {
+            documented.push((&info.path, info.span.start_line, &info.documentation));
+        }
+        for info in &analysis.type_aliases {
+            documented.push((&info.path, info.span.start_line, &info.documentation));
+        }
+        for info in &analysis.constants {
+            documented.push((&info.path, info.span.start_line, &info.documentation));
+        }
+        for info in &analysis.statics {
+            documented.push((&info.path, info.span.start_line, &info.documentation));
+        }
+        for info in &analysis.macros {
+            documented.push((&info.path, info.span.start_line, &info.documentation));
+        }
+        documented.sort_by_key(|(_, line, _)| *line);
 
-        let mut analysis = RustAnalysis {
-            file_path: file_path.to_string(),
-            modules: Vec::new(),
-            functions: Vec::new(),
-            structs: Vec::new(),
-            enums: Vec::new(),
-            implementations: Vec::new(),
-            ast_summary: String::new(),
-        };
+        documented
+            .into_iter()
+            .filter_map(|(item, line, documentation)| Some((item, line, documentation.as_deref()?)))
+            .flat_map(|(item, line, documentation)| {
+                Self::doc_test_blocks(documentation)
+                    .into_iter()
+                    .map(move |attributes| DocTestInfo {
+                        item: item.to_string(),
+                        line,
+                        attributes,
+                    })
+            })
+            .collect()
+    }
 
-        // Analyze each item in the file
-        for item in &syntax_tree.items {
-            match item {
-                Item::Mod(item_mod) => {
-                    analysis.modules.push(Self::parse_module(item_mod));
-                }
-                Item::Fn(item_fn) => {
-                    analysis.functions.push(Self::parse_function(item_fn));
-                }
-                Item::Struct(item_struct) => {
-                    analysis.structs.push(Self::parse_struct(item_struct));
-                }
-                Item::Enum(item_enum) => {
-                    analysis.enums.push(Self::parse_enum(item_enum));
-                }
-                Item::Impl(item_impl) => {
-                    analysis.implementations.push(Self::parse_impl(item_impl));
+    /// Rustdoc attributes of each code block of `documentation` that is run
+    /// as a doc test.
+    fn doc_test_blocks(documentation: &str) -> Vec<Vec<String>> {
+        const RUSTDOC_ATTRIBUTES: [&str; 4] = ["no_run", "should_panic", "ignore", "compile_fail"];
+
+        let mut blocks = Vec::new();
+        let mut fence: Option<&str> = None;
+        for line in documentation.lines() {
+            let line = line.trim_start();
+            if let Some(open) = fence {
+                if line.starts_with(open) && line[open.len()..].trim().is_empty() {
+                    fence = None;
                 }
-                _ => {}
+                continue;
+            }
+
+            let marker_len = line
+                .find(|c: char| c != '`' && c != '~')
+                .unwrap_or(line.len());
+            let marker = &line[..marker_len];
+            let is_fence = marker_len >= 3
+                && (marker.bytes().all(|b| b == b'`') || marker.bytes().all(|b| b == b'~'));
+            if !is_fence {
+                continue;
+            }
+            fence = Some(marker);
+
+            let tokens: Vec<&str> = line[marker_len..]
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|token| !token.is_empty())
+                .collect();
+            let is_rust = tokens.iter().all(|token| {
+                *token == "rust"
+                    || RUSTDOC_ATTRIBUTES.contains(token)
+                    || token.starts_with("edition")
+                    || token.starts_with("ignore-")
+                    || matches!(*token, "test_harness" | "standalone_crate")
+            });
+            if is_rust {
+                blocks.push(
+                    tokens
+                        .into_iter()
+                        .filter(|token| RUSTDOC_ATTRIBUTES.contains(token))
+                        .map(str::to_string)
+                        .collect(),
+                );
             }
         }
+        blocks
+    }
 
-        analysis.ast_summary = Self::generate_ast_summary(&analysis);
+    fn file_metrics(analysis: &RustAnalysis, content: &str) -> FileMetrics {
+        let functions = analysis
+            .functions
+            .iter()
+            .chain(analysis.implementations.iter().flat_map(|imp| &imp.methods))
+            .chain(
+                analysis
+                    .traits
+                    .iter()
+                    .flat_map(|trait_info| &trait_info.methods)
+                    .filter(|method| method.has_default)
+                    .map(|method| &method.function),
+            );
 
-        Ok(analysis)
+        let mut metrics = FileMetrics {
+            lines: content.lines().count(),
+            ..FileMetrics::default()
+        };
+        for function in functions {
+            let function_metrics = &function.metrics;
+            metrics.functions += 1;
+            metrics.total_complexity += function_metrics.cyclomatic_complexity;
+            metrics.max_complexity = metrics
+                .max_complexity
+                .max(function_metrics.cyclomatic_complexity);
+            metrics.max_nesting = metrics.max_nesting.max(function_metrics.max_nesting);
+            metrics.match_arms += function_metrics.match_arms;
+            metrics.async_functions += usize::from(function.is_async);
+            metrics.await_points += function_metrics.await_points;
+            metrics.unsafe_functions += usize::from(function.is_unsafe);
+            metrics.unsafe_blocks += function_metrics.unsafe_blocks;
+        }
+        metrics
     }
 
-    fn parse_module(item: &ItemMod) -> ModuleInfo {
-        let items_count = item
-            .content
-            .as_ref()
-            .map(|(_, items)| items.len())
-            .unwrap_or(0);
+    fn parse_generics(generics: &syn::Generics) -> GenericsInfo {
+        let params = generics
+            .params
+            .iter()
+            .map(|param| match param {
+                GenericParam::Lifetime(lifetime) => GenericParamInfo {
+                    name: lifetime.lifetime.to_string(),
+                    kind: GenericParamKind::Lifetime,
+                    bounds: lifetime.bounds.iter().map(|b| b.to_string()).collect(),
+                    default: None,
+                    const_type: None,
+                },
+                GenericParam::Type(ty) => GenericParamInfo {
+                    name: ty.ident.to_string(),
+                    kind: GenericParamKind::Type,
+                    bounds: ty.bounds.iter().map(Self::format_bound).collect(),
+                    default: ty.default.as_ref().map(Self::format_type),
+                    const_type: None,
+                },
+                GenericParam::Const(constant) => GenericParamInfo {
+                    name: constant.ident.to_string(),
+                    kind: GenericParamKind::Const,
+                    bounds: Vec::new(),
+                    default: constant
+                        .default
+                        .as_ref()
+                        .map(|expr| expr.to_token_stream().to_string()),
+                    const_type: Some(Self::format_type(&constant.ty)),
+                },
+            })
+            .collect();
 
-        ModuleInfo {
-            name: item.ident.to_string(),
-            visibility: Self::parse_visibility(&item.vis),
-            items_count,
+        let where_predicates = generics
+            .where_clause
+            .iter()
+            .flat_map(|clause| &clause.predicates)
+            .map(Self::format_where_predicate)
+            .collect();
+
+        GenericsInfo {
+            params,
+            where_predicates,
         }
     }
 
-    fn parse_function(item: &ItemFn) -> FunctionInfo {
-        let sig = &item.sig;
+    /// Traits named in `#[derive(...)]` attributes, in order.
+    fn derives(attrs: &[Attribute]) -> Vec<String> {
+        attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("derive"))
+            .filter_map(|attr| {
+                attr.parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+                )
+                .ok()
+            })
+            .flatten()
+            .map(|path| path.to_token_stream().to_string().replace(' ', ""))
+            .collect()
+    }
 
-        FunctionInfo {
-            name: sig.ident.to_string(),
+    /// Attributes listed in [`NOTABLE_ATTRIBUTES`], plus runtime entry points
+    /// and test harnesses such as `#[tokio::main]` or `#[tokio::test]`.
+    fn notable_attributes(attrs: &[Attribute]) -> Vec<String> {
+        attrs
+            .iter()
+            .filter(|attr| {
+                let path = attr.path();
+                let is_entry_point = path.segments.len() > 1
+                    && path
+                        .segments
+                        .last()
+                        .is_some_and(|segment| segment.ident == "main" || segment.ident == "test");
+                let name = path.to_token_stream().to_string().replace(' ', "");
+                is_entry_point || NOTABLE_ATTRIBUTES.contains(&name.as_str())
+            })
+            .map(Self::format_attribute)
+            .collect()
+    }
+
+    /// Predicates of the `#[cfg(...)]` attributes in `attrs`.
+    fn cfg_predicates(attrs: &[Attribute]) -> Vec<String> {
+        attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"))
+            .filter_map(|attr| {
+                let formatted = Self::format_attribute(attr);
+                formatted
+                    .strip_prefix("#[cfg(")
+                    .and_then(|rest| rest.strip_suffix(")]"))
+                    .map(str::to_string)
+            })
+            .collect()
+    }
+
+    /// Joins a scope path and an item name with `::`.
+    fn qualify(scope: &str, name: &str) -> String {
+        if scope.is_empty() {
+            name.to_string()
+        } else {
+            format!("{scope}::{name}")
+        }
+    }
+
+    /// Name used to scope an impl's methods: the last path segment of the
+    /// self type (`Vec<T>` becomes `Vec`), or the whole type otherwise.
+    ///
+    /// Method paths such as [`FunctionInfo::path`] are built from it, so
+    /// `impl<T> Stack<T> { fn push() }` yields `Stack::push`.
+    pub fn type_name(ty: &syn::Type) -> String {
+        match ty {
+            syn::Type::Path(type_path) => type_path
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string())
+                .unwrap_or_default(),
+            syn::Type::Reference(reference) => Self::type_name(&reference.elem),
+            _ => ty.to_token_stream().to_string(),
+        }
+    }
+
+    fn parse_struct(item: &ItemStruct, scope: &Scope) -> StructInfo {
+        StructInfo {
+            name: item.ident.to_string(),
+            path: Self::qualify(&scope.path, &item.ident.to_string()),
             visibility: Self::parse_visibility(&item.vis),
-            is_async: sig.asyncness.is_some(),
-            parameters: Self::parse_parameters(sig),
-            return_type: Self::parse_return_type(sig),
+            generics: Self::parse_generics(&item.generics),
+            kind: Self::fields_kind(&item.fields),
+            fields: Self::parse_fields(&item.fields),
+            signature: Self::format_declaration(Item::Struct(ItemStruct {
+                attrs: Vec::new(),
+                fields: syn::Fields::Unit,
+                semi_token: Some(Default::default()),
+                ..item.clone()
+            })),
+            derives: Self::derives(&item.attrs),
+            attributes: Self::notable_attributes(&item.attrs),
+            cfg: scope.cfg(&item.attrs),
             documentation: Self::extract_doc_comments(&item.attrs),
+            span: SourceSpan::of(item),
         }
     }
 
-    fn parse_struct(item: &ItemStruct) -> StructInfo {
-        let fields = match &item.fields {
+    fn parse_fields(fields: &syn::Fields) -> Vec<FieldInfo> {
+        match fields {
             syn::Fields::Named(fields) => fields
                 .named
                 .iter()
                 .map(|f| FieldInfo {
                     name: f.ident.as_ref().unwrap().to_string(),
-                    field_type: f.ty.to_token_stream().to_string(),
+                    field_type: Self::format_type(&f.ty),
                     visibility: Self::parse_visibility(&f.vis),
+                    documentation: Self::extract_doc_comments(&f.attrs),
                 })
                 .collect(),
             syn::Fields::Unnamed(fields) => fields
@@ -261,52 +2062,88 @@ impl RustParser {
                 .enumerate()
                 .map(|(i, f)| FieldInfo {
                     name: format!("field_{i}"),
-                    field_type: f.ty.to_token_stream().to_string(),
+                    field_type: Self::format_type(&f.ty),
                     visibility: Self::parse_visibility(&f.vis),
+                    documentation: Self::extract_doc_comments(&f.attrs),
                 })
                 .collect(),
             syn::Fields::Unit => Vec::new(),
-        };
+        }
+    }
 
-        StructInfo {
-            name: item.ident.to_string(),
-            visibility: Self::parse_visibility(&item.vis),
-            fields,
-            documentation: Self::extract_doc_comments(&item.attrs),
+    fn fields_kind(fields: &syn::Fields) -> VariantKind {
+        match fields {
+            syn::Fields::Named(_) => VariantKind::Struct,
+            syn::Fields::Unnamed(_) => VariantKind::Tuple,
+            syn::Fields::Unit => VariantKind::Unit,
         }
     }
 
-    fn parse_enum(item: &ItemEnum) -> EnumInfo {
-        let variants = item.variants.iter().map(|v| v.ident.to_string()).collect();
+    fn parse_enum(item: &ItemEnum, scope: &Scope) -> EnumInfo {
+        let variants = item
+            .variants
+            .iter()
+            .map(|variant| VariantInfo {
+                name: variant.ident.to_string(),
+                kind: Self::fields_kind(&variant.fields),
+                fields: Self::parse_fields(&variant.fields),
+                discriminant: variant
+                    .discriminant
+                    .as_ref()
+                    .map(|(_, expr)| Self::format_expr(expr)),
+                attributes: variant
+                    .attrs
+                    .iter()
+                    .filter(|attr| !attr.path().is_ident("doc"))
+                    .map(Self::format_attribute)
+                    .collect(),
+                documentation: Self::extract_doc_comments(&variant.attrs),
+                span: SourceSpan::of(variant),
+            })
+            .collect();
 
         EnumInfo {
             name: item.ident.to_string(),
+            path: Self::qualify(&scope.path, &item.ident.to_string()),
             visibility: Self::parse_visibility(&item.vis),
+            generics: Self::parse_generics(&item.generics),
             variants,
+            signature: Self::format_declaration(Item::Enum(ItemEnum {
+                attrs: Vec::new(),
+                variants: Default::default(),
+                ..item.clone()
+            })),
+            derives: Self::derives(&item.attrs),
+            attributes: Self::notable_attributes(&item.attrs),
+            cfg: scope.cfg(&item.attrs),
             documentation: Self::extract_doc_comments(&item.attrs),
+            span: SourceSpan::of(item),
         }
     }
 
-    fn parse_impl(item: &ItemImpl) -> ImplInfo {
-        let target = item.self_ty.to_token_stream().to_string();
-        let trait_name = item
-            .trait_
-            .as_ref()
-            .map(|(_, path, _)| path.to_token_stream().to_string());
+    fn parse_impl(item: &ItemImpl, scope: &Scope) -> ImplInfo {
+        let target = Self::format_type(&item.self_ty);
+        let method_scope = scope.child(&Self::type_name(&item.self_ty), &item.attrs);
+        let trait_name = item.trait_.as_ref().map(|(_, path, _)| {
+            Self::format_type(&syn::Type::Path(syn::TypePath {
+                qself: None,
+                path: path.clone(),
+            }))
+        });
 
         let methods = item
             .items
             .iter()
             .filter_map(|item| {
                 if let syn::ImplItem::Fn(method) = item {
-                    Some(FunctionInfo {
-                        name: method.sig.ident.to_string(),
-                        visibility: Self::parse_visibility(&method.vis),
-                        is_async: method.sig.asyncness.is_some(),
-                        parameters: Self::parse_parameters(&method.sig),
-                        return_type: Self::parse_return_type(&method.sig),
-                        documentation: Self::extract_doc_comments(&method.attrs),
-                    })
+                    Some(Self::parse_signature(
+                        &method.sig,
+                        Some(&method.block),
+                        &method.vis,
+                        &method.attrs,
+                        &method_scope,
+                        SourceSpan::of(method),
+                    ))
                 } else {
                     None
                 }
@@ -315,8 +2152,244 @@ impl RustParser {
 
         ImplInfo {
             target,
+            module_path: scope.path.clone(),
             trait_name,
+            generics: Self::parse_generics(&item.generics),
+            signature: Self::format_declaration(Item::Impl(ItemImpl {
+                attrs: Vec::new(),
+                items: Vec::new(),
+                ..item.clone()
+            })),
+            methods,
+            attributes: Self::notable_attributes(&item.attrs),
+            cfg: scope.cfg(&item.attrs),
+            span: SourceSpan::of(item),
+        }
+    }
+
+    fn parse_trait(item: &ItemTrait, scope: &Scope) -> TraitInfo {
+        let trait_scope = scope.child(&item.ident.to_string(), &item.attrs);
+        let supertraits = item.supertraits.iter().map(Self::format_bound).collect();
+
+        let mut associated_types = Vec::new();
+        let mut associated_consts = Vec::new();
+        let mut methods = Vec::new();
+
+        for trait_item in &item.items {
+            match trait_item {
+                syn::TraitItem::Type(ty) => associated_types.push(AssociatedTypeInfo {
+                    name: ty.ident.to_string(),
+                    bounds: ty.bounds.iter().map(Self::format_bound).collect(),
+                    default: ty.default.as_ref().map(|(_, ty)| Self::format_type(ty)),
+                }),
+                syn::TraitItem::Const(constant) => associated_consts.push(AssociatedConstInfo {
+                    name: constant.ident.to_string(),
+                    const_type: Self::format_type(&constant.ty),
+                    has_default: constant.default.is_some(),
+                }),
+                syn::TraitItem::Fn(method) => {
+                    // Trait methods share the trait's visibility but are
+                    // declared without one
+                    let mut function = Self::parse_signature(
+                        &method.sig,
+                        method.default.as_ref(),
+                        &item.vis,
+                        &method.attrs,
+                        &trait_scope,
+                        SourceSpan::of(method),
+                    );
+                    function.signature =
+                        Self::format_signature(&method.sig, &Visibility::Inherited);
+                    methods.push(TraitMethodInfo {
+                        function,
+                        has_default: method.default.is_some(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        TraitInfo {
+            name: item.ident.to_string(),
+            path: trait_scope.path.clone(),
+            visibility: Self::parse_visibility(&item.vis),
+            is_unsafe: item.unsafety.is_some(),
+            supertraits,
+            associated_types,
+            associated_consts,
             methods,
+            attributes: Self::notable_attributes(&item.attrs),
+            cfg: scope.cfg(&item.attrs),
+            documentation: Self::extract_doc_comments(&item.attrs),
+            span: SourceSpan::of(item),
+        }
+    }
+
+    fn parse_type_alias(item: &ItemType, scope: &Scope) -> TypeAliasInfo {
+        TypeAliasInfo {
+            name: item.ident.to_string(),
+            path: Self::qualify(&scope.path, &item.ident.to_string()),
+            visibility: Self::parse_visibility(&item.vis),
+            target: Self::format_type(&item.ty),
+            generics: Self::parse_generics(&item.generics),
+            signature: Self::format_declaration(Item::Type(ItemType {
+                attrs: Vec::new(),
+                ..item.clone()
+            })),
+            attributes: Self::notable_attributes(&item.attrs),
+            cfg: scope.cfg(&item.attrs),
+            documentation: Self::extract_doc_comments(&item.attrs),
+            span: SourceSpan::of(item),
+        }
+    }
+
+    fn parse_const(item: &ItemConst, scope: &Scope) -> ConstInfo {
+        ConstInfo {
+            name: item.ident.to_string(),
+            path: Self::qualify(&scope.path, &item.ident.to_string()),
+            visibility: Self::parse_visibility(&item.vis),
+            const_type: Self::format_type(&item.ty),
+            value: Self::format_expr(&item.expr),
+            attributes: Self::notable_attributes(&item.attrs),
+            cfg: scope.cfg(&item.attrs),
+            documentation: Self::extract_doc_comments(&item.attrs),
+            span: SourceSpan::of(item),
+        }
+    }
+
+    fn parse_static(item: &ItemStatic, scope: &Scope) -> StaticInfo {
+        StaticInfo {
+            name: item.ident.to_string(),
+            path: Self::qualify(&scope.path, &item.ident.to_string()),
+            visibility: Self::parse_visibility(&item.vis),
+            static_type: Self::format_type(&item.ty),
+            is_mutable: matches!(item.mutability, syn::StaticMutability::Mut(_)),
+            attributes: Self::notable_attributes(&item.attrs),
+            cfg: scope.cfg(&item.attrs),
+            documentation: Self::extract_doc_comments(&item.attrs),
+            span: SourceSpan::of(item),
+        }
+    }
+
+    /// Extracts `macro_rules!` definitions; other macro invocations in item
+    /// position are ignored.
+    fn parse_macro(item: &ItemMacro, scope: &Scope) -> Option<MacroInfo> {
+        if !item.mac.path.is_ident("macro_rules") {
+            return None;
+        }
+        let name = item.ident.as_ref()?.to_string();
+
+        // Each rule is `(matcher) => {transcriber}`; count the top-level `=>`
+        let rules = item
+            .mac
+            .tokens
+            .clone()
+            .into_iter()
+            .filter(|token| matches!(token, proc_macro2::TokenTree::Punct(p) if p.as_char() == '>'))
+            .count();
+
+        Some(MacroInfo {
+            path: Self::qualify(&scope.path, &name),
+            name,
+            is_exported: item
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("macro_export")),
+            rules,
+            attributes: Self::notable_attributes(&item.attrs),
+            cfg: scope.cfg(&item.attrs),
+            documentation: Self::extract_doc_comments(&item.attrs),
+            span: SourceSpan::of(item),
+        })
+    }
+
+    fn parse_use(item: &ItemUse, scope: &Scope) -> Vec<UseInfo> {
+        let visibility = Self::parse_visibility(&item.vis);
+        let mut imports = Vec::new();
+        let prefix = if item.leading_colon.is_some() {
+            "::"
+        } else {
+            ""
+        };
+        Self::flatten_use_tree(&item.tree, prefix.to_string(), &visibility, &mut imports);
+        let cfg = scope.cfg(&item.attrs);
+        let span = SourceSpan::of(item);
+        for import in &mut imports {
+            import.module_path = scope.path.clone();
+            import.cfg = cfg.clone();
+            import.span = span;
+        }
+        imports
+    }
+
+    fn flatten_use_tree(
+        tree: &UseTree,
+        prefix: String,
+        visibility: &str,
+        imports: &mut Vec<UseInfo>,
+    ) {
+        let join = |name: &str| {
+            if prefix.is_empty() || prefix == "::" {
+                format!("{prefix}{name}")
+            } else {
+                format!("{prefix}::{name}")
+            }
+        };
+
+        match tree {
+            UseTree::Path(path) => Self::flatten_use_tree(
+                &path.tree,
+                join(&path.ident.to_string()),
+                visibility,
+                imports,
+            ),
+            UseTree::Name(name) => {
+                // `use foo::{self}` imports `foo` itself
+                let path = if name.ident == "self" && !prefix.is_empty() {
+                    prefix.clone()
+                } else {
+                    join(&name.ident.to_string())
+                };
+                imports.push(UseInfo {
+                    path,
+                    alias: None,
+                    is_glob: false,
+                    visibility: visibility.to_string(),
+                    module_path: String::new(),
+                    cfg: None,
+                    span: SourceSpan::default(),
+                });
+            }
+            UseTree::Rename(rename) => {
+                let path = if rename.ident == "self" && !prefix.is_empty() {
+                    prefix.clone()
+                } else {
+                    join(&rename.ident.to_string())
+                };
+                imports.push(UseInfo {
+                    path,
+                    alias: Some(rename.rename.to_string()),
+                    is_glob: false,
+                    visibility: visibility.to_string(),
+                    module_path: String::new(),
+                    cfg: None,
+                    span: SourceSpan::default(),
+                });
+            }
+            UseTree::Glob(_) => imports.push(UseInfo {
+                path: join("*"),
+                alias: None,
+                is_glob: true,
+                visibility: visibility.to_string(),
+                module_path: String::new(),
+                cfg: None,
+                span: SourceSpan::default(),
+            }),
+            UseTree::Group(group) => {
+                for tree in &group.items {
+                    Self::flatten_use_tree(tree, prefix.clone(), visibility, imports);
+                }
+            }
         }
     }
 
@@ -335,17 +2408,31 @@ impl RustParser {
             .iter()
             .map(|input| match input {
                 syn::FnArg::Receiver(receiver) => {
-                    if receiver.mutability.is_some() {
-                        "&mut self".to_string()
+                    let mutability = if receiver.mutability.is_some() {
+                        "mut "
                     } else {
-                        "&self".to_string()
+                        ""
+                    };
+                    match (&receiver.colon_token, &receiver.reference) {
+                        // `self: Box<Self>`, `mut self: Pin<&mut Self>`
+                        (Some(_), _) => {
+                            format!("{mutability}self: {}", Self::format_type(&receiver.ty))
+                        }
+                        (None, Some((_, lifetime))) => {
+                            let lifetime = lifetime
+                                .as_ref()
+                                .map(|lifetime| format!("{lifetime} "))
+                                .unwrap_or_default();
+                            format!("&{lifetime}{mutability}self")
+                        }
+                        (None, None) => format!("{mutability}self"),
                     }
                 }
                 syn::FnArg::Typed(typed) => {
                     format!(
                         "{}: {}",
                         typed.pat.to_token_stream(),
-                        typed.ty.to_token_stream()
+                        Self::format_type(&typed.ty)
                     )
                 }
             })
@@ -355,17 +2442,144 @@ impl RustParser {
     fn parse_return_type(sig: &Signature) -> Option<String> {
         match &sig.output {
             syn::ReturnType::Default => None,
-            syn::ReturnType::Type(_, ty) => Some(ty.to_token_stream().to_string()),
+            syn::ReturnType::Type(_, ty) => Some(Self::format_type(ty)),
+        }
+    }
+
+    /// Collapses a formatted, possibly multi-line signature into one line,
+    /// e.g. for inline code in markdown lists.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ai_context_gen::parser::RustParser;
+    ///
+    /// let signature = "pub fn merge<T>(\n    left: Vec<T>,\n    right: Vec<T>,\n) -> Vec<T>\nwhere\n    T: Ord,";
+    /// assert_eq!(
+    ///     RustParser::inline_signature(signature),
+    ///     "pub fn merge<T>(left: Vec<T>, right: Vec<T>) -> Vec<T> where T: Ord"
+    /// );
+    /// ```
+    pub fn inline_signature(signature: &str) -> String {
+        let mut inline = String::new();
+
+        for line in signature
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            let closes = line.starts_with([')', ']', '>', '}']);
+            if closes && inline.ends_with(',') {
+                inline.pop();
+            }
+            let opens = inline.ends_with(['(', '[', '<', '{']);
+            if !inline.is_empty() && !opens && !closes {
+                inline.push(' ');
+            }
+            inline.push_str(line);
         }
+
+        inline.trim_end_matches(',').to_string()
+    }
+
+    /// Formats a function signature with rustfmt-style layout, without a body.
+    fn format_signature(sig: &Signature, vis: &Visibility) -> String {
+        Self::format_declaration(Item::Fn(ItemFn {
+            attrs: Vec::new(),
+            vis: vis.clone(),
+            sig: sig.clone(),
+            block: Box::new(syn::parse_quote!({})),
+        }))
+    }
+
+    /// Pretty-prints an item whose body was emptied and strips the empty
+    /// body (`{}` or `;`), keeping any `where` clause.
+    fn format_declaration(item: Item) -> String {
+        let formatted = Self::unparse(item);
+        let trimmed = formatted.trim_end();
+        let trimmed = trimmed
+            .strip_suffix("{}")
+            .or_else(|| trimmed.strip_suffix(';'))
+            .unwrap_or(trimmed);
+        trimmed.trim_end().to_string()
+    }
+
+    fn format_expr(expr: &syn::Expr) -> String {
+        let formatted = Self::unparse(syn::parse_quote!(const __C: () = #expr;));
+        let formatted = formatted.trim();
+        let expr = formatted
+            .strip_prefix("const __C: () = ")
+            .and_then(|rest| rest.strip_suffix(';'))
+            .unwrap_or(formatted);
+        Self::inline_signature(expr)
+    }
+
+    fn format_attribute(attr: &Attribute) -> String {
+        let formatted = Self::unparse(Item::Struct(syn::parse_quote!(#attr struct __S;)));
+        let formatted = formatted.trim();
+        let attr = formatted
+            .strip_suffix("struct __S;")
+            .unwrap_or(formatted)
+            .trim();
+        Self::inline_signature(attr)
+    }
+
+    fn format_type(ty: &syn::Type) -> String {
+        let formatted = Self::unparse(syn::parse_quote!(type __T = #ty;));
+        let formatted = formatted.trim();
+        let ty = formatted
+            .strip_prefix("type __T = ")
+            .and_then(|rest| rest.strip_suffix(';'))
+            .unwrap_or(formatted);
+        Self::inline_signature(ty)
+    }
+
+    fn format_bound(bound: &syn::TypeParamBound) -> String {
+        let formatted = Self::unparse(syn::parse_quote!(fn __f<__T: #bound>() {}));
+        let formatted = Self::inline_signature(&formatted);
+        formatted
+            .strip_prefix("fn __f<__T: ")
+            .and_then(|rest| rest.strip_suffix(">() {}"))
+            .map(str::to_string)
+            .unwrap_or_else(|| bound.to_token_stream().to_string())
+    }
+
+    fn format_where_predicate(predicate: &syn::WherePredicate) -> String {
+        let formatted = Self::unparse(syn::parse_quote!(fn __f() where #predicate {}));
+        let formatted = Self::inline_signature(&formatted);
+        formatted
+            .strip_prefix("fn __f() where ")
+            .and_then(|rest| rest.strip_suffix(" {}"))
+            .map(|predicate| predicate.trim_end_matches(',').to_string())
+            .unwrap_or_else(|| predicate.to_token_stream().to_string())
+    }
+
+    fn unparse(item: Item) -> String {
+        prettyplease::unparse(&syn::File {
+            shebang: None,
+            attrs: Vec::new(),
+            items: vec![item],
+        })
     }
 
     fn extract_doc_comments(attrs: &[syn::Attribute]) -> Option<String> {
         let mut doc_comments = Vec::new();
 
         for attr in attrs {
-            if attr.path().is_ident("doc") {
-                if let Ok(syn::Lit::Str(lit_str)) = attr.parse_args() {
-                    doc_comments.push(lit_str.value());
+            // `/// text` desugars to `#[doc = " text"]`
+            if let syn::Meta::NameValue(syn::MetaNameValue {
+                path,
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit_str),
+                        ..
+                    }),
+                ..
+            }) = &attr.meta
+            {
+                if path.is_ident("doc") {
+                    let line = lit_str.value();
+                    doc_comments.push(line.strip_prefix(' ').unwrap_or(&line).to_string());
                 }
             }
         }
@@ -384,11 +2598,13 @@ impl RustParser {
 
         if !analysis.modules.is_empty() {
             summary.push_str("## Modules\n");
-            for module in &analysis.modules {
+            let mut stack: Vec<&ModuleInfo> = analysis.modules.iter().rev().collect();
+            while let Some(module) = stack.pop() {
                 summary.push_str(&format!(
                     "- `{}` ({}) - {} items\n",
-                    module.name, module.visibility, module.items_count
+                    module.path, module.visibility, module.items_count
                 ));
+                stack.extend(module.submodules.iter().rev());
             }
             summary.push('\n');
         }
@@ -398,7 +2614,7 @@ impl RustParser {
             for struct_info in &analysis.structs {
                 summary.push_str(&format!(
                     "- `{}` ({}) - {} fields\n",
-                    struct_info.name,
+                    struct_info.path,
                     struct_info.visibility,
                     struct_info.fields.len()
                 ));
@@ -411,7 +2627,7 @@ impl RustParser {
             for enum_info in &analysis.enums {
                 summary.push_str(&format!(
                     "- `{}` ({}) - {} variants\n",
-                    enum_info.name,
+                    enum_info.path,
                     enum_info.visibility,
                     enum_info.variants.len()
                 ));
@@ -426,7 +2642,7 @@ impl RustParser {
                 summary.push_str(&format!(
                     "- `{}{}{}` ({})\n",
                     async_marker,
-                    func.name,
+                    func.path,
                     if func.parameters.is_empty() {
                         "()"
                     } else {
@@ -448,8 +2664,217 @@ impl RustParser {
                     .unwrap_or_default();
                 summary.push_str(&format!("- `impl {}{}`\n", trait_part, impl_info.target));
             }
+            summary.push('\n');
+        }
+
+        if !analysis.traits.is_empty() {
+            summary.push_str("## Traits\n");
+            for trait_info in &analysis.traits {
+                summary.push_str(&format!(
+                    "- `{}` ({}) - {} methods\n",
+                    trait_info.path,
+                    trait_info.visibility,
+                    trait_info.methods.len()
+                ));
+            }
+            summary.push('\n');
+        }
+
+        if !analysis.type_aliases.is_empty() {
+            summary.push_str("## Type Aliases\n");
+            for alias in &analysis.type_aliases {
+                summary.push_str(&format!(
+                    "- `{}` ({}) = `{}`\n",
+                    alias.path, alias.visibility, alias.target
+                ));
+            }
+            summary.push('\n');
+        }
+
+        if !analysis.constants.is_empty() || !analysis.statics.is_empty() {
+            summary.push_str("## Constants and Statics\n");
+            for constant in &analysis.constants {
+                summary.push_str(&format!(
+                    "- `const {}: {}` ({})\n",
+                    constant.path, constant.const_type, constant.visibility
+                ));
+            }
+            for static_info in &analysis.statics {
+                let mutability = if static_info.is_mutable { "mut " } else { "" };
+                summary.push_str(&format!(
+                    "- `static {}{}: {}` ({})\n",
+                    mutability, static_info.path, static_info.static_type, static_info.visibility
+                ));
+            }
+            summary.push('\n');
+        }
+
+        if !analysis.macros.is_empty() {
+            summary.push_str("## Macros\n");
+            for macro_info in &analysis.macros {
+                summary.push_str(&format!(
+                    "- `{}!` - {} rules{}\n",
+                    macro_info.path,
+                    macro_info.rules,
+                    if macro_info.is_exported {
+                        " (exported)"
+                    } else {
+                        ""
+                    }
+                ));
+            }
+            summary.push('\n');
+        }
+
+        if !analysis.imports.is_empty() {
+            summary.push_str(&format!("## Imports\n- {} paths\n", analysis.imports.len()));
         }
 
         summary
     }
 }
+
+/// Collects the items declared in a function body, at any expression depth.
+/// Items nested in those items are left to [`RustParser::parse_items`].
+#[derive(Default)]
+struct BlockItems<'ast> {
+    items: Vec<&'ast Item>,
+}
+
+impl<'ast> Visit<'ast> for BlockItems<'ast> {
+    fn visit_item(&mut self, item: &'ast Item) {
+        self.items.push(item);
+    }
+}
+
+/// Counts the figures of [`FunctionMetrics`] that depend on a function body.
+#[derive(Default)]
+struct MetricsVisitor {
+    metrics: FunctionMetrics,
+    depth: usize,
+}
+
+impl MetricsVisitor {
+    /// Visits a body one nesting level deeper.
+    fn nested(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        self.metrics.max_nesting = self.metrics.max_nesting.max(self.depth);
+        visit(self);
+        self.depth -= 1;
+    }
+}
+
+impl<'ast> Visit<'ast> for MetricsVisitor {
+    fn visit_item(&mut self, _item: &'ast Item) {
+        // Nested items are measured on their own
+    }
+
+    fn visit_expr_if(&mut self, expr: &'ast syn::ExprIf) {
+        self.metrics.cyclomatic_complexity += 1;
+        self.visit_expr(&expr.cond);
+        self.nested(|visitor| visitor.visit_block(&expr.then_branch));
+
+        // `else if` continues the chain at the same level
+        match expr.else_branch.as_ref().map(|(_, branch)| &**branch) {
+            Some(syn::Expr::If(else_if)) => self.visit_expr_if(else_if),
+            Some(branch) => self.nested(|visitor| visitor.visit_expr(branch)),
+            None => {}
+        }
+    }
+
+    fn visit_expr_match(&mut self, expr: &'ast syn::ExprMatch) {
+        self.metrics.match_arms += expr.arms.len();
+        self.metrics.cyclomatic_complexity += expr.arms.len().saturating_sub(1);
+        self.visit_expr(&expr.expr);
+        self.nested(|visitor| {
+            for arm in &expr.arms {
+                visitor.visit_arm(arm);
+            }
+        });
+    }
+
+    fn visit_arm(&mut self, arm: &'ast syn::Arm) {
+        if arm.guard.is_some() {
+            self.metrics.cyclomatic_complexity += 1;
+        }
+        visit::visit_arm(self, arm);
+    }
+
+    fn visit_expr_while(&mut self, expr: &'ast syn::ExprWhile) {
+        self.metrics.cyclomatic_complexity += 1;
+        self.visit_expr(&expr.cond);
+        self.nested(|visitor| visitor.visit_block(&expr.body));
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'ast syn::ExprForLoop) {
+        self.metrics.cyclomatic_complexity += 1;
+        self.visit_expr(&expr.expr);
+        self.nested(|visitor| visitor.visit_block(&expr.body));
+    }
+
+    fn visit_expr_loop(&mut self, expr: &'ast syn::ExprLoop) {
+        self.nested(|visitor| visitor.visit_block(&expr.body));
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        if local
+            .init
+            .as_ref()
+            .is_some_and(|init| init.diverge.is_some())
+        {
+            self.metrics.cyclomatic_complexity += 1;
+        }
+        visit::visit_local(self, local);
+    }
+
+    fn visit_expr_binary(&mut self, expr: &'ast syn::ExprBinary) {
+        if matches!(expr.op, syn::BinOp::And(_) | syn::BinOp::Or(_)) {
+            self.metrics.cyclomatic_complexity += 1;
+        }
+        visit::visit_expr_binary(self, expr);
+    }
+
+    fn visit_expr_try(&mut self, expr: &'ast syn::ExprTry) {
+        self.metrics.cyclomatic_complexity += 1;
+        visit::visit_expr_try(self, expr);
+    }
+
+    fn visit_expr_await(&mut self, expr: &'ast syn::ExprAwait) {
+        self.metrics.await_points += 1;
+        visit::visit_expr_await(self, expr);
+    }
+
+    fn visit_expr_unsafe(&mut self, expr: &'ast syn::ExprUnsafe) {
+        self.metrics.unsafe_blocks += 1;
+        visit::visit_expr_unsafe(self, expr);
+    }
+}
+
+/// Whether `attr` is a `#[cfg(...)]` limiting the item to test builds, like
+/// `#[cfg(test)]` or `#[cfg(all(test, feature = "slow"))]`.
+pub(crate) fn is_cfg_test(attr: &Attribute) -> bool {
+    attr.path().is_ident("cfg")
+        && attr
+            .parse_args::<Meta>()
+            .is_ok_and(|predicate| requires_test(&predicate))
+}
+
+/// Whether a `cfg` predicate as recorded by the parser (e.g.
+/// [`FunctionInfo::cfg`]) limits an item to test builds.
+pub(crate) fn is_test_only(cfg: &Option<String>) -> bool {
+    cfg.as_deref()
+        .and_then(|cfg| syn::parse_str::<Meta>(cfg).ok())
+        .is_some_and(|predicate| requires_test(&predicate))
+}
+
+/// Whether `test` appears in a `cfg` predicate outside of `not(...)`, as in
+/// `test`, `all(unix, test)` or `any(test, feature = "testing")`.
+fn requires_test(predicate: &Meta) -> bool {
+    match predicate {
+        Meta::Path(path) => path.is_ident("test"),
+        Meta::List(list) if list.path.is_ident("all") || list.path.is_ident("any") => list
+            .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            .is_ok_and(|predicates| predicates.iter().any(requires_test)),
+        _ => false,
+    }
+}
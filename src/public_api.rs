@@ -0,0 +1,822 @@
+//! Public API extraction for the AI Context Generator.
+//!
+//! This module walks the resolved [`ModuleTree`] of each library crate and
+//! keeps only the items a downstream crate can name: `pub` items in modules
+//! reachable through `pub mod` declarations, plus whatever `pub use`
+//! re-exports bring into those modules. The result is rendered as a
+//! rustdoc-like stub file with signatures and documentation but no bodies.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::module_tree::{qualify, ModuleNode, ModuleTree, PathResolver, ResolvedPath, RootKind};
+use crate::parser::{
+    is_test_only, EnumInfo, FunctionInfo, ImplInfo, ModuleInfo, ParsedSources, RustAnalysis,
+    RustParser, StructInfo, TraitInfo, VariantKind,
+};
+use crate::scanner::ScanResult;
+
+/// Longest constant value shown in a stub; longer values are left out.
+const MAX_CONST_VALUE_LEN: usize = 40;
+
+/// Public API of every library crate in a scan result.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::module_tree::ModuleTree;
+/// use ai_context_gen::public_api::PublicApi;
+/// use ai_context_gen::{Config, RepositoryScanner};
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// let scan_result = RepositoryScanner::new(Config::default()).scan().await?;
+/// let tree = ModuleTree::resolve(&scan_result);
+///
+/// for api in PublicApi::extract(&scan_result, &tree).crates {
+///     println!("{}:\n{}", api.target, api.stub);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PublicApi {
+    /// One entry per library root, in module tree order.
+    pub crates: Vec<CrateApi>,
+}
+
+/// Public API of a single library crate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrateApi {
+    /// Name of the package the library belongs to, if it lies inside one.
+    pub package: Option<String>,
+
+    /// Library target name (e.g., "ai_context_gen").
+    pub target: String,
+
+    /// Rust stub of the public items, with signatures and docs but no bodies.
+    pub stub: String,
+}
+
+impl PublicApi {
+    /// Extracts the public API of every library root in `module_tree`.
+    ///
+    /// Files that fail to parse contribute the items that could be recovered
+    /// (see [`RustParser::parse_rust_file_recovering`]) rather than aborting
+    /// the extraction.
+    pub fn extract(scan_result: &ScanResult, module_tree: &ModuleTree) -> Self {
+        Self::from_sources(module_tree, &ParsedSources::parse(scan_result))
+    }
+
+    /// Extracts the public API of every library root in `module_tree` from
+    /// already parsed sources.
+    pub fn from_sources(module_tree: &ModuleTree, sources: &ParsedSources) -> Self {
+        let paths = PathResolver::new(module_tree);
+        let crates = module_tree
+            .roots
+            .iter()
+            .enumerate()
+            .filter(|(_, root)| root.kind == RootKind::Lib)
+            .filter_map(|(position, root)| {
+                let file = root.module.file.as_deref()?;
+                let analysis = sources.get(file)?;
+
+                let mut index = ModuleIndex {
+                    modules: BTreeMap::new(),
+                    paths: &paths,
+                    root: position,
+                };
+                index.insert(&root.module, analysis, String::new(), true, None, sources);
+
+                let mut writer = StubWriter::new(&index);
+                writer.write_crate(analysis);
+
+                Some(CrateApi {
+                    package: root.package.clone(),
+                    target: root.target.clone(),
+                    stub: writer.out,
+                })
+            })
+            .collect();
+
+        Self { crates }
+    }
+}
+
+/// A module of the crate being rendered, located within a parsed file.
+struct ModuleEntry<'a> {
+    /// Analysis of the file declaring the module's items.
+    analysis: &'a RustAnalysis,
+
+    /// Path of the module relative to that file, empty for a file module.
+    prefix: String,
+
+    /// Whether the module is reachable from the crate root through `pub mod`.
+    is_public: bool,
+
+    /// Documentation of the declaration and the module body.
+    documentation: Option<String>,
+
+    /// Names of the child modules, in declaration order.
+    children: Vec<String>,
+}
+
+/// Every module of a crate, keyed by path (e.g., "crate::parser").
+struct ModuleIndex<'a> {
+    modules: BTreeMap<String, ModuleEntry<'a>>,
+    /// Resolver for `use` paths, and the crate's index in it.
+    paths: &'a PathResolver,
+    root: usize,
+}
+
+impl<'a> ModuleIndex<'a> {
+    /// Records `node` and its descendants. `analysis` is the file declaring
+    /// the module's items and `prefix` the module's path within it.
+    fn insert(
+        &mut self,
+        node: &ModuleNode,
+        analysis: &'a RustAnalysis,
+        prefix: String,
+        is_public: bool,
+        declaration: Option<&ModuleInfo>,
+        sources: &'a ParsedSources,
+    ) {
+        let documentation = join_docs(
+            declaration.and_then(|module| module.documentation.as_deref()),
+            if prefix.is_empty() {
+                analysis.documentation.as_deref()
+            } else {
+                None
+            },
+        );
+
+        for child in &node.children {
+            let child_prefix = qualify(&prefix, &child.name);
+            let child_declaration = find_module(&analysis.modules, &child_prefix);
+            let child_is_public = is_public
+                && child_declaration.is_some_and(|module| module.visibility == "pub")
+                && !child_declaration.is_some_and(|module| is_test_only(&module.cfg));
+
+            if child.is_inline {
+                self.insert(
+                    child,
+                    analysis,
+                    child_prefix,
+                    child_is_public,
+                    child_declaration,
+                    sources,
+                );
+            } else if let Some(child_analysis) =
+                child.file.as_deref().and_then(|file| sources.get(file))
+            {
+                self.insert(
+                    child,
+                    child_analysis,
+                    String::new(),
+                    child_is_public,
+                    child_declaration,
+                    sources,
+                );
+            }
+        }
+
+        self.modules.insert(
+            node.path.clone(),
+            ModuleEntry {
+                analysis,
+                prefix,
+                is_public,
+                documentation,
+                children: node
+                    .children
+                    .iter()
+                    .map(|child| child.name.clone())
+                    .collect(),
+            },
+        );
+    }
+
+    /// Resolves a `use` path written in module `from` to the module it
+    /// points into and the final segment. Returns `None` for paths into
+    /// other crates.
+    fn resolve(&self, from: &str, path: &str) -> Option<(String, String)> {
+        let segments: Vec<String> = path.split("::").map(str::to_string).collect();
+        let ResolvedPath::Crate(root, path) = self.paths.absolute(self.root, from, &segments)?
+        else {
+            return None;
+        };
+        let (module, last) = path.rsplit_once("::")?;
+        if root != self.root || !self.modules.contains_key(module) {
+            return None;
+        }
+        Some((module.to_string(), last.to_string()))
+    }
+
+    /// Resolves the type an impl in module `from` targets to the module
+    /// declaring it and its name, looking through the `use` declarations of
+    /// `module` for bare names that are not declared there.
+    fn resolve_type(
+        &self,
+        from: &str,
+        module: &ModuleEntry,
+        target: &str,
+    ) -> Option<(String, String)> {
+        let path = impl_target_path(target);
+        if path.contains("::") {
+            return self.resolve(from, path);
+        }
+
+        let imports: Vec<_> = module
+            .analysis
+            .imports
+            .iter()
+            .filter(|import| import.module_path == module.prefix)
+            .collect();
+        let named = imports.iter().find(|import| {
+            !import.is_glob
+                && import
+                    .alias
+                    .as_deref()
+                    .or_else(|| import.path.rsplit("::").next())
+                    == Some(path)
+        });
+        if let Some(import) = named {
+            return self.resolve(from, &import.path);
+        }
+        if declares_type(module, path) {
+            return Some((from.to_string(), path.to_string()));
+        }
+
+        imports
+            .iter()
+            .filter(|import| import.is_glob)
+            .filter_map(|import| self.resolve(from, &import.path))
+            .map(|(target, _)| target)
+            .find(|target| {
+                self.modules
+                    .get(target)
+                    .is_some_and(|module| declares_type(module, path))
+            })
+            .map(|target| (target, path.to_string()))
+    }
+}
+
+/// Renders modules of a [`ModuleIndex`] as Rust stubs.
+struct StubWriter<'a, 'b> {
+    index: &'b ModuleIndex<'a>,
+    /// Impl blocks keyed by the module and name of the type they target.
+    impls: Vec<((String, String), &'a ImplInfo)>,
+    out: String,
+    depth: usize,
+    /// Modules being rendered, to stop re-export cycles.
+    visiting: HashSet<String>,
+}
+
+impl<'a, 'b> StubWriter<'a, 'b> {
+    fn new(index: &'b ModuleIndex<'a>) -> Self {
+        let impls = index
+            .modules
+            .iter()
+            .flat_map(|(path, module)| {
+                module
+                    .analysis
+                    .implementations
+                    .iter()
+                    .filter(|impl_info| {
+                        impl_info.module_path == module.prefix && !is_test_only(&impl_info.cfg)
+                    })
+                    .filter_map(|impl_info| {
+                        let target = index.resolve_type(path, module, &impl_info.target)?;
+                        Some((target, impl_info))
+                    })
+            })
+            .collect();
+
+        Self {
+            index,
+            impls,
+            out: String::new(),
+            depth: 0,
+            visiting: HashSet::new(),
+        }
+    }
+
+    fn write_crate(&mut self, root: &RustAnalysis) {
+        if let Some(documentation) = &root.documentation {
+            self.write_docs("//!", documentation);
+            self.line("");
+        }
+
+        // Exported macros live at the crate root wherever they are defined
+        let index = self.index;
+        let mut macros = Vec::new();
+        for module in index.modules.values() {
+            macros.extend(module.analysis.macros.iter().filter(|macro_info| {
+                macro_info.is_exported && in_module(&macro_info.path, &module.prefix)
+            }));
+        }
+        for macro_info in macros {
+            if let Some(documentation) = &macro_info.documentation {
+                self.write_docs("///", documentation);
+            }
+            self.line("#[macro_export]");
+            self.line(&format!(
+                "macro_rules! {} {{ /* {} rules */ }}",
+                macro_info.name, macro_info.rules
+            ));
+            self.line("");
+        }
+
+        self.write_module_body("crate");
+        self.trim_blank_line();
+    }
+
+    fn write_module_body(&mut self, path: &str) {
+        let index = self.index;
+        let Some(module) = index.modules.get(path) else {
+            return;
+        };
+        if !self.visiting.insert(path.to_string()) {
+            return;
+        }
+
+        self.write_reexports(path, module);
+
+        for child in &module.children {
+            let child_path = qualify(path, child);
+            if self
+                .index
+                .modules
+                .get(&child_path)
+                .is_some_and(|child| child.is_public)
+            {
+                self.write_module(child, &child_path);
+            }
+        }
+
+        self.write_items(path, module, None);
+        self.visiting.remove(path);
+    }
+
+    fn write_module(&mut self, name: &str, path: &str) {
+        let index = self.index;
+        if let Some(documentation) = index
+            .modules
+            .get(path)
+            .and_then(|module| module.documentation.clone())
+        {
+            self.write_docs("///", &documentation);
+        }
+        self.line(&format!("pub mod {name} {{"));
+        self.depth += 1;
+        self.write_module_body(path);
+        self.trim_blank_line();
+        self.depth -= 1;
+        self.line("}");
+        self.line("");
+    }
+
+    /// Writes the `pub use` declarations of `module`. Re-exports of items in
+    /// private modules are inlined, since they are not reachable otherwise.
+    fn write_reexports(&mut self, path: &str, module: &ModuleEntry<'a>) {
+        let index = self.index;
+        let reexports = module.analysis.imports.iter().filter(|import| {
+            import.visibility == "pub"
+                && import.module_path == module.prefix
+                && !is_test_only(&import.cfg)
+        });
+
+        let mut lines = Vec::new();
+        for import in reexports {
+            let use_line = match &import.alias {
+                Some(alias) => format!("pub use {} as {alias};", import.path),
+                None => format!("pub use {};", import.path),
+            };
+            // A re-exported module is reachable if it is public itself, any
+            // other item if the module declaring it is
+            let target = index.resolve(path, &import.path).filter(|(target, name)| {
+                let reexported = index.modules.get(&qualify(target, name));
+                !reexported.unwrap_or(&index.modules[target]).is_public
+            });
+            let Some((target, name)) = target else {
+                lines.push(use_line);
+                continue;
+            };
+
+            self.write_lines(&lines);
+            lines.clear();
+
+            let start = self.out.len();
+            let child_path = qualify(&target, &name);
+            let source = if index.modules.contains_key(&child_path) {
+                &child_path
+            } else {
+                &target
+            };
+            self.line(&format!("// re-exported from `{source}`"));
+            let written = self.out.len();
+            if name == "*" {
+                self.write_items(&target, &index.modules[&target], None);
+            } else if index.modules.contains_key(&child_path) {
+                let alias = import.alias.as_deref().unwrap_or(&name).to_string();
+                self.write_module(&alias, &child_path);
+            } else {
+                self.write_items(&target, &index.modules[&target], Some(&name));
+            }
+
+            // Chained re-exports and macros are not followed; keep the `use`
+            if self.out.len() == written {
+                self.out.truncate(start);
+                lines.push(use_line);
+            }
+        }
+        self.write_lines(&lines);
+    }
+
+    /// Writes the public items declared directly in `module` (at `path`),
+    /// or only the ones called `only` when given.
+    fn write_items(&mut self, path: &str, module: &ModuleEntry<'a>, only: Option<&str>) {
+        let analysis = module.analysis;
+        let prefix = module.prefix.as_str();
+        let wanted = |path: &str, visibility: &str, cfg: &Option<String>| {
+            visibility == "pub"
+                && in_module(path, prefix)
+                && !is_test_only(cfg)
+                && only.map_or(true, |name| path.rsplit("::").next() == Some(name))
+        };
+
+        for struct_info in &analysis.structs {
+            if wanted(&struct_info.path, &struct_info.visibility, &struct_info.cfg) {
+                self.write_struct(struct_info);
+                self.write_impls(path, &struct_info.name);
+            }
+        }
+        for enum_info in &analysis.enums {
+            if wanted(&enum_info.path, &enum_info.visibility, &enum_info.cfg) {
+                self.write_enum(enum_info);
+                self.write_impls(path, &enum_info.name);
+            }
+        }
+        for trait_info in &analysis.traits {
+            if wanted(&trait_info.path, &trait_info.visibility, &trait_info.cfg) {
+                self.write_trait(trait_info);
+            }
+        }
+        for function in &analysis.functions {
+            if wanted(&function.path, &function.visibility, &function.cfg) {
+                self.write_function(function, ";");
+                self.line("");
+            }
+        }
+        for alias in &analysis.type_aliases {
+            if wanted(&alias.path, &alias.visibility, &alias.cfg) {
+                self.write_header(&alias.documentation, &[], &alias.attributes, &alias.cfg);
+                self.line(&format!(
+                    "{};",
+                    RustParser::inline_signature(&alias.signature)
+                ));
+                self.line("");
+            }
+        }
+        for constant in &analysis.constants {
+            if wanted(&constant.path, &constant.visibility, &constant.cfg) {
+                let value = if constant.value.len() <= MAX_CONST_VALUE_LEN {
+                    format!(" = {}", constant.value)
+                } else {
+                    String::new()
+                };
+                self.write_header(
+                    &constant.documentation,
+                    &[],
+                    &constant.attributes,
+                    &constant.cfg,
+                );
+                self.line(&format!(
+                    "pub const {}: {}{value};",
+                    constant.name, constant.const_type
+                ));
+                self.line("");
+            }
+        }
+        for static_info in &analysis.statics {
+            if wanted(&static_info.path, &static_info.visibility, &static_info.cfg) {
+                let mutability = if static_info.is_mutable { "mut " } else { "" };
+                self.write_header(
+                    &static_info.documentation,
+                    &[],
+                    &static_info.attributes,
+                    &static_info.cfg,
+                );
+                self.line(&format!(
+                    "pub static {mutability}{}: {};",
+                    static_info.name, static_info.static_type
+                ));
+                self.line("");
+            }
+        }
+    }
+
+    fn write_struct(&mut self, struct_info: &StructInfo) {
+        self.write_header(
+            &struct_info.documentation,
+            &struct_info.derives,
+            &struct_info.attributes,
+            &struct_info.cfg,
+        );
+
+        let signature = RustParser::inline_signature(&struct_info.signature);
+        match struct_info.kind {
+            VariantKind::Unit => self.line(&format!("{signature};")),
+            VariantKind::Tuple => {
+                // Private fields keep their place so positional indices still match
+                let types: Vec<String> = struct_info
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        if field.visibility == "pub" {
+                            format!("pub {}", field.field_type)
+                        } else {
+                            "/* private field */".to_string()
+                        }
+                    })
+                    .collect();
+                self.line(&format!("{signature}({});", types.join(", ")));
+            }
+            VariantKind::Struct if struct_info.fields.is_empty() => {
+                self.line(&format!("{signature} {{}}"));
+            }
+            VariantKind::Struct => {
+                self.line(&format!("{signature} {{"));
+                self.depth += 1;
+                for field in struct_info
+                    .fields
+                    .iter()
+                    .filter(|field| field.visibility == "pub")
+                {
+                    if let Some(documentation) = &field.documentation {
+                        self.write_docs("///", documentation);
+                    }
+                    self.line(&format!("pub {}: {},", field.name, field.field_type));
+                }
+                if struct_info
+                    .fields
+                    .iter()
+                    .any(|field| field.visibility != "pub")
+                {
+                    self.line("/* private fields */");
+                }
+                self.depth -= 1;
+                self.line("}");
+            }
+        }
+        self.line("");
+    }
+
+    fn write_enum(&mut self, enum_info: &EnumInfo) {
+        self.write_header(
+            &enum_info.documentation,
+            &enum_info.derives,
+            &enum_info.attributes,
+            &enum_info.cfg,
+        );
+        self.line(&format!(
+            "{} {{",
+            RustParser::inline_signature(&enum_info.signature)
+        ));
+        self.depth += 1;
+        for variant in &enum_info.variants {
+            if let Some(documentation) = &variant.documentation {
+                self.write_docs("///", documentation);
+            }
+            for attribute in &variant.attributes {
+                self.line(attribute);
+            }
+            let payload = match variant.kind {
+                VariantKind::Unit => String::new(),
+                VariantKind::Tuple => {
+                    let types: Vec<&str> = variant
+                        .fields
+                        .iter()
+                        .map(|field| field.field_type.as_str())
+                        .collect();
+                    format!("({})", types.join(", "))
+                }
+                VariantKind::Struct => {
+                    let fields: Vec<String> = variant
+                        .fields
+                        .iter()
+                        .map(|field| format!("{}: {}", field.name, field.field_type))
+                        .collect();
+                    format!(" {{ {} }}", fields.join(", "))
+                }
+            };
+            let discriminant = variant
+                .discriminant
+                .as_ref()
+                .map(|discriminant| format!(" = {discriminant}"))
+                .unwrap_or_default();
+            self.line(&format!("{}{payload}{discriminant},", variant.name));
+        }
+        self.depth -= 1;
+        self.line("}");
+        self.line("");
+    }
+
+    fn write_trait(&mut self, trait_info: &TraitInfo) {
+        self.write_header(
+            &trait_info.documentation,
+            &[],
+            &trait_info.attributes,
+            &trait_info.cfg,
+        );
+        let unsafety = if trait_info.is_unsafe { "unsafe " } else { "" };
+        let supertraits = if trait_info.supertraits.is_empty() {
+            String::new()
+        } else {
+            format!(": {}", trait_info.supertraits.join(" + "))
+        };
+        self.line(&format!(
+            "pub {unsafety}trait {}{supertraits} {{",
+            trait_info.name
+        ));
+        self.depth += 1;
+        for ty in &trait_info.associated_types {
+            let bounds = if ty.bounds.is_empty() {
+                String::new()
+            } else {
+                format!(": {}", ty.bounds.join(" + "))
+            };
+            let default = ty
+                .default
+                .as_ref()
+                .map(|default| format!(" = {default}"))
+                .unwrap_or_default();
+            self.line(&format!("type {}{bounds}{default};", ty.name));
+        }
+        for constant in &trait_info.associated_consts {
+            self.line(&format!(
+                "const {}: {};",
+                constant.name, constant.const_type
+            ));
+        }
+        for method in &trait_info.methods {
+            let body = if method.has_default { " { ... }" } else { ";" };
+            self.write_function(&method.function, body);
+        }
+        self.depth -= 1;
+        self.line("}");
+        self.line("");
+    }
+
+    /// Writes the impl blocks of the type `name` declared in module `path`:
+    /// inherent impls with their public methods and trait impls by header
+    /// only.
+    fn write_impls(&mut self, path: &str, name: &str) {
+        let impls: Vec<&ImplInfo> = self
+            .impls
+            .iter()
+            .filter(|((module, target), _)| module == path && target == name)
+            .map(|(_, impl_info)| *impl_info)
+            .collect();
+
+        for impl_info in impls {
+            let header = RustParser::inline_signature(&impl_info.signature);
+            if impl_info.trait_name.is_some() {
+                self.write_header(&None, &[], &[], &impl_info.cfg);
+                self.line(&format!("{header} {{ ... }}"));
+                self.line("");
+                continue;
+            }
+
+            let methods: Vec<&FunctionInfo> = impl_info
+                .methods
+                .iter()
+                .filter(|method| method.visibility == "pub" && !is_test_only(&method.cfg))
+                .collect();
+            if methods.is_empty() {
+                continue;
+            }
+            self.write_header(&None, &[], &impl_info.attributes, &impl_info.cfg);
+            self.line(&format!("{header} {{"));
+            self.depth += 1;
+            for (i, method) in methods.into_iter().enumerate() {
+                if i > 0 {
+                    self.line("");
+                }
+                self.write_function(method, ";");
+            }
+            self.depth -= 1;
+            self.line("}");
+            self.line("");
+        }
+    }
+
+    fn write_function(&mut self, function: &FunctionInfo, body: &str) {
+        self.write_header(&function.documentation, &[], &function.attributes, &None);
+        let signature = format!("{}{body}", function.signature);
+        for line in signature.lines() {
+            self.line(line);
+        }
+    }
+
+    /// Writes an item's doc comment, derives, notable attributes and `cfg`.
+    fn write_header(
+        &mut self,
+        documentation: &Option<String>,
+        derives: &[String],
+        attributes: &[String],
+        cfg: &Option<String>,
+    ) {
+        if let Some(documentation) = documentation {
+            self.write_docs("///", documentation);
+        }
+        if let Some(cfg) = cfg {
+            self.line(&format!("#[cfg({cfg})]"));
+        }
+        if !derives.is_empty() {
+            self.line(&format!("#[derive({})]", derives.join(", ")));
+        }
+        for attribute in attributes {
+            self.line(attribute);
+        }
+    }
+
+    fn write_docs(&mut self, marker: &str, documentation: &str) {
+        for line in documentation.lines() {
+            if line.is_empty() {
+                self.line(marker);
+            } else {
+                self.line(&format!("{marker} {line}"));
+            }
+        }
+    }
+
+    fn write_lines(&mut self, lines: &[String]) {
+        if lines.is_empty() {
+            return;
+        }
+        for line in lines {
+            self.line(line);
+        }
+        self.line("");
+    }
+
+    fn line(&mut self, line: &str) {
+        if !line.is_empty() {
+            self.out.push_str(&"    ".repeat(self.depth));
+            self.out.push_str(line);
+        }
+        self.out.push('\n');
+    }
+
+    /// Drops the blank line left after the last item of a block.
+    fn trim_blank_line(&mut self) {
+        if self.out.ends_with("\n\n") {
+            self.out.pop();
+        }
+    }
+}
+
+/// Joins the doc comment of a `mod` declaration with the module's own
+/// inner documentation.
+fn join_docs(outer: Option<&str>, inner: Option<&str>) -> Option<String> {
+    match (outer, inner) {
+        (Some(outer), Some(inner)) => Some(format!("{outer}\n\n{inner}")),
+        (Some(docs), None) | (None, Some(docs)) => Some(docs.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// Finds the module declared at `path` (relative to the file) in `modules`.
+fn find_module<'m>(modules: &'m [ModuleInfo], path: &str) -> Option<&'m ModuleInfo> {
+    modules.iter().find_map(|module| {
+        if module.path == path {
+            Some(module)
+        } else if path.starts_with(&format!("{}::", module.path)) {
+            find_module(&module.submodules, path)
+        } else {
+            None
+        }
+    })
+}
+
+/// Whether an item with qualified `path` is declared directly in the module
+/// at `prefix` (both relative to the file).
+fn in_module(path: &str, prefix: &str) -> bool {
+    path.rsplit_once("::").map_or("", |(parent, _)| parent) == prefix
+}
+
+/// Whether `module` declares a struct or enum called `name`.
+fn declares_type(module: &ModuleEntry, name: &str) -> bool {
+    let path = qualify(&module.prefix, name);
+    let analysis = module.analysis;
+    analysis.structs.iter().any(|info| info.path == path)
+        || analysis.enums.iter().any(|info| info.path == path)
+}
+
+/// Path of the type an impl targets: `&'a mut outer::Wrapper<T>` becomes
+/// `outer::Wrapper`.
+fn impl_target_path(target: &str) -> &str {
+    let target = target.split('<').next().unwrap_or(target);
+    let target = target.split_whitespace().last().unwrap_or(target);
+    target.trim_start_matches('&')
+}
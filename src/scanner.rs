@@ -3,13 +3,19 @@
 //! This module provides functionality to scan and analyze repository structure,
 //! extracting metadata, file information, and project organization.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+use std::path::{Component, Path, PathBuf};
 
-use crate::config::{Config, IGNORED_DIRS, IGNORED_FILES, SUPPORTED_EXTENSIONS};
+use crate::config::{
+    Config, CUSTOM_IGNORE_FILE, IGNORED_DIRS, IGNORED_FILES, SUPPORTED_EXTENSIONS,
+};
+use crate::lockfile::{CargoLock, ResolvedDependency};
+use crate::manifest::{CargoManifest, DependencyInfo, PackageInfo, TargetInfo, WorkspaceManifest};
 
 /// Information about a single file in the repository.
 ///
@@ -36,6 +42,13 @@ pub struct FileInfo {
 
     /// Size of the file in bytes.
     pub size: u64,
+
+    /// Name of the crate that owns this file, if it lies inside a package.
+    ///
+    /// In a Cargo workspace this is the member whose directory is the closest
+    /// ancestor of the file.
+    #[serde(default)]
+    pub crate_name: Option<String>,
 }
 
 /// Classification of file types supported by the generator.
@@ -72,6 +85,35 @@ pub struct ScanResult {
 
     /// Metadata extracted from project configuration files.
     pub metadata: ProjectMetadata,
+
+    /// Crates found in the repository.
+    ///
+    /// Contains the root package (if any) followed by every resolved
+    /// workspace member. Empty when the repository has no `Cargo.toml`.
+    #[serde(default)]
+    pub crates: Vec<CrateInfo>,
+}
+
+/// A Cargo package inside the scanned repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrateInfo {
+    /// Package name from the member's `Cargo.toml`.
+    pub name: String,
+
+    /// Directory of the package relative to the repository root.
+    ///
+    /// Empty for a package at the repository root.
+    pub root: PathBuf,
+
+    /// Metadata parsed from the package's own `Cargo.toml`.
+    pub metadata: ProjectMetadata,
+}
+
+impl ScanResult {
+    /// Returns whether the repository is a workspace with more than one crate.
+    pub fn is_workspace(&self) -> bool {
+        self.crates.len() > 1
+    }
 }
 
 /// Information about the overall structure and organization of the project.
@@ -104,21 +146,44 @@ pub struct ProjectMetadata {
     /// Extracted from `Cargo.toml` description field or README.md content.
     pub description: Option<String>,
 
-    /// List of main dependencies.
+    /// Crate version from the `version` field of `Cargo.toml`.
+    pub version: Option<String>,
+
+    /// Rust edition from the `edition` field of `Cargo.toml`.
+    pub edition: Option<String>,
+
+    /// Minimum supported Rust version from the `rust-version` field of `Cargo.toml`.
+    pub rust_version: Option<String>,
+
+    /// License expression from the `license` field of `Cargo.toml`.
+    pub license: Option<String>,
+
+    /// Features declared in `Cargo.toml`, mapped to what each one enables.
+    pub features: BTreeMap<String, Vec<String>>,
+
+    /// Library and binary targets of the package.
+    pub targets: Vec<TargetInfo>,
+
+    /// All declared dependencies.
     ///
-    /// Extracted from the `[dependencies]` section of `Cargo.toml`.
-    pub dependencies: Vec<String>,
+    /// Includes `[dependencies]`, `[dev-dependencies]`, `[build-dependencies]`
+    /// and target-specific tables, with version requirements and features.
+    pub dependencies: Vec<DependencyInfo>,
 
-    /// Rust version or project version.
+    /// Direct dependencies resolved against `Cargo.lock`, with exact versions,
+    /// sources and transitive dependency trees.
     ///
-    /// Extracted from `Cargo.toml` version field.
-    pub rust_version: Option<String>,
+    /// Only populated when [`Config::include_deps`] is set and a lockfile exists.
+    #[serde(default)]
+    pub resolved_dependencies: Vec<ResolvedDependency>,
 }
 
 /// Repository scanner that processes project files and structure.
 ///
 /// The scanner walks through the repository directory, identifies relevant files,
-/// extracts their content, and gathers project metadata.
+/// extracts their content, and gathers project metadata. Ignore rules from
+/// `.gitignore`, `.ignore`, `.git/info/exclude` and [`CUSTOM_IGNORE_FILE`]
+/// are honored with full gitignore semantics.
 ///
 /// # Examples
 ///
@@ -133,6 +198,7 @@ pub struct ProjectMetadata {
 ///     output_file: "context.md".to_string(),
 ///     include_hidden: false,
 ///     include_deps: true,
+///     ..Default::default()
 /// };
 ///
 /// let scanner = RepositoryScanner::new(config);
@@ -142,6 +208,7 @@ pub struct ProjectMetadata {
 /// # Ok(())
 /// # }
 /// ```
+#[derive(Clone)]
 pub struct RepositoryScanner {
     config: Config,
 }
@@ -171,6 +238,11 @@ impl RepositoryScanner {
     /// all supported files, extracts project metadata, and builds a comprehensive
     /// scan result.
     ///
+    /// Nested `.gitignore` and `.ignore` files, `.git/info/exclude` and
+    /// [`CUSTOM_IGNORE_FILE`] are applied while walking, whether or not the
+    /// repository is a git checkout. The configured include/exclude globs are
+    /// applied afterwards, before any file is read.
+    ///
     /// # Returns
     ///
     /// A `ScanResult` containing all discovered files, project structure, and metadata.
@@ -179,6 +251,7 @@ impl RepositoryScanner {
     ///
     /// Returns an error if:
     /// - The repository path doesn't exist or isn't accessible
+    /// - An include or exclude pattern is not a valid glob
     /// - File system errors occur during scanning
     /// - Files can't be read or parsed
     ///
@@ -201,14 +274,23 @@ impl RepositoryScanner {
         let mut files = Vec::new();
         let mut total_size = 0u64;
 
-        for entry in WalkDir::new(&self.config.repo_path)
-            .into_iter()
-            .filter_entry(|e| self.should_include_path(e.path()))
-        {
+        let scanner = self.clone();
+        let filter = PathFilter::new(&self.config)?;
+        let walker = WalkBuilder::new(&self.config.repo_path)
+            .hidden(!self.config.include_hidden)
+            .require_git(false)
+            .add_custom_ignore_filename(CUSTOM_IGNORE_FILE)
+            .sort_by_file_path(|a, b| a.cmp(b))
+            .filter_entry(move |e| {
+                scanner.should_include_path(e.path()) && scanner.matches_filter(&filter, e)
+            })
+            .build();
+
+        for entry in walker {
             let entry = entry?;
             let path = entry.path();
 
-            if path.is_file() {
+            if entry.file_type().is_some_and(|t| t.is_file()) {
                 if let Some(file_info) = self.process_file(path).await? {
                     total_size += file_info.size;
                     files.push(file_info);
@@ -217,12 +299,21 @@ impl RepositoryScanner {
         }
 
         let project_structure = self.build_project_structure(&files, total_size)?;
-        let metadata = self.extract_project_metadata().await?;
+        let (metadata, crates) = self.extract_project_metadata().await?;
+
+        for file in &mut files {
+            file.crate_name = crates
+                .iter()
+                .filter(|c| file.relative_path.starts_with(&c.root))
+                .max_by_key(|c| c.root.components().count())
+                .map(|c| c.name.clone());
+        }
 
         Ok(ScanResult {
             files,
             project_structure,
             metadata,
+            crates,
         })
     }
 
@@ -240,22 +331,40 @@ impl RepositoryScanner {
     ///
     /// `true` if the path should be included, `false` otherwise
     fn should_include_path(&self, path: &Path) -> bool {
-        let path_str = path.to_string_lossy();
+        // Rules are evaluated per component of the path relative to the
+        // repository root, so the location of the repository itself (e.g.
+        // `./.config/project`) never affects what gets included.
+        let relative = path.strip_prefix(&self.config.repo_path).unwrap_or(path);
+        let is_file = path.is_file();
+
+        let names: Vec<_> = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy()),
+                _ => None,
+            })
+            .collect();
+
+        let dir_count = if is_file {
+            names.len().saturating_sub(1)
+        } else {
+            names.len()
+        };
 
-        // Ignore hidden directories if not configured to include them
-        if !self.config.include_hidden && path_str.contains("/.") {
-            return false;
-        }
+        for (i, name) in names.iter().enumerate() {
+            // Ignore hidden files and directories if not configured to include them
+            if !self.config.include_hidden && name.starts_with('.') {
+                return false;
+            }
 
-        // Ignore specific directories
-        for ignored_dir in IGNORED_DIRS {
-            if path_str.contains(ignored_dir) {
+            // Ignore specific directories
+            if i < dir_count && IGNORED_DIRS.contains(&name.as_ref()) {
                 return false;
             }
         }
 
         // If it's a file, check if it's supported
-        if path.is_file() {
+        if is_file {
             let filename = path.file_name().unwrap_or_default().to_string_lossy();
 
             // Ignore specific files
@@ -275,6 +384,20 @@ impl RepositoryScanner {
         true
     }
 
+    /// Applies the user-supplied include/exclude globs to a walked entry.
+    ///
+    /// Directories are only tested against the exclude set so that included
+    /// files nested below them are still reached.
+    fn matches_filter(&self, filter: &PathFilter, entry: &ignore::DirEntry) -> bool {
+        let relative = match entry.path().strip_prefix(&self.config.repo_path) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative,
+            _ => return true,
+        };
+
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        filter.allows(relative, is_dir)
+    }
+
     /// Processes a single file and extracts its information.
     ///
     /// Reads the file content, determines its type based on extension,
@@ -313,6 +436,7 @@ impl RepositoryScanner {
             content,
             file_type,
             size: metadata.len(),
+            crate_name: None,
         }))
     }
 
@@ -346,84 +470,205 @@ impl RepositoryScanner {
         })
     }
 
-    async fn extract_project_metadata(&self) -> Result<ProjectMetadata> {
-        let cargo_toml_path = self.config.repo_path.join("Cargo.toml");
-        let readme_path = self.config.repo_path.join("README.md");
+    /// Extracts metadata for the repository root and every crate it contains.
+    ///
+    /// For a Cargo workspace, the `members`/`exclude` globs are resolved and
+    /// each member manifest is parsed with workspace inheritance applied. A
+    /// root `[package]` is reported as a crate as well.
+    async fn extract_project_metadata(&self) -> Result<(ProjectMetadata, Vec<CrateInfo>)> {
+        let root = &self.config.repo_path;
+        let fallback_name = root
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+        let manifest = self.load_manifest(root, None)?;
+        let workspace = manifest.as_ref().and_then(|m| m.workspace.clone());
+
+        let mut crates = Vec::new();
+        if let Some(manifest) = manifest.as_ref().filter(|m| m.package.is_some()) {
+            let metadata = self.build_metadata(root, &fallback_name, manifest.clone())?;
+            crates.push(CrateInfo {
+                name: metadata.name.clone(),
+                root: PathBuf::new(),
+                metadata,
+            });
+        }
 
-        let mut metadata = ProjectMetadata {
-            name: self
-                .config
-                .repo_path
-                .file_name()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_string(),
-            description: None,
-            dependencies: Vec::new(),
-            rust_version: None,
-        };
+        if let Some(workspace) = &workspace {
+            for member in workspace.resolve_members(root) {
+                let member_dir = root.join(&member);
+                let Some(member_manifest) = self.load_manifest(&member_dir, Some(workspace))?
+                else {
+                    continue;
+                };
+                if member_manifest.package.is_none() {
+                    continue;
+                }
 
-        // Extract information from Cargo.toml
-        if cargo_toml_path.exists() {
-            let cargo_content = fs::read_to_string(&cargo_toml_path)?;
-            self.parse_cargo_toml(&cargo_content, &mut metadata)?;
+                let dir_name = member.file_name().unwrap_or_default().to_string_lossy();
+                let metadata = self.build_metadata(&member_dir, &dir_name, member_manifest)?;
+                crates.push(CrateInfo {
+                    name: metadata.name.clone(),
+                    root: member,
+                    metadata,
+                });
+            }
         }
 
-        // Extract description from README.md
-        if readme_path.exists() {
-            let readme_content = fs::read_to_string(&readme_path)?;
-            metadata.description = self.extract_description_from_readme(&readme_content);
+        let mut metadata = match manifest {
+            Some(manifest) if manifest.package.is_some() => crates[0].metadata.clone(),
+            Some(manifest) => {
+                // Virtual workspace: describe it through `[workspace.package]`
+                let shared = workspace.unwrap_or_default();
+                let mut metadata = self.build_metadata(
+                    root,
+                    &fallback_name,
+                    CargoManifest {
+                        package: Some(shared.package),
+                        dependencies: shared.dependencies,
+                        ..manifest
+                    },
+                )?;
+                metadata.name = fallback_name;
+                metadata
+            }
+            None => self.build_metadata(root, &fallback_name, CargoManifest::default())?,
+        };
+
+        if self.config.include_deps {
+            if let Some(lock) = self.load_lockfile()? {
+                for crate_info in &mut crates {
+                    Self::resolve_dependencies(&lock, &mut crate_info.metadata);
+                }
+                match crates.iter().find(|c| c.root.as_os_str().is_empty()) {
+                    Some(root_crate) => {
+                        metadata.resolved_dependencies =
+                            root_crate.metadata.resolved_dependencies.clone();
+                    }
+                    None => Self::resolve_dependencies(&lock, &mut metadata),
+                }
+            }
         }
 
-        Ok(metadata)
+        Ok((metadata, crates))
     }
 
-    fn parse_cargo_toml(&self, content: &str, metadata: &mut ProjectMetadata) -> Result<()> {
-        let lines: Vec<&str> = content.lines().collect();
-        let mut in_package = false;
-        let mut in_dependencies = false;
-
-        for line in lines {
-            let line = line.trim();
+    /// Reads `Cargo.lock` from the repository root, if present.
+    ///
+    /// A lockfile that is not valid TOML (e.g. left with merge conflict
+    /// markers) is reported as a warning and treated as absent.
+    fn load_lockfile(&self) -> Result<Option<CargoLock>> {
+        let lock_path = self.config.repo_path.join("Cargo.lock");
+        if !lock_path.exists() {
+            return Ok(None);
+        }
 
-            if line.starts_with("[package]") {
-                in_package = true;
-                in_dependencies = false;
-                continue;
+        let content = fs::read_to_string(&lock_path)?;
+        match CargoLock::parse(&content) {
+            Ok(lock) => Ok(Some(lock)),
+            Err(error) => {
+                eprintln!(
+                    "Warning: Failed to parse {}, skipping resolved dependencies: {error}",
+                    lock_path.display()
+                );
+                Ok(None)
             }
+        }
+    }
 
-            if line.starts_with("[dependencies") {
-                in_package = false;
-                in_dependencies = true;
-                continue;
-            }
+    fn resolve_dependencies(lock: &CargoLock, metadata: &mut ProjectMetadata) {
+        metadata.resolved_dependencies = lock.resolve(
+            &metadata.name,
+            metadata.version.as_deref(),
+            &metadata.dependencies,
+        );
+    }
 
-            if line.starts_with("[") {
-                in_package = false;
-                in_dependencies = false;
-                continue;
-            }
+    /// Reads and parses `Cargo.toml` in `dir`, if present.
+    ///
+    /// A manifest that is not valid TOML is reported as a warning and
+    /// replaced by an empty package named after `dir`, so one broken member
+    /// does not prevent the rest of the repository from being described.
+    fn load_manifest(
+        &self,
+        dir: &Path,
+        workspace: Option<&WorkspaceManifest>,
+    ) -> Result<Option<CargoManifest>> {
+        let cargo_toml_path = dir.join("Cargo.toml");
+        if !cargo_toml_path.exists() {
+            return Ok(None);
+        }
 
-            if in_package {
-                if line.starts_with("name") {
-                    if let Some(name) = line.split('=').nth(1) {
-                        metadata.name = name.trim().trim_matches('"').to_string();
-                    }
-                } else if line.starts_with("version") {
-                    if let Some(version) = line.split('=').nth(1) {
-                        metadata.rust_version = Some(version.trim().trim_matches('"').to_string());
-                    }
+        let cargo_content = fs::read_to_string(&cargo_toml_path)?;
+        let mut manifest = match CargoManifest::parse(&cargo_content) {
+            Ok(manifest) => manifest,
+            Err(error) => {
+                eprintln!(
+                    "Warning: Failed to parse {}, using default metadata: {error}",
+                    cargo_toml_path.display()
+                );
+                CargoManifest {
+                    package: Some(PackageInfo {
+                        name: dir
+                            .file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy()
+                            .to_string(),
+                        ..PackageInfo::default()
+                    }),
+                    ..CargoManifest::default()
                 }
             }
+        };
+        manifest.discover_targets(dir);
+        if let Some(workspace) = workspace {
+            manifest.inherit_workspace(workspace);
+        }
 
-            if in_dependencies && !line.is_empty() {
-                if let Some(dep_name) = line.split('=').next() {
-                    metadata.dependencies.push(dep_name.trim().to_string());
-                }
+        Ok(Some(manifest))
+    }
+
+    /// Builds [`ProjectMetadata`] for the package in `dir`.
+    fn build_metadata(
+        &self,
+        dir: &Path,
+        fallback_name: &str,
+        manifest: CargoManifest,
+    ) -> Result<ProjectMetadata> {
+        let mut metadata = ProjectMetadata {
+            name: fallback_name.to_string(),
+            description: None,
+            version: None,
+            edition: None,
+            rust_version: None,
+            license: None,
+            features: manifest.features,
+            targets: manifest.targets,
+            dependencies: manifest.dependencies,
+            resolved_dependencies: Vec::new(),
+        };
+
+        if let Some(package) = manifest.package {
+            if !package.name.is_empty() {
+                metadata.name = package.name;
             }
+            metadata.description = package.description;
+            metadata.version = package.version;
+            metadata.edition = package.edition;
+            metadata.rust_version = package.rust_version;
+            metadata.license = package.license;
         }
 
-        Ok(())
+        // Fall back to the README.md introduction for the description
+        let readme_path = dir.join("README.md");
+        if metadata.description.is_none() && readme_path.exists() {
+            let readme_content = fs::read_to_string(&readme_path)?;
+            metadata.description = self.extract_description_from_readme(&readme_content);
+        }
+
+        Ok(metadata)
     }
 
     fn extract_description_from_readme(&self, content: &str) -> Option<String> {
@@ -452,3 +697,69 @@ impl RepositoryScanner {
         }
     }
 }
+
+/// Compiled include/exclude globs from [`Config`].
+struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl PathFilter {
+    fn new(config: &Config) -> Result<Self> {
+        let mut include = GlobSetBuilder::new();
+        let mut exclude = GlobSetBuilder::new();
+        let mut has_includes = false;
+
+        for pattern in &config.include_patterns {
+            match pattern.strip_prefix('!') {
+                Some(negated) => Self::add_exclude(&mut exclude, negated)?,
+                None => {
+                    include.add(Self::compile(pattern)?);
+                    has_includes = true;
+                }
+            }
+        }
+
+        for pattern in &config.exclude_patterns {
+            Self::add_exclude(&mut exclude, pattern.trim_start_matches('!'))?;
+        }
+
+        Ok(Self {
+            include: if has_includes {
+                Some(include.build()?)
+            } else {
+                None
+            },
+            exclude: exclude.build()?,
+        })
+    }
+
+    /// Adds an exclusion, also matching everything below a matching directory.
+    fn add_exclude(builder: &mut GlobSetBuilder, pattern: &str) -> Result<()> {
+        let pattern = pattern.trim_end_matches('/');
+        builder.add(Self::compile(pattern)?);
+        builder.add(Self::compile(&format!("{pattern}/**"))?);
+        Ok(())
+    }
+
+    fn compile(pattern: &str) -> Result<Glob> {
+        GlobBuilder::new(pattern.trim_start_matches("./"))
+            .literal_separator(true)
+            .build()
+            .with_context(|| format!("Invalid glob pattern: {pattern}"))
+    }
+
+    fn allows(&self, relative: &Path, is_dir: bool) -> bool {
+        if self.exclude.is_match(relative) {
+            return false;
+        }
+
+        if is_dir {
+            return true;
+        }
+
+        self.include
+            .as_ref()
+            .map_or(true, |include| include.is_match(relative))
+    }
+}
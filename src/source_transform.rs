@@ -0,0 +1,496 @@
+//! Source code transformations for the AI Context Generator.
+//!
+//! This module rewrites Rust source files into cheaper renderings: compacted
+//! source without comments, blank line runs or test modules, and source with
+//! function bodies elided, used when the complete file does not fit in the
+//! token budget. Edits are spliced into the original text at the locations
+//! `syn` reports, so formatting outside the edited ranges survives.
+
+use anyhow::Result;
+use std::ops::Range;
+use syn::visit::{self, Visit};
+use syn::{parse_file, Block, ImplItemFn, ItemFn, ItemMod, TraitItemFn};
+
+use crate::config::CompactionConfig;
+use crate::parser::is_cfg_test;
+
+/// Rust source transformer working on the original text.
+pub struct SourceTransformer;
+
+impl SourceTransformer {
+    /// Replaces the body of every function, method and provided trait method
+    /// with `{ ... }`, keeping docs, attributes, signatures, types and trait
+    /// definitions untouched.
+    ///
+    /// Items nested inside a function body disappear with it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source code cannot be parsed as valid Rust syntax.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ai_context_gen::source_transform::SourceTransformer;
+    ///
+    /// let source = r#"
+    /// /// Adds one.
+    /// pub fn inc(x: u32) -> u32 {
+    ///     // the hard part
+    ///     x + 1
+    /// }
+    ///
+    /// pub trait Named {
+    ///     fn name(&self) -> String;
+    ///     fn greet(&self) -> String {
+    ///         format!("hi {}", self.name())
+    ///     }
+    /// }
+    /// "#;
+    ///
+    /// let elided = SourceTransformer::elide_function_bodies(source).unwrap();
+    /// assert!(elided.contains("/// Adds one.\npub fn inc(x: u32) -> u32 { ... }"));
+    /// assert!(elided.contains("fn name(&self) -> String;"));
+    /// assert!(elided.contains("fn greet(&self) -> String { ... }"));
+    /// assert!(!elided.contains("the hard part"));
+    /// ```
+    pub fn elide_function_bodies(content: &str) -> Result<String> {
+        let syntax_tree = parse_file(content)?;
+        Ok(Self::rewrite(
+            content,
+            Some(&syntax_tree),
+            &CompactionConfig::default(),
+            true,
+        ))
+    }
+
+    /// Elides function bodies like [`Self::elide_function_bodies`], then
+    /// compacts the result according to `options`.
+    ///
+    /// `syntax_tree` must be parsed from `content`. A recovered tree (see
+    /// [`crate::parser::RustParser::parse_file_recovering`]) only rewrites
+    /// the items it holds.
+    pub fn elide_and_compact(
+        content: &str,
+        syntax_tree: &syn::File,
+        options: &CompactionConfig,
+    ) -> String {
+        Self::rewrite(content, Some(syntax_tree), options, true)
+    }
+
+    /// Compacts Rust source according to `options`.
+    ///
+    /// Steps run in order: `#[cfg(test)]` modules are dropped, then comments
+    /// are stripped, then runs of blank lines are collapsed. Comment
+    /// stripping understands string, raw string and character literals, and
+    /// removes lines left empty by it. Files that do not parse keep their
+    /// test modules.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ai_context_gen::config::CompactionConfig;
+    /// use ai_context_gen::source_transform::SourceTransformer;
+    ///
+    /// let source = r#"// Copyright notice
+    /// /// Greets.
+    /// pub fn greet() -> &'static str {
+    ///     "// not a comment" // says hi
+    /// }
+    ///
+    ///
+    /// #[cfg(test)]
+    /// mod tests {
+    ///     #[test]
+    ///     fn works() {}
+    /// }
+    /// "#;
+    ///
+    /// let options = CompactionConfig {
+    ///     strip_comments: true,
+    ///     strip_doc_comments: false,
+    ///     collapse_blank_lines: true,
+    ///     strip_test_modules: true,
+    /// };
+    /// let compacted = SourceTransformer::compact(source, &options);
+    /// assert_eq!(
+    ///     compacted,
+    ///     "/// Greets.\npub fn greet() -> &'static str {\n    \"// not a comment\"\n}\n"
+    /// );
+    /// ```
+    pub fn compact(content: &str, options: &CompactionConfig) -> String {
+        let syntax_tree = options
+            .strip_test_modules
+            .then(|| parse_file(content).ok())
+            .flatten();
+        Self::rewrite(content, syntax_tree.as_ref(), options, false)
+    }
+
+    /// Compacts like [`Self::compact`], with `syntax_tree` parsed from
+    /// `content`. With a recovered tree, the test modules among the
+    /// recovered items are dropped too.
+    pub fn compact_parsed(
+        content: &str,
+        syntax_tree: &syn::File,
+        options: &CompactionConfig,
+    ) -> String {
+        Self::rewrite(content, Some(syntax_tree), options, false)
+    }
+
+    /// Splices the edits located in `syntax_tree` (test modules, and
+    /// function bodies when `elide_bodies` is set) into `content`, then runs
+    /// the text-only compaction steps.
+    fn rewrite(
+        content: &str,
+        syntax_tree: Option<&syn::File>,
+        options: &CompactionConfig,
+        elide_bodies: bool,
+    ) -> String {
+        let mut edits = Vec::new();
+        if let Some(syntax_tree) = syntax_tree {
+            let offsets = LineOffsets::new(content);
+
+            if options.strip_test_modules {
+                let mut collector = TestModuleCollector::default();
+                collector.visit_file(syntax_tree);
+                edits.extend(collector.modules.iter().filter_map(|(start, end)| {
+                    let start = offsets.byte_offset(*start)?;
+                    let end = offsets.byte_offset(*end)?;
+                    let (start, end) = offsets.whole_lines(start, end);
+                    Some((start, end, ""))
+                }));
+            }
+
+            if elide_bodies {
+                let mut collector = BodyCollector::default();
+                collector.visit_file(syntax_tree);
+                edits.extend(collector.bodies.iter().filter_map(|block| {
+                    let span = block.brace_token.span;
+                    let start = offsets.byte_offset(span.open().start())?;
+                    let end = offsets.byte_offset(span.close().end())?;
+                    Some((start, end, "{ ... }"))
+                }));
+            }
+        }
+        let mut result = Self::splice(content, edits);
+
+        if options.strip_comments || options.strip_doc_comments {
+            result = Self::strip_comments(&result, options);
+        }
+
+        if options.collapse_blank_lines {
+            result = Self::collapse_blank_lines(&result);
+        }
+
+        result
+    }
+
+    /// Locates the comments in `content`, skipping string and character
+    /// literals so that `"// not a comment"` is left alone.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ai_context_gen::source_transform::SourceTransformer;
+    ///
+    /// let source = "/// Docs.\nlet url = \"http://x\"; // TODO: drop\n/* a /* nested */ b */";
+    ///
+    /// let comments = SourceTransformer::comments(source);
+    /// let texts: Vec<&str> = comments.iter().map(|c| &source[c.range.clone()]).collect();
+    /// assert_eq!(texts, ["/// Docs.", "// TODO: drop", "/* a /* nested */ b */"]);
+    /// assert!(comments[0].is_doc && !comments[1].is_doc);
+    /// ```
+    pub fn comments(content: &str) -> Vec<Comment> {
+        let chars: Vec<(usize, char)> = content.char_indices().collect();
+        let at = |i: usize| chars.get(i).map(|&(_, c)| c);
+        let byte = |i: usize| chars.get(i).map_or(content.len(), |&(b, _)| b);
+
+        let mut comments = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let start = i;
+
+            match (chars[i].1, at(i + 1)) {
+                ('/', Some('/')) => {
+                    while i < chars.len() && chars[i].1 != '\n' {
+                        i += 1;
+                    }
+                    let comment = &content[byte(start)..byte(i)];
+                    let is_doc = (comment.starts_with("///") && !comment.starts_with("////"))
+                        || comment.starts_with("//!");
+                    comments.push(Comment {
+                        range: byte(start)..byte(i),
+                        is_doc,
+                    });
+                }
+                ('/', Some('*')) => {
+                    let mut depth = 0;
+                    while i < chars.len() {
+                        match (chars[i].1, at(i + 1)) {
+                            ('/', Some('*')) => {
+                                depth += 1;
+                                i += 2;
+                            }
+                            ('*', Some('/')) => {
+                                depth -= 1;
+                                i += 2;
+                                if depth == 0 {
+                                    break;
+                                }
+                            }
+                            _ => i += 1,
+                        }
+                    }
+                    let comment = &content[byte(start)..byte(i)];
+                    let is_doc = (comment.starts_with("/**")
+                        && !comment.starts_with("/***")
+                        && comment != "/**/")
+                        || comment.starts_with("/*!");
+                    comments.push(Comment {
+                        range: byte(start)..byte(i),
+                        is_doc,
+                    });
+                }
+                ('"', _) => {
+                    let raw_hashes = raw_string_hashes(&content[..byte(start)]);
+                    i += 1;
+                    while i < chars.len() {
+                        match chars[i].1 {
+                            '\\' if raw_hashes.is_none() => i += 2,
+                            '"' => {
+                                i += 1;
+                                let hashes = raw_hashes.unwrap_or(0);
+                                if (0..hashes).all(|n| at(i + n) == Some('#')) {
+                                    i += hashes;
+                                    break;
+                                }
+                            }
+                            _ => i += 1,
+                        }
+                    }
+                }
+                ('\'', Some('\\')) => {
+                    i += 2;
+                    while i < chars.len() && chars[i].1 != '\'' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                ('\'', Some(_)) if at(i + 2) == Some('\'') => i += 3,
+                _ => i += 1,
+            }
+        }
+
+        comments
+    }
+
+    /// Removes the comments selected by `options`. Whitespace before a
+    /// removed comment is dropped too, and so is the whole line when the
+    /// comment was alone on it.
+    fn strip_comments(content: &str, options: &CompactionConfig) -> String {
+        let mut result = String::with_capacity(content.len());
+        let mut position = 0;
+
+        for comment in Self::comments(content) {
+            if Self::keeps(comment.is_doc, options) {
+                continue;
+            }
+            result.push_str(&content[position..comment.range.start]);
+            result.truncate(result.trim_end_matches([' ', '\t']).len());
+            position = comment.range.end;
+
+            if result.is_empty() || result.ends_with('\n') {
+                let rest = &content[position..];
+                let after_blanks = rest.trim_start_matches([' ', '\t', '\r']);
+                if after_blanks.is_empty() || after_blanks.starts_with('\n') {
+                    position += rest.len() - after_blanks.len();
+                    position = (position + 1).min(content.len());
+                }
+            }
+        }
+
+        result.push_str(&content[position..]);
+        result
+    }
+
+    fn keeps(is_doc: bool, options: &CompactionConfig) -> bool {
+        if is_doc {
+            !options.strip_doc_comments
+        } else {
+            !options.strip_comments
+        }
+    }
+
+    /// Trims trailing whitespace and keeps at most one blank line in a row,
+    /// with none at the start or end of the file.
+    fn collapse_blank_lines(content: &str) -> String {
+        let mut result = String::with_capacity(content.len());
+        let mut blank_run = true;
+        for line in content.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                if !blank_run {
+                    result.push('\n');
+                }
+                blank_run = true;
+            } else {
+                result.push_str(line);
+                result.push('\n');
+                blank_run = false;
+            }
+        }
+
+        if blank_run && result.ends_with("\n\n") {
+            result.pop();
+        }
+        result
+    }
+
+    /// Replaces each `(start, end)` byte range of `content` by its
+    /// replacement. Ranges nested in an earlier one are dropped.
+    fn splice(content: &str, mut edits: Vec<(usize, usize, &str)>) -> String {
+        edits.sort_unstable();
+
+        let mut result = String::with_capacity(content.len());
+        let mut position = 0;
+        for (start, end, replacement) in edits {
+            if start < position {
+                continue;
+            }
+            result.push_str(&content[position..start]);
+            result.push_str(replacement);
+            position = end;
+        }
+        result.push_str(&content[position..]);
+        result
+    }
+}
+
+/// A comment located by [`SourceTransformer::comments`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    /// Byte range of the comment, from its opening `//` or `/*` to the end
+    /// of the line or the closing `*/`.
+    pub range: Range<usize>,
+
+    /// Whether this is a doc comment (`///`, `//!`, `/** */` or `/*! */`).
+    pub is_doc: bool,
+}
+
+/// Collects the bodies of functions, without descending into them.
+#[derive(Default)]
+struct BodyCollector<'ast> {
+    bodies: Vec<&'ast Block>,
+}
+
+impl<'ast> Visit<'ast> for BodyCollector<'ast> {
+    fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+        self.bodies.push(&item.block);
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast ImplItemFn) {
+        self.bodies.push(&item.block);
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast TraitItemFn) {
+        match &item.default {
+            Some(block) => self.bodies.push(block),
+            None => visit::visit_trait_item_fn(self, item),
+        }
+    }
+}
+
+/// Number of `#` delimiting a raw string literal whose opening quote follows
+/// `prefix` (`r"`, `r#"`, `br"`, `cr"`...), or `None` for other literals.
+fn raw_string_hashes(prefix: &str) -> Option<usize> {
+    let before_hashes = prefix.trim_end_matches('#');
+    let hashes = prefix.len() - before_hashes.len();
+    let before_r = before_hashes.strip_suffix('r')?;
+    let before_prefix = before_r.strip_suffix(['b', 'c']).unwrap_or(before_r);
+
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    if before_prefix.chars().next_back().is_some_and(is_ident) {
+        None
+    } else {
+        Some(hashes)
+    }
+}
+
+/// Collects the locations of `#[cfg(test)]` modules, from their first
+/// attribute to their closing brace or semicolon.
+#[derive(Default)]
+struct TestModuleCollector {
+    modules: Vec<(proc_macro2::LineColumn, proc_macro2::LineColumn)>,
+}
+
+impl<'ast> Visit<'ast> for TestModuleCollector {
+    fn visit_item_mod(&mut self, item: &'ast ItemMod) {
+        if !item.attrs.iter().any(is_cfg_test) {
+            return visit::visit_item_mod(self, item);
+        }
+
+        let start = item.attrs[0].pound_token.span.start();
+        let end = match (&item.content, &item.semi) {
+            (Some((brace, _)), _) => brace.span.close().end(),
+            (None, Some(semi)) => semi.span.end(),
+            (None, None) => return,
+        };
+        self.modules.push((start, end));
+    }
+}
+
+/// Maps the 1-based lines and 0-based character columns reported by
+/// `proc-macro2` spans to byte offsets in the source text, and back from
+/// byte offsets to lines.
+pub(crate) struct LineOffsets<'a> {
+    content: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineOffsets<'a> {
+    pub(crate) fn new(content: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            content,
+            line_starts,
+        }
+    }
+
+    fn byte_offset(&self, location: proc_macro2::LineColumn) -> Option<usize> {
+        let line_start = *self.line_starts.get(location.line.checked_sub(1)?)?;
+        let line = &self.content[line_start..];
+        let column = line
+            .char_indices()
+            .nth(location.column)
+            .map_or(line.len(), |(i, _)| i);
+        Some(line_start + column)
+    }
+
+    /// Line (1-based) holding the byte at `offset`.
+    pub(crate) fn line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset)
+    }
+
+    /// Widens a byte range to whole lines when only whitespace surrounds it
+    /// on its first and last line.
+    fn whole_lines(&self, start: usize, end: usize) -> (usize, usize) {
+        let line_start = self.content[..start].rfind('\n').map_or(0, |i| i + 1);
+        let start = if self.content[line_start..start].trim().is_empty() {
+            line_start
+        } else {
+            start
+        };
+
+        let rest = &self.content[end..];
+        let line_end = rest.find('\n').map_or(self.content.len(), |i| end + i + 1);
+        let end = if self.content[end..line_end].trim().is_empty() {
+            line_end
+        } else {
+            end
+        };
+
+        (start, end)
+    }
+}
@@ -0,0 +1,352 @@
+//! Test inventory for the AI Context Generator.
+//!
+//! This module lists the unit tests, integration tests, benchmarks and doc
+//! tests of a project, and maps each test function to the crate-local items
+//! it exercises: the functions it calls, resolved through the
+//! [`CallGraph`](crate::call_graph::CallGraph) and followed through helpers
+//! defined in the test's file, plus the function its name refers to
+//! (`test_parse` or `parse_works` for `parse`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::call_graph::CallGraph;
+use crate::manifest::TargetKind;
+use crate::module_tree::{qualify, CrateRoot, ModuleTree, RootKind};
+use crate::parser::{ParsedSources, TestInfo};
+use crate::scanner::ScanResult;
+
+/// Tests of a project and what they cover.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::module_tree::ModuleTree;
+/// use ai_context_gen::test_inventory::TestInventory;
+/// use ai_context_gen::{Config, RepositoryScanner};
+/// use std::fs;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let dir = tempfile::tempdir()?;
+/// fs::create_dir(dir.path().join("src"))?;
+/// fs::create_dir(dir.path().join("tests"))?;
+/// fs::write(
+///     dir.path().join("Cargo.toml"),
+///     "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+/// )?;
+/// fs::write(
+///     dir.path().join("src/lib.rs"),
+///     r#"
+///     /// Parses a number.
+///     ///
+///     /// ```
+///     /// assert_eq!(demo::parse("1"), 1);
+///     /// ```
+///     pub fn parse(input: &str) -> u32 { input.parse().unwrap() }
+///
+///     pub fn render(value: u32) -> String { value.to_string() }
+///
+///     #[cfg(test)]
+///     mod tests {
+///         use super::*;
+///
+///         #[test]
+///         #[should_panic]
+///         fn rejects_words() { parse("one"); }
+///
+///         #[test]
+///         fn test_render() { assert_eq!(super::render(1), "1"); }
+///     }
+///     "#,
+/// )?;
+/// fs::write(
+///     dir.path().join("tests/cli.rs"),
+///     "#[test]\n#[ignore]\nfn round_trip() { demo::render(demo::parse(\"2\")); }\n",
+/// )?;
+///
+/// let config = Config { repo_path: dir.path().to_path_buf(), ..Config::default() };
+/// let scan_result = RepositoryScanner::new(config).scan().await?;
+/// let inventory = TestInventory::build(&scan_result, &ModuleTree::resolve(&scan_result));
+///
+/// let tests: Vec<(&str, &[String])> = inventory
+///     .tests
+///     .iter()
+///     .map(|test| (test.short_path(), test.exercises.as_slice()))
+///     .collect();
+/// assert_eq!(
+///     tests,
+///     [
+///         ("tests::rejects_words", &["parse".to_string()][..]),
+///         ("tests::test_render", &["render".to_string()][..]),
+///         ("round_trip", &["demo::render".to_string(), "demo::parse".to_string()][..]),
+///     ]
+/// );
+/// assert!(inventory.tests[0].test.should_panic);
+/// assert!(inventory.tests[2].test.ignored);
+///
+/// assert_eq!(inventory.doc_tests[0].item, "crate::parse");
+/// assert_eq!(inventory.test_files[0].file, std::path::Path::new("tests/cli.rs"));
+/// assert_eq!(inventory.test_files[0].tests, 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TestInventory {
+    /// Test and benchmark functions, grouped by crate root in module tree
+    /// order.
+    pub tests: Vec<TestCase>,
+
+    /// Doc tests of library crates, in module tree and source order.
+    pub doc_tests: Vec<DocTest>,
+
+    /// Integration test and benchmark crate roots (`tests/`, `benches/`).
+    pub test_files: Vec<TestFile>,
+}
+
+/// A test or benchmark function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestCase {
+    /// Target name of the crate root the test belongs to.
+    pub target: String,
+
+    /// Kind of that crate root.
+    pub kind: RootKind,
+
+    /// Fully qualified path (e.g., "crate::parser::tests::parses_structs").
+    pub path: String,
+
+    /// File defining the test, relative to the repository root.
+    pub file: PathBuf,
+
+    /// Line of the test function.
+    pub line: usize,
+
+    /// Harness attributes of the test.
+    pub test: TestInfo,
+
+    /// Crate-local functions the test exercises, as seen from its crate
+    /// (e.g., "scanner::RepositoryScanner::scan", or
+    /// "ai_context_gen::scanner::RepositoryScanner::scan" from an
+    /// integration test).
+    pub exercises: Vec<String>,
+}
+
+/// A doc test, run by rustdoc from an item's documentation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocTest {
+    /// Target name of the library crate.
+    pub target: String,
+
+    /// Fully qualified path of the documented item (e.g., "crate::Config").
+    pub item: String,
+
+    /// File defining the item, relative to the repository root.
+    pub file: PathBuf,
+
+    /// Line of the documented item.
+    pub line: usize,
+
+    /// Rustdoc attributes of the code block (e.g., "no_run").
+    pub attributes: Vec<String>,
+}
+
+/// An integration test or benchmark crate root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestFile {
+    /// Target name of the crate root.
+    pub target: String,
+
+    /// [`RootKind::Test`] or [`RootKind::Bench`].
+    pub kind: RootKind,
+
+    /// Root file, relative to the repository root.
+    pub file: PathBuf,
+
+    /// Number of `#[test]`/`#[bench]` functions in the crate.
+    pub tests: usize,
+
+    /// Whether the crate runs under the libtest harness; `false` for
+    /// targets declared with `harness = false`, such as criterion
+    /// benchmarks.
+    pub harness: bool,
+}
+
+impl TestCase {
+    /// Path within the crate, without the leading `crate::`.
+    pub fn short_path(&self) -> &str {
+        self.path.strip_prefix("crate::").unwrap_or(&self.path)
+    }
+}
+
+impl TestInventory {
+    /// Builds the test inventory of every crate root in `module_tree`.
+    pub fn build(scan_result: &ScanResult, module_tree: &ModuleTree) -> Self {
+        let sources = ParsedSources::parse(scan_result);
+        let graph = CallGraph::from_sources(module_tree, &sources);
+        Self::from_sources(scan_result, module_tree, &sources, &graph)
+    }
+
+    /// Builds the test inventory of every crate root in `module_tree` from
+    /// already parsed sources and the call graph built from them.
+    pub fn from_sources(
+        scan_result: &ScanResult,
+        module_tree: &ModuleTree,
+        sources: &ParsedSources,
+        graph: &CallGraph,
+    ) -> Self {
+        let nodes: HashMap<(&Path, usize), usize> = graph
+            .functions
+            .iter()
+            .enumerate()
+            .map(|(index, function)| ((function.file.as_path(), function.line), index))
+            .collect();
+
+        let mut inventory = TestInventory::default();
+        let mut test_nodes = Vec::new();
+        for (root, module) in module_tree.module_files() {
+            let Some(file) = &module.file else {
+                continue;
+            };
+            let Some(analysis) = sources.get(file) else {
+                continue;
+            };
+            let crate_root = &module_tree.roots[root];
+
+            let mut functions: Vec<_> = analysis
+                .functions
+                .iter()
+                .chain(analysis.implementations.iter().flat_map(|imp| &imp.methods))
+                .filter_map(|function| Some((function, function.test.clone()?)))
+                .collect();
+            functions.sort_by_key(|(function, _)| function.span.start_line);
+            for (function, test) in functions {
+                let line = function.span.start_line;
+                test_nodes.push(nodes.get(&(file.as_path(), line)).copied());
+                inventory.tests.push(TestCase {
+                    target: crate_root.target.clone(),
+                    kind: crate_root.kind,
+                    path: qualify(&module.path, &function.path),
+                    file: file.clone(),
+                    line,
+                    test,
+                    exercises: Vec::new(),
+                });
+            }
+
+            if crate_root.kind == RootKind::Lib {
+                for doc_test in &analysis.doc_tests {
+                    inventory.doc_tests.push(DocTest {
+                        target: crate_root.target.clone(),
+                        item: qualify(&module.path, &doc_test.item),
+                        file: file.clone(),
+                        line: doc_test.line,
+                        attributes: doc_test.attributes.clone(),
+                    });
+                }
+            }
+        }
+
+        let tested: HashSet<usize> = test_nodes.iter().flatten().copied().collect();
+        for (test, node) in inventory.tests.iter_mut().zip(test_nodes) {
+            // Helpers defined next to the test are followed to what they call
+            let mut exercised: Vec<usize> = Vec::new();
+            let mut seen: HashSet<usize> = HashSet::new();
+            let mut pending: Vec<usize> = node.into_iter().collect();
+            while let Some(caller) = pending.pop() {
+                for callee in graph.callees(caller) {
+                    if tested.contains(&callee) || !seen.insert(callee) {
+                        continue;
+                    }
+                    exercised.push(callee);
+                    if graph.functions[callee].file == test.file {
+                        pending.push(callee);
+                    }
+                }
+            }
+            let named = named_function(graph, test, &tested);
+            if let Some(named) = named.filter(|named| seen.insert(*named)) {
+                exercised.push(named);
+            }
+            test.exercises = exercised
+                .into_iter()
+                .map(|function| graph.display_path(function, Some(&test.target)))
+                .collect();
+        }
+
+        for crate_root in &module_tree.roots {
+            if !matches!(crate_root.kind, RootKind::Test | RootKind::Bench) {
+                continue;
+            }
+            let Some(file) = &crate_root.module.file else {
+                continue;
+            };
+            inventory.test_files.push(TestFile {
+                target: crate_root.target.clone(),
+                kind: crate_root.kind,
+                file: file.clone(),
+                tests: inventory
+                    .tests
+                    .iter()
+                    .filter(|test| test.target == crate_root.target && test.kind == crate_root.kind)
+                    .count(),
+                harness: uses_harness(scan_result, crate_root),
+            });
+        }
+
+        inventory
+    }
+}
+
+/// The crate-local function a test is named after, e.g. `parse` for
+/// `test_parse`, `parse_test` or `parse_works`, when only one function of the
+/// test's crate (or failing that, of a library) has that name.
+fn named_function(graph: &CallGraph, test: &TestCase, tested: &HashSet<usize>) -> Option<usize> {
+    let name = test.path.rsplit("::").next()?;
+    let name = name.strip_prefix("test_").unwrap_or(name);
+    let name = ["_test", "_works"]
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .unwrap_or(name);
+
+    let candidates = |matches: &dyn Fn(&str, RootKind) -> bool| -> Vec<usize> {
+        graph
+            .functions
+            .iter()
+            .enumerate()
+            .filter(|(index, function)| {
+                !tested.contains(index)
+                    && function.path.rsplit("::").next() == Some(name)
+                    && matches(&function.target, function.kind)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    };
+    [
+        candidates(&|target, _| target == test.target),
+        candidates(&|_, kind| kind == RootKind::Lib),
+    ]
+    .into_iter()
+    .find_map(|candidates| match candidates.as_slice() {
+        [function] => Some(*function),
+        _ => None,
+    })
+}
+
+/// Whether a test or bench crate root runs under the libtest harness, that
+/// is unless its package declares the target with `harness = false`.
+fn uses_harness(scan_result: &ScanResult, crate_root: &CrateRoot) -> bool {
+    let kind = match crate_root.kind {
+        RootKind::Test => TargetKind::Test,
+        RootKind::Bench => TargetKind::Bench,
+        _ => return true,
+    };
+    scan_result
+        .crates
+        .iter()
+        .filter(|crate_info| crate_root.package.as_ref() == Some(&crate_info.name))
+        .flat_map(|crate_info| &crate_info.metadata.targets)
+        .find(|target| target.kind == kind && target.name == crate_root.target)
+        .map_or(true, |target| target.harness)
+}
@@ -156,11 +156,17 @@ impl ContentPrioritizer {
         })
     }
 
+    /// Returns the token counter used to measure sections.
+    pub fn token_counter(&self) -> &TokenCounter {
+        &self.token_counter
+    }
+
     /// Prioritizes and truncates content sections to fit within token limits.
     ///
     /// Sorts sections by priority (highest first) and includes as many complete
-    /// sections as possible. When a section would exceed the token limit, it
-    /// attempts to truncate it if there are sufficient remaining tokens.
+    /// sections as possible. When a section would exceed the token limit, its
+    /// [`fallback`](ContentSection::fallback) is tried instead, and the last
+    /// candidate is truncated if there are sufficient remaining tokens.
     ///
     /// # Arguments
     ///
@@ -183,6 +189,17 @@ impl ContentPrioritizer {
     /// ];
     /// let result = prioritizer.prioritize_content(sections, 100);
     /// // Higher priority sections appear first
+    ///
+    /// // A section that does not fit is replaced by its fallback
+    /// let mut full = ContentSection::new("Source".to_string(), "code ".repeat(2000), 3);
+    /// full.fallback = Some(Box::new(ContentSection::new(
+    ///     "Source (bodies elided)".to_string(),
+    ///     "fn main() { ... }".to_string(),
+    ///     3,
+    /// )));
+    /// let result = prioritizer.prioritize_content(vec![full], 500);
+    /// assert_eq!(result[0].title, "Source (bodies elided)");
+    /// assert!(!result[0].truncated);
     /// ```
     pub fn prioritize_content(
         &self,
@@ -192,30 +209,39 @@ impl ContentPrioritizer {
         let mut prioritized = sections;
 
         // Sort by priority (highest priority first)
-        prioritized.sort_by(|a, b| b.priority.cmp(&a.priority));
+        prioritized.sort_by_key(|section| std::cmp::Reverse(section.priority));
 
         let mut total_tokens = 0;
         let mut result = Vec::new();
 
-        for mut section in prioritized {
-            let section_tokens = self.token_counter.count_tokens(&section.content);
+        'sections: for mut section in prioritized {
+            loop {
+                let section_tokens = self.token_counter.count_tokens(&section.content);
 
-            if total_tokens + section_tokens <= max_tokens {
-                total_tokens += section_tokens;
-                result.push(section);
-            } else {
-                // Try to truncate content to fit within the limit
-                let remaining_tokens = max_tokens - total_tokens;
-                if remaining_tokens > 100 {
-                    // Only include if at least 100 tokens remain
-                    section.content = self
-                        .token_counter
-                        .truncate_to_token_limit(&section.content, remaining_tokens);
-                    section.truncated = true;
+                if total_tokens + section_tokens <= max_tokens {
+                    total_tokens += section_tokens;
+                    section.fallback = None;
                     result.push(section);
-                    break;
+                    continue 'sections;
+                }
+
+                match section.fallback.take() {
+                    Some(fallback) => section = *fallback,
+                    None => break,
                 }
             }
+
+            // Try to truncate content to fit within the limit
+            let remaining_tokens = max_tokens - total_tokens;
+            if remaining_tokens > 100 {
+                // Only include if at least 100 tokens remain
+                section.content = self
+                    .token_counter
+                    .truncate_to_token_limit(&section.content, remaining_tokens);
+                section.truncated = true;
+                result.push(section);
+                break;
+            }
         }
 
         result
@@ -264,6 +290,10 @@ pub struct ContentSection {
 
     /// Whether this section was truncated to fit token limits.
     pub truncated: bool,
+
+    /// Cheaper rendering of the same content, used in place of this section
+    /// when it does not fit (e.g., source code with function bodies elided).
+    pub fallback: Option<Box<ContentSection>>,
 }
 
 impl ContentSection {
@@ -292,6 +322,7 @@ impl ContentSection {
             content,
             priority,
             truncated: false,
+            fallback: None,
         }
     }
 
@@ -0,0 +1,557 @@
+//! Type relationship graph for the AI Context Generator.
+//!
+//! This module connects the structs, enums and traits of every crate: which
+//! crate-local types a type contains through its fields, which traits it
+//! implements or derives, which types it converts from and into through
+//! `From`/`Into`/`TryFrom`/`TryInto` impls, and whether it is an error type.
+//! Type paths are resolved through the module tree and `use` declarations;
+//! a bare name that is neither imported nor declared in its module falls
+//! back to the one type of that name in the crate, then in the libraries.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use syn::visit::{self, Visit};
+use syn::Type;
+
+use crate::module_tree::{join, qualify, ModuleTree, PathResolver, ResolvedPath, RootKind};
+use crate::parser::{ParsedSources, RustAnalysis};
+use crate::scanner::ScanResult;
+
+/// Relationships between the crate-local types of a project.
+///
+/// # Examples
+///
+/// ```rust
+/// use ai_context_gen::module_tree::ModuleTree;
+/// use ai_context_gen::type_graph::TypeGraph;
+/// use ai_context_gen::{Config, RepositoryScanner};
+/// use std::fs;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let dir = tempfile::tempdir()?;
+/// fs::create_dir(dir.path().join("src"))?;
+/// fs::write(
+///     dir.path().join("src/lib.rs"),
+///     r#"
+///     #[derive(Debug)]
+///     pub struct Report { pub files: Vec<FileEntry> }
+///     pub struct FileEntry { pub name: String }
+///
+///     #[derive(Debug)]
+///     pub enum ScanError { Io(std::io::Error) }
+///     impl std::fmt::Display for ScanError { /* ... */ }
+///     impl std::error::Error for ScanError {}
+///     impl From<std::io::Error> for ScanError { /* ... */ }
+///     "#,
+/// )?;
+///
+/// let config = Config { repo_path: dir.path().to_path_buf(), ..Config::default() };
+/// let scan_result = RepositoryScanner::new(config).scan().await?;
+/// let graph = TypeGraph::build(&scan_result, &ModuleTree::resolve(&scan_result));
+///
+/// let report = &graph.types[graph.find("Report").unwrap()];
+/// assert_eq!(report.derives, ["Debug"]);
+/// assert_eq!(report.contains, [graph.find("FileEntry").unwrap()]);
+///
+/// let error = &graph.types[graph.find("ScanError").unwrap()];
+/// assert!(error.is_error);
+/// assert_eq!(error.implements, ["std::fmt::Display", "std::error::Error"]);
+/// assert_eq!(error.converts_from[0].other, "std::io::Error");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TypeGraph {
+    /// Structs, enums and traits, grouped by crate root in module tree order.
+    pub types: Vec<TypeNode>,
+}
+
+/// A crate-local type and its relationships.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeNode {
+    /// Target name of the crate root defining the type.
+    pub target: String,
+
+    /// Kind of that crate root.
+    pub kind: RootKind,
+
+    /// Type name (e.g., "ScanResult").
+    pub name: String,
+
+    /// Fully qualified path (e.g., "crate::scanner::ScanResult").
+    pub path: String,
+
+    /// Whether this is a struct, an enum or a trait.
+    pub category: TypeCategory,
+
+    /// File defining the type, relative to the repository root.
+    pub file: PathBuf,
+
+    /// Line of the type's declaration.
+    pub line: usize,
+
+    /// Traits listed in `#[derive(...)]`.
+    pub derives: Vec<String>,
+
+    /// Traits implemented through `impl Trait for Type`, other than
+    /// conversions, as written (e.g., "fmt::Display").
+    pub implements: Vec<String>,
+
+    /// Crate-local types used in the fields of the struct or of the enum's
+    /// variants, as indices into [`TypeGraph::types`].
+    pub contains: Vec<usize>,
+
+    /// Types this one can be built from (`From`/`TryFrom` impls on it).
+    pub converts_from: Vec<Conversion>,
+
+    /// Types this one can be turned into (`Into`/`TryInto` impls on it, and
+    /// `From`/`TryFrom` impls on crate-local targets taking it).
+    pub converts_into: Vec<Conversion>,
+
+    /// For a trait, the crate-local types implementing it through an impl or
+    /// a derive, as indices into [`TypeGraph::types`].
+    pub implementors: Vec<usize>,
+
+    /// Whether the type implements or derives `Error`.
+    pub is_error: bool,
+}
+
+/// Kind of a [`TypeNode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TypeCategory {
+    /// A struct definition.
+    Struct,
+    /// An enum definition.
+    Enum,
+    /// A trait definition.
+    Trait,
+}
+
+/// One side of a conversion between two types.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conversion {
+    /// The other type, as written in the impl (e.g., "io::Error").
+    pub other: String,
+
+    /// Index of the other type in [`TypeGraph::types`], if it is
+    /// crate-local.
+    pub index: Option<usize>,
+
+    /// Whether the conversion can fail (`TryFrom`/`TryInto`).
+    pub fallible: bool,
+}
+
+impl TypeCategory {
+    /// Lowercase keyword of the category, e.g. "struct".
+    pub fn label(self) -> &'static str {
+        match self {
+            TypeCategory::Struct => "struct",
+            TypeCategory::Enum => "enum",
+            TypeCategory::Trait => "trait",
+        }
+    }
+}
+
+impl TypeNode {
+    /// Path within the crate, without the leading `crate::`.
+    pub fn short_path(&self) -> &str {
+        self.path.strip_prefix("crate::").unwrap_or(&self.path)
+    }
+}
+
+impl TypeGraph {
+    /// Builds the type graph of every crate root in `module_tree`.
+    pub fn build(scan_result: &ScanResult, module_tree: &ModuleTree) -> Self {
+        Self::from_sources(module_tree, &ParsedSources::parse(scan_result))
+    }
+
+    /// Builds the type graph of every crate root in `module_tree` from
+    /// already parsed sources.
+    pub fn from_sources(module_tree: &ModuleTree, sources: &ParsedSources) -> Self {
+        let libraries: Vec<usize> = module_tree
+            .roots
+            .iter()
+            .enumerate()
+            .filter(|(_, root)| root.kind == RootKind::Lib)
+            .map(|(index, _)| index)
+            .collect();
+
+        let modules: Vec<(usize, &str, &RustAnalysis)> = module_tree
+            .module_files()
+            .into_iter()
+            .filter_map(|(root, module)| {
+                let analysis = sources.get(module.file.as_deref()?)?;
+                Some((root, module.path.as_str(), analysis))
+            })
+            .collect();
+
+        // Names imported by each module: crate-local ones are followed to
+        // their definition, foreign ones never name a crate-local type
+        let mut paths = PathResolver::new(module_tree);
+        let mut foreign: HashSet<(usize, String, String)> = HashSet::new();
+        for (root, module, analysis) in &modules {
+            for import in analysis.imports.iter().filter(|import| !import.is_glob) {
+                let scope = qualify(module, &import.module_path);
+                let segments: Vec<String> = import.path.split("::").map(str::to_string).collect();
+                let Some(name) = import.alias.as_ref().or(segments.last()) else {
+                    continue;
+                };
+                if name == "self" {
+                    continue;
+                }
+                match paths.absolute(*root, &scope, &segments) {
+                    Some(ResolvedPath::Crate(target_root, target)) => {
+                        paths.add_import(*root, scope, name.clone(), (target_root, target));
+                    }
+                    Some(ResolvedPath::Std(_) | ResolvedPath::External(_)) => {
+                        foreign.insert((*root, scope, name.clone()));
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        let mut graph = TypeGraph::default();
+        let mut by_name: HashMap<(usize, String), Vec<usize>> = HashMap::new();
+        let mut by_path: HashMap<(usize, String), usize> = HashMap::new();
+        for (root, module, analysis) in &modules {
+            let crate_root = &module_tree.roots[*root];
+            let mut definitions = Vec::new();
+            for info in &analysis.structs {
+                definitions.push((
+                    &info.name,
+                    &info.path,
+                    info.span.start_line,
+                    TypeCategory::Struct,
+                    info.derives.clone(),
+                ));
+            }
+            for info in &analysis.enums {
+                definitions.push((
+                    &info.name,
+                    &info.path,
+                    info.span.start_line,
+                    TypeCategory::Enum,
+                    info.derives.clone(),
+                ));
+            }
+            for info in &analysis.traits {
+                definitions.push((
+                    &info.name,
+                    &info.path,
+                    info.span.start_line,
+                    TypeCategory::Trait,
+                    Vec::new(),
+                ));
+            }
+            definitions.sort_by_key(|definition| definition.2);
+
+            for (name, path, line, category, derives) in definitions {
+                by_name
+                    .entry((*root, name.clone()))
+                    .or_default()
+                    .push(graph.types.len());
+                by_path.insert((*root, qualify(module, path)), graph.types.len());
+                graph.types.push(TypeNode {
+                    target: crate_root.target.clone(),
+                    kind: crate_root.kind,
+                    name: name.clone(),
+                    path: qualify(module, path),
+                    category,
+                    file: PathBuf::from(&analysis.file_path),
+                    line,
+                    is_error: derives.iter().any(|derive| derive == "Error"),
+                    derives,
+                    implements: Vec::new(),
+                    contains: Vec::new(),
+                    converts_from: Vec::new(),
+                    converts_into: Vec::new(),
+                    implementors: Vec::new(),
+                });
+            }
+        }
+
+        let named = |root: usize, name: &str| -> Option<usize> {
+            std::iter::once(root)
+                .chain(libraries.iter().copied().filter(|&lib| lib != root))
+                .find_map(
+                    |root| match by_name.get(&(root, name.to_string()))?.as_slice() {
+                        [index] => Some(*index),
+                        _ => None,
+                    },
+                )
+        };
+        let lookup = |root: usize, path: &str| -> Option<usize> {
+            let (root, path) = paths.follow_reexports(root, path);
+            by_path.get(&(root, path)).copied()
+        };
+        // Resolves a type path written in module `scope`
+        let resolve = |root: usize, scope: &str, segments: &[String]| -> Option<usize> {
+            let (first, rest) = segments.split_first()?;
+            if foreign.contains(&(root, scope.to_string(), first.clone())) {
+                return None;
+            }
+            if let Some((target_root, target)) = paths.import(root, scope, first) {
+                return lookup(*target_root, &join(target, rest));
+            }
+            if rest.is_empty() {
+                return lookup(root, &qualify(scope, first)).or_else(|| named(root, first));
+            }
+            match paths.absolute(root, scope, segments)? {
+                ResolvedPath::Crate(root, path) => lookup(root, &path),
+                ResolvedPath::Std(_) | ResolvedPath::External(_) => None,
+            }
+        };
+        let resolve_trait = |types: &[TypeNode], root: usize, scope: &str, segments: &[String]| {
+            resolve(root, scope, segments)
+                .filter(|&index| types[index].category == TypeCategory::Trait)
+        };
+
+        for (root, module, analysis) in &modules {
+            let local = |path: &str| by_path.get(&(*root, qualify(module, path))).copied();
+
+            let field_types = analysis
+                .structs
+                .iter()
+                .map(|info| (&info.path, info.fields.iter().collect::<Vec<_>>()))
+                .chain(analysis.enums.iter().map(|info| {
+                    let fields = info.variants.iter().flat_map(|variant| &variant.fields);
+                    (&info.path, fields.collect())
+                }));
+            for (path, fields) in field_types {
+                let Some(index) = local(path) else {
+                    continue;
+                };
+                let parent = path.rsplit_once("::").map_or("", |(parent, _)| parent);
+                let scope = qualify(module, parent);
+                let mut contains = Vec::new();
+                for field in fields {
+                    for used in type_paths(&field.field_type) {
+                        if let Some(used) = resolve(*root, &scope, &used) {
+                            if used != index && !contains.contains(&used) {
+                                contains.push(used);
+                            }
+                        }
+                    }
+                }
+                graph.types[index].contains = contains;
+
+                for derive in graph.types[index].derives.clone() {
+                    let segments: Vec<String> = derive.split("::").map(str::to_string).collect();
+                    if let Some(trait_index) = resolve_trait(&graph.types, *root, &scope, &segments)
+                    {
+                        graph.types[trait_index].implementors.push(index);
+                    }
+                }
+            }
+
+            for implementation in &analysis.implementations {
+                let Some(trait_name) = &implementation.trait_name else {
+                    continue;
+                };
+                let scope = qualify(module, &implementation.module_path);
+                let resolve_type = |ty: &str| {
+                    syn::parse_str::<Type>(ty)
+                        .ok()
+                        .and_then(|ty| nominal(&ty))
+                        .and_then(|segments| resolve(*root, &scope, &segments))
+                };
+                let Some(index) = resolve_type(&implementation.target) else {
+                    continue;
+                };
+
+                let (trait_path, argument) = split_trait(trait_name);
+                let trait_ident = trait_path.last().cloned().unwrap_or_default();
+                match trait_ident.as_str() {
+                    "From" | "TryFrom" => {
+                        let Some(source) = argument else { continue };
+                        let fallible = trait_ident == "TryFrom";
+                        let source_index = resolve_type(&source);
+                        if let Some(source_index) = source_index {
+                            graph.types[source_index].converts_into.push(Conversion {
+                                other: implementation.target.clone(),
+                                index: Some(index),
+                                fallible,
+                            });
+                        }
+                        graph.types[index].converts_from.push(Conversion {
+                            other: source,
+                            index: source_index,
+                            fallible,
+                        });
+                    }
+                    "Into" | "TryInto" => {
+                        let Some(target) = argument else { continue };
+                        let target_index = resolve_type(&target);
+                        graph.types[index].converts_into.push(Conversion {
+                            other: target,
+                            index: target_index,
+                            fallible: trait_ident == "TryInto",
+                        });
+                    }
+                    _ => {
+                        if let Some(trait_index) =
+                            resolve_trait(&graph.types, *root, &scope, &trait_path)
+                        {
+                            graph.types[trait_index].implementors.push(index);
+                        }
+                        let node = &mut graph.types[index];
+                        node.is_error |= trait_ident == "Error";
+                        if !node.implements.contains(trait_name) {
+                            node.implements.push(trait_name.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        for node in &mut graph.types {
+            node.implementors.sort_unstable();
+            node.implementors.dedup();
+        }
+        graph
+    }
+
+    /// Finds a type by name, full path or path without `crate::`.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.types
+            .iter()
+            .position(|node| node.name == name || node.path == name || node.short_path() == name)
+    }
+
+    /// Path of type `index` as seen from crate root `from`: prefixed with its
+    /// target name when it lives in another crate.
+    pub fn display_path(&self, index: usize, from: Option<&str>) -> String {
+        let node = &self.types[index];
+        match from {
+            Some(target) if target != node.target => {
+                format!("{}::{}", node.target, node.short_path())
+            }
+            _ => node.short_path().to_string(),
+        }
+    }
+
+    /// Whether any two crate-local types are related, i.e. whether
+    /// [`Self::to_mermaid`] draws at least one edge.
+    pub fn has_edges(&self) -> bool {
+        self.edges().next().is_some()
+    }
+
+    /// Relationships drawn in the diagram, as `(from, arrow, to, label)`:
+    /// fields, implemented traits and `From` conversions between crate-local
+    /// types.
+    fn edges(&self) -> impl Iterator<Item = (usize, &'static str, usize, &'static str)> + '_ {
+        self.types.iter().enumerate().flat_map(|(index, node)| {
+            let contains = node
+                .contains
+                .iter()
+                .map(move |&contained| (index, "*--", contained, ""));
+            let implementors = node
+                .implementors
+                .iter()
+                .map(move |&implementor| (implementor, "..|>", index, ""));
+            let conversions = node
+                .converts_from
+                .iter()
+                .filter_map(move |conversion| Some((index, "..>", conversion.index?, " : from")));
+            contains.chain(implementors).chain(conversions)
+        })
+    }
+
+    /// Renders the relationships between crate-local types as a Mermaid
+    /// class diagram: composition for fields, realization for implemented
+    /// traits and dependencies for conversions.
+    ///
+    /// ```rust
+    /// use ai_context_gen::type_graph::TypeGraph;
+    ///
+    /// assert_eq!(TypeGraph::default().to_mermaid(), "classDiagram\n");
+    /// ```
+    pub fn to_mermaid(&self) -> String {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for node in &self.types {
+            *counts.entry(node.name.as_str()).or_default() += 1;
+        }
+        let id = |index: usize| {
+            let name = &self.types[index].name;
+            if counts[name.as_str()] > 1 {
+                format!("{name}_{index}")
+            } else {
+                name.clone()
+            }
+        };
+
+        let mut mermaid = String::from("classDiagram\n");
+        for (index, node) in self.types.iter().enumerate() {
+            if node.category == TypeCategory::Trait {
+                mermaid.push_str(&format!(
+                    "    class {} {{\n        <<trait>>\n    }}\n",
+                    id(index)
+                ));
+            }
+        }
+        for (from, arrow, to, label) in self.edges() {
+            mermaid.push_str(&format!("    {} {arrow} {}{label}\n", id(from), id(to)));
+        }
+        mermaid
+    }
+}
+
+/// Segments of the path types mentioned anywhere in `ty`, e.g. `Vec` and
+/// `scanner::FileInfo` for `Vec<scanner::FileInfo>`.
+fn type_paths(ty: &str) -> Vec<Vec<String>> {
+    #[derive(Default)]
+    struct Paths(Vec<Vec<String>>);
+
+    impl<'ast> Visit<'ast> for Paths {
+        fn visit_type_path(&mut self, path: &'ast syn::TypePath) {
+            if path.qself.is_none() {
+                self.0.push(segments(&path.path));
+            }
+            visit::visit_type_path(self, path);
+        }
+    }
+
+    let mut paths = Paths::default();
+    if let Ok(ty) = syn::parse_str::<Type>(ty) {
+        paths.visit_type(&ty);
+    }
+    paths.0
+}
+
+/// Path of the type an impl is for, looking through references.
+fn nominal(ty: &Type) -> Option<Vec<String>> {
+    match ty {
+        Type::Reference(reference) => nominal(&reference.elem),
+        Type::Paren(paren) => nominal(&paren.elem),
+        Type::Path(path) if path.qself.is_none() => Some(segments(&path.path)),
+        _ => None,
+    }
+}
+
+/// Segments of a path without their generic arguments, with an empty first
+/// segment for a leading `::`.
+fn segments(path: &syn::Path) -> Vec<String> {
+    let leading = path.leading_colon.map(|_| String::new());
+    leading
+        .into_iter()
+        .chain(
+            path.segments
+                .iter()
+                .map(|segment| segment.ident.to_string()),
+        )
+        .collect()
+}
+
+/// Splits a trait as written into its path and its first generic argument:
+/// `convert::From<io::Error>` gives `convert::From` and `io::Error`.
+fn split_trait(trait_name: &str) -> (Vec<String>, Option<String>) {
+    let (path, argument) = match trait_name.split_once('<') {
+        Some((path, rest)) => (path, rest.strip_suffix('>').map(str::trim)),
+        None => (trait_name, None),
+    };
+    let segments = path.split("::").map(|segment| segment.trim().to_string());
+    (segments.collect(), argument.map(str::to_string))
+}
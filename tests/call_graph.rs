@@ -0,0 +1,290 @@
+//! Tests for resolving calls between crate-local functions.
+
+mod common;
+
+use ai_context_gen::call_graph::CallGraph;
+use ai_context_gen::module_tree::ModuleTree;
+use common::{scan, write};
+use std::path::Path;
+
+async fn build(root: &Path) -> CallGraph {
+    let scan_result = scan(root).await;
+    CallGraph::build(&scan_result, &ModuleTree::resolve(&scan_result))
+}
+
+/// Every edge as `caller -> callee`, with callees in other crates prefixed
+/// by their target name.
+fn edges(graph: &CallGraph) -> Vec<String> {
+    graph
+        .calls
+        .iter()
+        .map(|edge| {
+            let caller = &graph.functions[edge.caller];
+            format!(
+                "{} -> {}",
+                caller.short_path(),
+                graph.display_path(edge.callee, Some(&caller.target))
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn glob_imports_and_super_paths_are_resolved() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        r#"
+        mod util;
+        mod net;
+        use util::*;
+
+        pub fn run() {
+            clamp();
+            net::tcp::connect();
+        }
+
+        fn top() {}
+        "#,
+    );
+    write(dir.path(), "src/util.rs", "pub fn clamp() {}\n");
+    write(
+        dir.path(),
+        "src/net.rs",
+        r#"
+        pub mod tcp {
+            pub fn connect() {
+                super::resolve();
+                super::super::top();
+                self::handshake();
+            }
+
+            fn handshake() {}
+        }
+
+        fn resolve() {}
+        "#,
+    );
+
+    let graph = build(dir.path()).await;
+
+    assert_eq!(
+        edges(&graph),
+        [
+            "run -> util::clamp",
+            "run -> net::tcp::connect",
+            "net::tcp::connect -> net::resolve",
+            "net::tcp::connect -> top",
+            "net::tcp::connect -> net::tcp::handshake",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn method_calls_through_struct_fields_are_resolved() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        r#"
+        pub struct Pool;
+
+        impl Pool {
+            pub fn get(&self) -> Connection { Connection }
+        }
+
+        pub struct Connection;
+
+        impl Connection {
+            pub fn query(&self) {}
+        }
+
+        pub struct App {
+            pool: Pool,
+            backup: Option<Box<Pool>>,
+        }
+
+        impl App {
+            pub fn handle(&self) {
+                self.pool.get().query();
+                let connection = self.pool.get();
+                connection.query();
+                self.backup.as_ref().unwrap().get();
+            }
+        }
+        "#,
+    );
+
+    let graph = build(dir.path()).await;
+
+    // `as_ref()` is not crate-local, so the type of the backup is lost
+    assert_eq!(
+        edges(&graph),
+        [
+            "App::handle -> Pool::get",
+            "App::handle -> Connection::query"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn ambiguous_type_methods_resolve_to_no_edge() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        r#"
+        mod http {
+            pub struct Handler;
+            impl Handler { pub fn handle() {} }
+        }
+
+        mod grpc {
+            pub struct Handler;
+            impl Handler { pub fn handle() {} }
+        }
+
+        pub fn unqualified() {
+            Handler::handle();
+        }
+
+        mod api {
+            use super::grpc::Handler;
+
+            pub fn imported() {
+                Handler::handle();
+            }
+        }
+
+        pub fn qualified() {
+            http::Handler::handle();
+        }
+        "#,
+    );
+
+    let graph = build(dir.path()).await;
+
+    assert_eq!(
+        edges(&graph),
+        [
+            "api::imported -> grpc::Handler::handle",
+            "qualified -> http::Handler::handle",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn binaries_call_into_their_library() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[package]\nname = \"my-app\"\nversion = \"0.1.0\"\n",
+    );
+    write(
+        dir.path(),
+        "src/lib.rs",
+        r#"
+        mod server;
+        pub use server::Server;
+
+        pub fn init() {}
+        "#,
+    );
+    write(
+        dir.path(),
+        "src/server.rs",
+        r#"
+        pub struct Server;
+
+        impl Server {
+            pub fn new() -> Self { Server }
+            pub fn start(&self) {}
+        }
+        "#,
+    );
+    write(
+        dir.path(),
+        "src/main.rs",
+        r#"
+        use my_app::Server;
+
+        fn main() {
+            my_app::init();
+            let server = Server::new();
+            server.start();
+            local();
+        }
+
+        fn local() {}
+        "#,
+    );
+
+    let graph = build(dir.path()).await;
+
+    assert_eq!(
+        edges(&graph),
+        [
+            "main -> my_app::init",
+            "main -> my_app::server::Server::new",
+            "main -> my_app::server::Server::start",
+            "main -> local",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn libraries_are_searched_in_module_tree_order() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"alpha\", \"beta\", \"cli\"]\n",
+    );
+    for name in ["alpha", "beta"] {
+        write(
+            dir.path(),
+            &format!("{name}/Cargo.toml"),
+            &format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n"),
+        );
+        write(
+            dir.path(),
+            &format!("{name}/src/lib.rs"),
+            r#"
+            pub struct Widget;
+
+            impl Widget {
+                pub fn draw(&self) {}
+            }
+
+            pub fn make() -> Widget { Widget }
+            "#,
+        );
+    }
+    write(
+        dir.path(),
+        "cli/Cargo.toml",
+        "[package]\nname = \"cli\"\nversion = \"0.1.0\"\n",
+    );
+    // The type of `widget` is only known by name, so `draw` is looked up in
+    // the libraries one after the other
+    write(
+        dir.path(),
+        "cli/src/main.rs",
+        r#"
+        fn main() {
+            let widget = beta::make();
+            widget.draw();
+        }
+        "#,
+    );
+
+    for _ in 0..4 {
+        let graph = build(dir.path()).await;
+        assert_eq!(
+            edges(&graph),
+            ["main -> beta::make", "main -> alpha::Widget::draw"]
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Tests for collecting markers, unsafe code and panic sites.
+
+mod common;
+
+use ai_context_gen::code_health::{CodeHealth, FileHealth, PanicKind};
+use common::{scan, write};
+use std::path::Path;
+
+fn audit(source: &str) -> FileHealth {
+    CodeHealth::audit_file(Path::new("src/lib.rs"), source, false)
+}
+
+fn markers(health: &FileHealth) -> Vec<(&str, usize, &str)> {
+    health
+        .markers
+        .iter()
+        .map(|marker| (marker.tag.as_str(), marker.line, marker.text.as_str()))
+        .collect()
+}
+
+#[test]
+fn tags_must_open_the_comment_or_be_labelled() {
+    let source = "\
+// TODO: parse IPv6
+// FIXME(alice): leaks on retry
+// XXX - temporary
+// We used to have a TODO list here.
+// The TODOS are tracked elsewhere, see MY_TODO and TODO_LIST.
+let x = 1; // HACK: work around borrowck
+/* first line
+   TODO(bob) second line */
+// ends with a bare tag TODO
+";
+
+    assert_eq!(
+        markers(&audit(source)),
+        [
+            ("TODO", 1, "parse IPv6"),
+            ("FIXME", 2, "leaks on retry"),
+            ("XXX", 3, "temporary"),
+            ("HACK", 6, "work around borrowck"),
+            ("TODO", 8, "second line"),
+        ]
+    );
+}
+
+#[test]
+fn doc_comments_only_count_tags_that_open_a_line() {
+    let source = r#"
+/// TODO: document the error cases
+///
+/// ```
+/// // TODO: this is an example comment
+/// let todo = "TODO: inside a string";
+/// ```
+//! FIXME crate docs are outdated
+/** Mentions FIXME: in passing */
+pub fn run() {
+    let s = "// TODO: not a comment";
+}
+"#;
+
+    assert_eq!(
+        markers(&audit(source)),
+        [
+            ("TODO", 2, "document the error cases"),
+            ("FIXME", 8, "crate docs are outdated"),
+        ]
+    );
+}
+
+#[test]
+fn long_marker_text_is_cut_at_a_word_boundary() {
+    let long = "word ".repeat(40);
+    let health = audit(&format!("// TODO: {long}\n"));
+
+    let text = &health.markers[0].text;
+    assert!(text.ends_with("word…"));
+    assert!(text.chars().count() <= 101);
+}
+
+#[test]
+fn test_code_is_skipped_for_unsafe_and_panic_sites() {
+    let source = r#"
+pub fn load() -> u32 {
+    std::env::var("N").unwrap().parse().expect("number")
+}
+
+#[test]
+fn top_level_test() { load().checked_add(1).unwrap(); }
+
+#[tokio::test]
+async fn async_test() { todo!() }
+
+#[cfg(test)]
+mod tests {
+    // TODO: cover errors
+    fn helper() { unsafe { std::hint::unreachable_unchecked() } }
+}
+
+impl Loader {
+    #[test]
+    fn method_test() { panic!() }
+
+    fn real(&self) { unimplemented!() }
+}
+"#;
+
+    let health = audit(source);
+
+    let panics: Vec<(PanicKind, usize)> = health
+        .panic_sites
+        .iter()
+        .map(|site| (site.kind, site.line))
+        .collect();
+    assert_eq!(
+        panics,
+        [
+            (PanicKind::Expect, 3),
+            (PanicKind::Unwrap, 3),
+            (PanicKind::Unimplemented, 22),
+        ]
+    );
+    assert!(health.unsafe_sites.is_empty());
+    // Markers are still reported inside test code
+    assert_eq!(markers(&health), [("TODO", 14, "cover errors")]);
+}
+
+#[test]
+fn compound_test_cfgs_are_skipped_like_cfg_test() {
+    let source = r#"
+#[cfg(all(test, feature = "slow"))]
+mod slow_tests {
+    fn helper() { Some(1).unwrap(); }
+}
+
+#[cfg(not(test))]
+mod runtime {
+    fn start() { Some(1).unwrap(); }
+}
+"#;
+
+    let lines: Vec<usize> = audit(source)
+        .panic_sites
+        .iter()
+        .map(|site| site.line)
+        .collect();
+    assert_eq!(lines, [9]);
+}
+
+#[tokio::test]
+async fn files_under_tests_directories_only_report_markers() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = "// TODO: more cases\nfn check() { Some(1).unwrap(); }\n";
+    for path in ["src/lib.rs", "tests/api.rs", "benches/speed.rs"] {
+        write(dir.path(), path, source);
+    }
+
+    let scan_result = scan(dir.path()).await;
+    let mut files = CodeHealth::audit(&scan_result).files;
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let summary: Vec<(String, usize, usize)> = files
+        .iter()
+        .map(|file| {
+            (
+                file.path.display().to_string(),
+                file.markers.len(),
+                file.panic_sites.len(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("benches/speed.rs".to_string(), 1, 0),
+            ("src/lib.rs".to_string(), 1, 1),
+            ("tests/api.rs".to_string(), 1, 0),
+        ]
+    );
+}
@@ -0,0 +1,24 @@
+//! Fixture helpers shared by the integration tests.
+
+// Each test crate only uses some of the helpers
+#![allow(dead_code)]
+
+use ai_context_gen::{Config, RepositoryScanner, ScanResult};
+use std::fs;
+use std::path::Path;
+
+/// Writes `content` to `relative` below `root`, creating parent directories.
+pub fn write(root: &Path, relative: &str, content: &str) {
+    let path = root.join(relative);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/// Scans `root` with the default configuration.
+pub async fn scan(root: &Path) -> ScanResult {
+    let config = Config {
+        repo_path: root.to_path_buf(),
+        ..Config::default()
+    };
+    RepositoryScanner::new(config).scan().await.unwrap()
+}
@@ -0,0 +1,148 @@
+//! Tests for summarizing the APIs of locally available dependencies.
+
+mod common;
+
+use ai_context_gen::dependency_api::{
+    ApiItemKind, DependencyApi, DependencyApiAnalyzer, MAX_ITEMS_PER_DEPENDENCY,
+};
+use common::{scan, write};
+use std::path::Path;
+
+/// Writes a project depending on the path dependency `helper-lib` in
+/// `vendor/helper`, and returns the analyzed dependency APIs.
+async fn analyze(dir: &Path, project_src: &str, helper_src: &str) -> Vec<DependencyApi> {
+    write(
+        dir,
+        "Cargo.toml",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n\
+         [dependencies]\nhelper-lib = { path = \"vendor/helper\" }\nunused = \"1\"\n",
+    );
+    write(dir, "src/main.rs", project_src);
+    write(
+        dir,
+        "vendor/helper/Cargo.toml",
+        "[package]\nname = \"helper-lib\"\nversion = \"0.2.0\"\n",
+    );
+    write(dir, "vendor/helper/src/lib.rs", helper_src);
+
+    let scan_result = scan(dir).await;
+    DependencyApiAnalyzer::new(dir.to_path_buf()).analyze(&scan_result)
+}
+
+#[tokio::test]
+async fn only_public_items_imported_by_the_project_are_listed() {
+    let dir = tempfile::tempdir().unwrap();
+    let apis = analyze(
+        dir.path(),
+        r#"
+        use helper_lib::{Client, Mode};
+
+        fn main() {
+            let client = Client::new();
+            helper_lib::connect(&client, Mode::Fast);
+            helper_lib::internal();
+        }
+        "#,
+        r#"
+        /// A client.
+        pub struct Client { pub name: String, secret: u32 }
+
+        impl Client {
+            /// Creates a client.
+            pub fn new() -> Self { todo!() }
+            fn reset(&mut self) {}
+        }
+
+        pub enum Mode { Fast, Slow }
+
+        /// Connects the client.
+        pub fn connect(client: &Client, mode: Mode) {}
+
+        pub(crate) fn internal() {}
+
+        pub fn never_used() {}
+        "#,
+    )
+    .await;
+
+    assert_eq!(apis.len(), 1);
+    let api = &apis[0];
+    assert_eq!(api.name, "helper-lib");
+    assert_eq!(api.version.as_deref(), Some("0.2.0"));
+    assert_eq!(api.imported, ["Client", "Mode", "connect", "internal"]);
+
+    let items: Vec<(&str, ApiItemKind)> = api
+        .items
+        .iter()
+        .map(|item| (item.name.as_str(), item.kind))
+        .collect();
+    assert_eq!(
+        items,
+        [
+            ("connect", ApiItemKind::Function),
+            ("Client", ApiItemKind::Struct),
+            ("Mode", ApiItemKind::Enum),
+        ]
+    );
+    assert_eq!(
+        api.items[0].summary.as_deref(),
+        Some("Connects the client.")
+    );
+    assert!(api.items[1].signature.ends_with("(1 public fields)"));
+    assert_eq!(api.items[1].methods, ["pub fn new() -> Self"]);
+    assert_eq!(api.items[2].signature, "pub enum Mode { Fast, Slow }");
+}
+
+#[tokio::test]
+async fn items_are_truncated_per_dependency() {
+    let dir = tempfile::tempdir().unwrap();
+    let count = MAX_ITEMS_PER_DEPENDENCY + 5;
+    let names: Vec<String> = (0..count).map(|i| format!("f{i:02}")).collect();
+    let helper_src: String = names
+        .iter()
+        .map(|name| format!("pub fn {name}() {{}}\n"))
+        .collect();
+    let project_src = format!(
+        "use helper_lib::{{{}}};\nfn main() {{}}\n",
+        names.join(", ")
+    );
+
+    let apis = analyze(dir.path(), &project_src, &helper_src).await;
+
+    assert_eq!(apis[0].imported.len(), count);
+    assert_eq!(apis[0].items.len(), MAX_ITEMS_PER_DEPENDENCY);
+    assert_eq!(apis[0].items[0].name, "f00");
+}
+
+#[tokio::test]
+async fn dependencies_that_are_never_imported_are_skipped() {
+    let dir = tempfile::tempdir().unwrap();
+    let apis = analyze(
+        dir.path(),
+        "use std::fmt;\nfn main() { let _ = helper_lib; }\n",
+        "pub fn connect() {}\n",
+    )
+    .await;
+
+    assert!(apis.is_empty());
+}
+
+#[tokio::test]
+async fn imports_of_partly_parsed_files_still_count() {
+    let dir = tempfile::tempdir().unwrap();
+    let apis = analyze(
+        dir.path(),
+        r#"
+        use helper_lib::{connect as open, Mode};
+
+        fn broken() { let = ; }
+
+        fn main() { helper_lib::close(); }
+        "#,
+        "pub fn connect() {}\npub fn close() {}\npub struct Mode;\n",
+    )
+    .await;
+
+    assert_eq!(apis.len(), 1);
+    assert_eq!(apis[0].imported, ["Mode", "close", "connect"]);
+}
@@ -0,0 +1,152 @@
+//! Tests for module dependencies derived from `use` declarations.
+
+mod common;
+
+use ai_context_gen::import_graph::{ImportGraph, ImportOrigin};
+use ai_context_gen::module_tree::ModuleTree;
+use common::{scan, write};
+use std::path::Path;
+
+async fn build(root: &Path) -> ImportGraph {
+    let scan_result = scan(root).await;
+    ImportGraph::build(&scan_result, &ModuleTree::resolve(&scan_result))
+}
+
+/// Short paths of the modules at `indices`.
+fn paths<'a>(graph: &'a ImportGraph, indices: &[usize]) -> Vec<&'a str> {
+    indices
+        .iter()
+        .map(|&index| graph.modules[index].short_path())
+        .collect()
+}
+
+#[tokio::test]
+async fn modules_are_layered_above_their_dependencies() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        "mod config;\nmod store;\nmod cache;\nmod api;\n",
+    );
+    write(dir.path(), "src/config.rs", "pub struct Config;\n");
+    write(
+        dir.path(),
+        "src/store.rs",
+        "use crate::config::Config;\npub struct Store;\n",
+    );
+    write(
+        dir.path(),
+        "src/cache.rs",
+        "use super::config::Config;\npub struct Cache;\n",
+    );
+    write(
+        dir.path(),
+        "src/api.rs",
+        "use crate::store::Store;\nuse crate::cache::Cache;\nuse crate::config::Config;\n",
+    );
+
+    let graph = build(dir.path()).await;
+
+    let layers: Vec<(&str, usize)> = graph
+        .modules
+        .iter()
+        .map(|module| (module.short_path(), module.layer))
+        .collect();
+    assert_eq!(
+        layers,
+        [
+            ("crate", 0),
+            ("config", 0),
+            ("store", 1),
+            ("cache", 1),
+            ("api", 2),
+        ]
+    );
+    let api = &graph.modules[graph.find("api").unwrap()];
+    assert_eq!(paths(&graph, &api.depends_on), ["store", "cache", "config"]);
+    assert!(graph.cycles.is_empty());
+}
+
+#[tokio::test]
+async fn cycles_share_a_layer_and_are_listed_once() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        "mod base;\nmod ping;\nmod pong;\nmod top;\nmod a;\nmod b;\nmod c;\n",
+    );
+    write(dir.path(), "src/base.rs", "pub struct Base;\n");
+    write(
+        dir.path(),
+        "src/ping.rs",
+        "use crate::pong::Pong;\nuse crate::base::Base;\npub struct Ping;\n",
+    );
+    write(
+        dir.path(),
+        "src/pong.rs",
+        "use crate::ping::Ping;\npub struct Pong;\n",
+    );
+    write(dir.path(), "src/top.rs", "use crate::pong::Pong;\n");
+    // A longer cycle: a -> b -> c -> a
+    write(dir.path(), "src/a.rs", "use crate::b::B;\npub struct A;\n");
+    write(dir.path(), "src/b.rs", "use crate::c::C;\npub struct B;\n");
+    write(dir.path(), "src/c.rs", "use crate::a::A;\npub struct C;\n");
+
+    let graph = build(dir.path()).await;
+
+    let layer = |path: &str| graph.modules[graph.find(path).unwrap()].layer;
+    assert_eq!(layer("base"), 0);
+    assert_eq!(layer("ping"), 1);
+    assert_eq!(layer("pong"), 1);
+    assert_eq!(layer("top"), 2);
+    assert_eq!(layer("a"), 0);
+    assert_eq!(layer("c"), 0);
+
+    let cycles: Vec<Vec<&str>> = graph
+        .cycles
+        .iter()
+        .map(|cycle| paths(&graph, cycle))
+        .collect();
+    assert_eq!(cycles, [vec!["ping", "pong"], vec!["a", "b", "c"]]);
+}
+
+#[tokio::test]
+async fn super_paths_stop_at_the_crate_root() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "src/lib.rs", "mod net;\nmod util;\n");
+    write(dir.path(), "src/util.rs", "pub fn clamp() {}\n");
+    write(
+        dir.path(),
+        "src/net.rs",
+        r#"
+        use super::util::clamp;
+        use super::super::Outside;
+
+        pub enum Kind { Tcp, Udp }
+        use Kind::*;
+
+        mod tcp {
+            use super::super::util;
+        }
+        "#,
+    );
+
+    let graph = build(dir.path()).await;
+
+    let net = &graph.modules[graph.find("net").unwrap()];
+    let imports: Vec<(&str, ImportOrigin)> = net
+        .imports
+        .iter()
+        .map(|import| (import.path.as_str(), import.origin))
+        .collect();
+    assert_eq!(
+        imports,
+        [
+            ("crate::util::clamp", ImportOrigin::Crate),
+            ("crate::net::Kind::*", ImportOrigin::Crate),
+            ("crate::util", ImportOrigin::Crate),
+        ]
+    );
+    assert!(net.external_crates.is_empty());
+    assert_eq!(paths(&graph, &net.depends_on), ["util"]);
+}
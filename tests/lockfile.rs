@@ -0,0 +1,245 @@
+//! Tests for resolving dependency trees from `Cargo.lock`.
+
+mod common;
+
+use ai_context_gen::lockfile::{CargoLock, PackageSource, ResolvedDependency};
+use ai_context_gen::manifest::{CargoManifest, DependencyKind};
+use ai_context_gen::{Config, ContextGenerator, RepositoryScanner};
+use common::write;
+use std::fs;
+
+const CRATES_IO: &str = "registry+https://github.com/rust-lang/crates.io-index";
+
+fn locked(name: &str, version: &str, source: Option<&str>, deps: &[&str]) -> String {
+    let mut entry = format!("[[package]]\nname = \"{name}\"\nversion = \"{version}\"\n");
+    if let Some(source) = source {
+        entry.push_str(&format!("source = \"{source}\"\n"));
+    }
+    if !deps.is_empty() {
+        let deps: Vec<String> = deps.iter().map(|dep| format!("\"{dep}\"")).collect();
+        entry.push_str(&format!("dependencies = [{}]\n", deps.join(", ")));
+    }
+    entry.push('\n');
+    entry
+}
+
+/// Renders a tree as `name version` lines indented by depth, with `(*)` for
+/// repeated subtrees.
+fn render(deps: &[ResolvedDependency], depth: usize, lines: &mut Vec<String>) {
+    for dep in deps {
+        let marker = if dep.repeated { " (*)" } else { "" };
+        lines.push(format!(
+            "{}{} {}{marker}",
+            "  ".repeat(depth),
+            dep.name,
+            dep.version
+        ));
+        render(&dep.dependencies, depth + 1, lines);
+    }
+}
+
+fn resolve(lock: &str, manifest: &str) -> Vec<String> {
+    let lock = CargoLock::parse(lock).unwrap();
+    let manifest = CargoManifest::parse(manifest).unwrap();
+    let package = manifest.package.unwrap();
+    let resolved = lock.resolve(
+        &package.name,
+        package.version.as_deref(),
+        &manifest.dependencies,
+    );
+    let mut lines = Vec::new();
+    render(&resolved, 0, &mut lines);
+    lines
+}
+
+#[test]
+fn subtrees_are_expanded_once_at_their_shallowest_depth() {
+    let lock = [
+        locked("app", "0.1.0", None, &["http", "log", "tls"]),
+        locked("http", "1.0.0", Some(CRATES_IO), &["bytes", "tls"]),
+        locked("tls", "0.5.0", Some(CRATES_IO), &["bytes"]),
+        locked("bytes", "1.6.0", Some(CRATES_IO), &["log"]),
+        locked("log", "0.4.21", Some(CRATES_IO), &[]),
+    ]
+    .concat();
+    let manifest = r#"
+        [package]
+        name = "app"
+        version = "0.1.0"
+
+        [dependencies]
+        http = "1"
+        tls = "0.5"
+        log = "0.4"
+    "#;
+
+    // `tls` is a direct dependency, so it is expanded there and not below
+    // `http`, even though `http` is listed first. Leaf packages are never
+    // marked as repeated.
+    assert_eq!(
+        resolve(&lock, manifest),
+        [
+            "http 1.0.0",
+            "  bytes 1.6.0",
+            "    log 0.4.21",
+            "  tls 0.5.0 (*)",
+            "log 0.4.21",
+            "tls 0.5.0",
+            "  bytes 1.6.0 (*)",
+        ]
+    );
+}
+
+#[test]
+fn the_root_package_pins_dependencies_locked_at_several_versions() {
+    let lock = [
+        locked("rand", "0.7.3", Some(CRATES_IO), &[]),
+        locked("app", "0.1.0", None, &["rand 0.8.5", "old"]),
+        locked("old", "0.1.0", Some(CRATES_IO), &["rand 0.7.3"]),
+        locked("rand", "0.8.5", Some(CRATES_IO), &["rand_core"]),
+        locked("rand_core", "0.6.4", Some(CRATES_IO), &[]),
+    ]
+    .concat();
+    let manifest = r#"
+        [package]
+        name = "app"
+        version = "0.1.0"
+
+        [dependencies]
+        random = { package = "rand", version = "0.8" }
+        old = "0.1"
+
+        [dev-dependencies]
+        rand_core = "0.6"
+    "#;
+
+    assert_eq!(
+        resolve(&lock, manifest),
+        [
+            "old 0.1.0",
+            "  rand 0.7.3",
+            "rand 0.8.5",
+            "  rand_core 0.6.4",
+            "rand_core 0.6.4",
+        ]
+    );
+
+    let lock = CargoLock::parse(&lock).unwrap();
+    let manifest = CargoManifest::parse(manifest).unwrap();
+    let resolved = lock.resolve("app", Some("0.1.0"), &manifest.dependencies);
+    assert_eq!(resolved[0].kind, Some(DependencyKind::Normal));
+    assert_eq!(resolved[2].kind, Some(DependencyKind::Dev));
+    assert_eq!(resolved[0].dependencies[0].kind, None);
+}
+
+#[test]
+fn package_sources_are_classified() {
+    let lock = [
+        locked("a", "1.0.0", Some(CRATES_IO), &[]),
+        locked(
+            "b",
+            "1.0.0",
+            Some("git+https://github.com/org/b?branch=main#0123456789abcdef"),
+            &[],
+        ),
+        locked("c", "1.0.0", Some("sparse+https://example.com/index/"), &[]),
+        locked("d", "1.0.0", None, &[]),
+    ]
+    .concat();
+    let lock = CargoLock::parse(&lock).unwrap();
+
+    let sources: Vec<String> = lock
+        .packages
+        .iter()
+        .map(|package| package.source().to_string())
+        .collect();
+    assert_eq!(
+        sources,
+        [
+            "crates.io",
+            "git https://github.com/org/b#01234567",
+            "registry https://example.com/index/",
+            "path",
+        ]
+    );
+    assert_eq!(lock.packages[3].source(), PackageSource::Path);
+}
+
+#[tokio::test]
+async fn malformed_lockfile_does_not_fail_the_scan() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nlog = \"0.4\"\n",
+    );
+    write(dir.path(), "src/lib.rs", "");
+    write(
+        dir.path(),
+        "Cargo.lock",
+        "<<<<<<< HEAD\n[[package]]\nname = \"log\"\n=======\n>>>>>>> main\n",
+    );
+
+    let config = Config {
+        repo_path: dir.path().to_path_buf(),
+        include_deps: true,
+        ..Config::default()
+    };
+    let result = RepositoryScanner::new(config).scan().await.unwrap();
+
+    assert_eq!(result.metadata.name, "app");
+    assert!(result.metadata.resolved_dependencies.is_empty());
+}
+
+#[tokio::test]
+async fn root_package_dependency_tree_is_rendered_once() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nlog = \"0.4\"\n\n\
+         [workspace]\nmembers = [\"member\"]\n",
+    );
+    write(dir.path(), "src/lib.rs", "");
+    write(
+        dir.path(),
+        "member/Cargo.toml",
+        "[package]\nname = \"member\"\nversion = \"0.1.0\"\n\n[dependencies]\nlog = \"0.4\"\n",
+    );
+    write(dir.path(), "member/src/lib.rs", "");
+    write(
+        dir.path(),
+        "Cargo.lock",
+        &[
+            locked("app", "0.1.0", None, &["log"]),
+            locked("member", "0.1.0", None, &["log"]),
+            locked("log", "0.4.21", Some(CRATES_IO), &[]),
+        ]
+        .concat(),
+    );
+
+    let output = dir.path().join("context.md");
+    let config = Config {
+        repo_path: dir.path().to_path_buf(),
+        include_deps: true,
+        output_file: output.to_string_lossy().to_string(),
+        ..Config::default()
+    };
+    let result = RepositoryScanner::new(config.clone()).scan().await.unwrap();
+    ContextGenerator::new(config)
+        .generate_context(result)
+        .await
+        .unwrap();
+    let context = fs::read_to_string(output).unwrap();
+
+    // Once in "Project Metadata", once in "Crate: member"
+    assert_eq!(
+        context
+            .matches("**Resolved dependencies (Cargo.lock):**")
+            .count(),
+        2
+    );
+    let root_crate = &context[context.find("# Crate: app").unwrap()..];
+    let root_crate = &root_crate[..root_crate.find("# Crate: member").unwrap()];
+    assert!(!root_crate.contains("Resolved dependencies"));
+}
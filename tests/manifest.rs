@@ -0,0 +1,243 @@
+//! Tests for `Cargo.toml` parsing, workspace resolution and target discovery.
+
+mod common;
+
+use ai_context_gen::manifest::{CargoManifest, TargetKind};
+use common::{scan, write};
+use std::path::PathBuf;
+
+fn package(name: &str) -> String {
+    format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n")
+}
+
+fn targets(manifest: &CargoManifest) -> Vec<(&str, TargetKind, &str)> {
+    manifest
+        .targets
+        .iter()
+        .map(|t| (t.name.as_str(), t.kind, t.path.as_deref().unwrap_or("")))
+        .collect()
+}
+
+#[test]
+fn members_inherit_package_fields_and_dependencies() {
+    let root = CargoManifest::parse(
+        r#"
+        [workspace]
+        members = ["crates/*"]
+
+        [workspace.package]
+        version = "2.0.0"
+        edition = "2021"
+        license = "MIT"
+
+        [workspace.dependencies]
+        serde = { version = "1.0", features = ["derive"], default-features = false }
+        local = { path = "crates/local" }
+        "#,
+    )
+    .unwrap();
+    let workspace = root.workspace.unwrap();
+
+    let mut member = CargoManifest::parse(
+        r#"
+        [package]
+        name = "member"
+        version.workspace = true
+        edition = "2018"
+        license.workspace = true
+
+        [dependencies]
+        serde = { workspace = true, features = ["rc"] }
+        local.workspace = true
+        regex = "1"
+        "#,
+    )
+    .unwrap();
+    member.inherit_workspace(&workspace);
+
+    let package = member.package.unwrap();
+    assert_eq!(package.version.as_deref(), Some("2.0.0"));
+    assert_eq!(package.license.as_deref(), Some("MIT"));
+    // Fields set by the member itself are kept
+    assert_eq!(package.edition.as_deref(), Some("2018"));
+
+    let dependency = |name: &str| member.dependencies.iter().find(|d| d.name == name).unwrap();
+
+    let serde = dependency("serde");
+    assert_eq!(serde.version_req.as_deref(), Some("1.0"));
+    assert_eq!(serde.features, ["rc", "derive"]);
+    assert!(!serde.default_features);
+
+    let local = dependency("local");
+    assert_eq!(local.path.as_deref(), Some("crates/local"));
+    assert!(local.workspace);
+
+    let regex = dependency("regex");
+    assert_eq!(regex.version_req.as_deref(), Some("1"));
+    assert!(!regex.workspace);
+}
+
+#[test]
+fn resolve_members_expands_globs_and_applies_excludes() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "crates/core/Cargo.toml", &package("core"));
+    write(dir.path(), "crates/cli/Cargo.toml", &package("cli"));
+    write(
+        dir.path(),
+        "crates/experimental/Cargo.toml",
+        &package("exp"),
+    );
+    write(dir.path(), "crates/assets/logo.svg", "");
+    write(dir.path(), "tools/xtask/Cargo.toml", &package("xtask"));
+    write(dir.path(), "tools/nested/deep/Cargo.toml", &package("deep"));
+
+    let manifest = CargoManifest::parse(
+        r#"
+        [workspace]
+        members = ["crates/*", "tools/xtask/", "./crates/core", "tools/*"]
+        exclude = ["crates/experimental"]
+        "#,
+    )
+    .unwrap();
+    let members = manifest.workspace.unwrap().resolve_members(dir.path());
+
+    // Directories without a manifest are skipped, `*` does not cross `/`
+    assert_eq!(
+        members,
+        [
+            PathBuf::from("crates/cli"),
+            PathBuf::from("crates/core"),
+            PathBuf::from("tools/xtask"),
+        ]
+    );
+}
+
+#[test]
+fn targets_are_discovered_from_the_file_layout() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "src/lib.rs", "");
+    write(dir.path(), "src/main.rs", "");
+    write(dir.path(), "src/bin/migrate.rs", "");
+    write(dir.path(), "src/bin/admin/main.rs", "");
+
+    let mut manifest = CargoManifest::parse(
+        r#"
+        [package]
+        name = "my-tool"
+
+        [[bin]]
+        name = "admin"
+        "#,
+    )
+    .unwrap();
+    manifest.discover_targets(dir.path());
+
+    assert_eq!(
+        targets(&manifest),
+        [
+            ("admin", TargetKind::Bin, "src/bin/admin/main.rs"),
+            ("my_tool", TargetKind::Lib, "src/lib.rs"),
+            ("my-tool", TargetKind::Bin, "src/main.rs"),
+            ("migrate", TargetKind::Bin, "src/bin/migrate.rs"),
+        ]
+    );
+}
+
+#[test]
+fn declared_targets_and_autobins_are_respected() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "src/lib.rs", "");
+    write(dir.path(), "src/main.rs", "");
+    write(dir.path(), "src/bin/extra.rs", "");
+
+    let mut manifest = CargoManifest::parse(
+        r#"
+        [package]
+        name = "demo"
+        autobins = false
+
+        [lib]
+        name = "demo_core"
+        path = "src/core.rs"
+        "#,
+    )
+    .unwrap();
+    manifest.discover_targets(dir.path());
+
+    assert_eq!(
+        targets(&manifest),
+        [("demo_core", TargetKind::Lib, "src/core.rs")]
+    );
+}
+
+#[test]
+fn test_and_bench_targets_record_their_harness() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "tests/smoke/main.rs", "");
+
+    let mut manifest = CargoManifest::parse(
+        r#"
+        [package]
+        name = "demo"
+        autobins = false
+
+        [[test]]
+        name = "smoke"
+
+        [[bench]]
+        name = "throughput"
+        harness = false
+        "#,
+    )
+    .unwrap();
+    manifest.discover_targets(dir.path());
+
+    assert_eq!(
+        targets(&manifest),
+        [
+            ("smoke", TargetKind::Test, "tests/smoke/main.rs"),
+            ("throughput", TargetKind::Bench, "benches/throughput.rs"),
+        ]
+    );
+    let harness: Vec<bool> = manifest.targets.iter().map(|t| t.harness).collect();
+    assert_eq!(harness, [true, false]);
+}
+
+#[tokio::test]
+async fn malformed_manifests_fall_back_to_default_metadata() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"crates/*\"]\n",
+    );
+    write(dir.path(), "crates/good/Cargo.toml", &package("good"));
+    write(dir.path(), "crates/good/src/lib.rs", "");
+    write(dir.path(), "crates/broken/Cargo.toml", "[package\nname = ");
+    write(dir.path(), "crates/broken/src/lib.rs", "");
+
+    let result = scan(dir.path()).await;
+
+    let crates: Vec<_> = result
+        .crates
+        .iter()
+        .map(|c| (c.name.as_str(), c.metadata.version.as_deref()))
+        .collect();
+    assert_eq!(crates, [("broken", None), ("good", Some("0.1.0"))]);
+}
+
+#[tokio::test]
+async fn malformed_root_manifest_does_not_fail_the_scan() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[package]\nname = \"demo\"\nversion = ",
+    );
+    write(dir.path(), "src/lib.rs", "pub fn demo() {}");
+
+    let result = scan(dir.path()).await;
+
+    assert_eq!(result.files.len(), 1);
+    assert_eq!(result.metadata.version, None);
+}
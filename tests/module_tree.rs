@@ -0,0 +1,236 @@
+//! Tests for resolving `mod` declarations to files.
+
+mod common;
+
+use ai_context_gen::module_tree::{ModuleNode, ModuleTree, RootKind};
+use ai_context_gen::parser::ParsedSources;
+use common::{scan, write};
+use std::path::{Path, PathBuf};
+
+async fn resolve(root: &Path) -> ModuleTree {
+    let scan_result = scan(root).await;
+    ModuleTree::resolve(&scan_result)
+}
+
+/// Lists `path -> file` for every module below `module`, depth first.
+fn flatten(module: &ModuleNode) -> Vec<String> {
+    let mut lines = vec![match &module.file {
+        Some(file) => format!("{} -> {}", module.path, file.display()),
+        None if module.is_inline => format!("{} (inline)", module.path),
+        None => format!("{} (missing)", module.path),
+    }];
+    for child in &module.children {
+        lines.extend(flatten(child));
+    }
+    lines
+}
+
+fn paths(files: &[PathBuf]) -> Vec<String> {
+    files.iter().map(|f| f.display().to_string()).collect()
+}
+
+#[tokio::test]
+async fn file_and_mod_rs_modules_are_resolved() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        "mod net;\nmod db;\nmod missing;\n",
+    );
+    // A non-`mod.rs` file keeps its children in a directory named after it
+    write(dir.path(), "src/net.rs", "mod tcp;\n");
+    write(dir.path(), "src/net/tcp.rs", "");
+    // A `mod.rs` file keeps its children next to it
+    write(dir.path(), "src/db/mod.rs", "pub mod pool;\n");
+    write(dir.path(), "src/db/pool.rs", "");
+    write(dir.path(), "src/tcp.rs", "");
+
+    let tree = resolve(dir.path()).await;
+
+    assert_eq!(tree.roots.len(), 1);
+    assert_eq!(tree.roots[0].kind, RootKind::Lib);
+    assert_eq!(
+        flatten(&tree.roots[0].module),
+        [
+            "crate -> src/lib.rs",
+            "crate::net -> src/net.rs",
+            "crate::net::tcp -> src/net/tcp.rs",
+            "crate::db -> src/db/mod.rs",
+            "crate::db::pool -> src/db/pool.rs",
+            "crate::missing (missing)",
+        ]
+    );
+    assert_eq!(
+        tree.module_path(Path::new("src/db/pool.rs")),
+        Some("crate::db::pool")
+    );
+    assert_eq!(paths(&tree.orphans), ["src/tcp.rs"]);
+}
+
+#[tokio::test]
+async fn path_attributes_are_relative_to_the_declaring_file_or_inline_module() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        r#"
+        #[path = "platform/unix_impl.rs"]
+        mod platform;
+
+        #[path = "../shared/util.rs"]
+        mod util;
+
+        mod generated {
+            // Inside an inline module, paths start from `src/generated/`
+            #[path = "schema_v2.rs"]
+            mod schema;
+            mod tables;
+        }
+        "#,
+    );
+    // Files loaded through `#[path]` keep their children next to them
+    write(dir.path(), "src/platform/unix_impl.rs", "mod signals;\n");
+    write(dir.path(), "src/platform/signals.rs", "");
+    write(dir.path(), "shared/util.rs", "");
+    write(dir.path(), "src/generated/schema_v2.rs", "");
+    write(dir.path(), "src/generated/tables.rs", "");
+
+    let tree = resolve(dir.path()).await;
+
+    assert_eq!(
+        flatten(&tree.roots[0].module),
+        [
+            "crate -> src/lib.rs",
+            "crate::platform -> src/platform/unix_impl.rs",
+            "crate::platform::signals -> src/platform/signals.rs",
+            "crate::util -> shared/util.rs",
+            "crate::generated (inline)",
+            "crate::generated::schema -> src/generated/schema_v2.rs",
+            "crate::generated::tables -> src/generated/tables.rs",
+        ]
+    );
+    assert!(tree.orphans.is_empty());
+}
+
+#[tokio::test]
+async fn path_attribute_cycles_terminate() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/main.rs",
+        "#[path = \"main.rs\"]\nmod again;\n",
+    );
+
+    let tree = resolve(dir.path()).await;
+
+    assert_eq!(
+        flatten(&tree.roots[0].module),
+        ["crate -> src/main.rs", "crate::again -> src/main.rs"]
+    );
+    assert_eq!(tree.module_path(Path::new("src/main.rs")), Some("crate"));
+}
+
+#[tokio::test]
+async fn every_crate_root_is_resolved_and_shared_files_belong_to_the_library() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[package]\nname = \"demo-app\"\nversion = \"0.1.0\"\n",
+    );
+    write(
+        dir.path(),
+        "src/main.rs",
+        "#[path = \"config.rs\"]\nmod settings;\n",
+    );
+    write(dir.path(), "src/lib.rs", "pub mod config;\n");
+    write(dir.path(), "src/config.rs", "");
+    write(dir.path(), "src/bin/admin.rs", "");
+    write(dir.path(), "build.rs", "");
+    write(dir.path(), "tests/api.rs", "mod common;\n");
+    write(dir.path(), "tests/common/mod.rs", "");
+    write(dir.path(), "examples/demo/main.rs", "");
+    write(dir.path(), "benches/speed.rs", "");
+    write(dir.path(), "benches/helpers/data.rs", "");
+
+    let tree = resolve(dir.path()).await;
+
+    let roots: Vec<(&str, RootKind)> = tree
+        .roots
+        .iter()
+        .map(|root| (root.target.as_str(), root.kind))
+        .collect();
+    assert_eq!(
+        roots,
+        [
+            ("demo_app", RootKind::Lib),
+            ("demo-app", RootKind::Bin),
+            ("admin", RootKind::Bin),
+            ("build-script", RootKind::BuildScript),
+            ("api", RootKind::Test),
+            ("demo", RootKind::Example),
+            ("speed", RootKind::Bench),
+        ]
+    );
+    assert!(tree
+        .roots
+        .iter()
+        .all(|root| root.package.as_deref() == Some("demo-app")));
+
+    // `src/config.rs` is also `crate::settings` of the binary
+    assert_eq!(
+        tree.module_path(Path::new("src/config.rs")),
+        Some("crate::config")
+    );
+    assert_eq!(
+        flatten(&tree.roots[1].module),
+        ["crate -> src/main.rs", "crate::settings -> src/config.rs"]
+    );
+    assert_eq!(
+        tree.module_path(Path::new("tests/common/mod.rs")),
+        Some("crate::common")
+    );
+
+    let module_files: Vec<(usize, &str)> = tree
+        .module_files()
+        .into_iter()
+        .map(|(root, module)| (root, module.path.as_str()))
+        .collect();
+    assert_eq!(
+        module_files,
+        [
+            (0, "crate"),
+            (0, "crate::config"),
+            (1, "crate"),
+            (2, "crate"),
+            (3, "crate"),
+            (4, "crate"),
+            (4, "crate::common"),
+            (5, "crate"),
+            (6, "crate"),
+        ]
+    );
+    assert_eq!(paths(&tree.orphans), ["benches/helpers/data.rs"]);
+}
+
+#[tokio::test]
+async fn parsed_sources_get_the_module_path_of_their_file() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "src/lib.rs", "pub mod net;\n");
+    write(dir.path(), "src/net/mod.rs", "mod tcp;\n");
+    write(dir.path(), "src/net/tcp.rs", "pub fn connect() {}\n");
+    write(dir.path(), "src/unused.rs", "pub fn dead() {}\n");
+
+    let scan_result = scan(dir.path()).await;
+    let mut sources = ParsedSources::parse(&scan_result);
+    let tree = ModuleTree::from_sources(&scan_result, &sources);
+    sources.assign_module_paths(&tree);
+
+    let module_path = |path: &str| sources.get(Path::new(path)).unwrap().module_path.clone();
+    assert_eq!(module_path("src/lib.rs").as_deref(), Some("crate"));
+    assert_eq!(
+        module_path("src/net/tcp.rs").as_deref(),
+        Some("crate::net::tcp")
+    );
+    assert_eq!(module_path("src/unused.rs"), None);
+}
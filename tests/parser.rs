@@ -0,0 +1,202 @@
+//! Tests for the Rust parser, including recovering items from files that do
+//! not parse as a whole.
+
+use ai_context_gen::parser::{RustAnalysis, RustParser, VariantKind};
+
+fn recover(source: &str) -> RustAnalysis {
+    RustParser::parse_rust_file_recovering("lib.rs", source)
+}
+
+fn function_names(analysis: &RustAnalysis) -> Vec<&str> {
+    analysis
+        .functions
+        .iter()
+        .map(|function| function.name.as_str())
+        .collect()
+}
+
+#[test]
+fn each_broken_item_is_reported_on_its_own() {
+    let source = r#"
+pub fn first() {}
+
+pub fn broken_one() -> u32 { let x = ; x }
+
+pub struct Kept { pub field: u8 }
+
+impl Kept {
+    pub fn broken_two(&self) { match self { => 1 } }
+}
+
+pub fn last() {}
+"#;
+
+    let analysis = recover(source);
+
+    assert_eq!(function_names(&analysis), ["first", "last"]);
+    assert_eq!(analysis.structs[0].name, "Kept");
+    let errors: Vec<(usize, &[String])> = analysis
+        .parse_errors
+        .iter()
+        .map(|error| (error.line, error.skipped_items.as_slice()))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (4, &["fn broken_one (line 4)".to_string()][..]),
+            (
+                9,
+                &[
+                    "impl Kept (line 8)".to_string(),
+                    "fn broken_two (line 9)".to_string()
+                ][..]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn nightly_syntax_between_valid_items_only_skips_that_item() {
+    let source = r#"
+#![feature(gen_blocks, never_type)]
+
+pub struct Before;
+
+pub fn numbers() -> impl Iterator<Item = u32> { gen { yield 1; } }
+
+pub trait Middle {}
+
+pub fn after() {}
+"#;
+
+    let analysis = recover(source);
+
+    assert_eq!(analysis.structs[0].name, "Before");
+    assert_eq!(analysis.traits[0].name, "Middle");
+    assert_eq!(function_names(&analysis), ["after"]);
+    assert_eq!(analysis.parse_errors.len(), 1);
+    assert_eq!(
+        analysis.parse_errors[0].skipped_items,
+        ["fn numbers (line 6)"]
+    );
+}
+
+#[test]
+fn blocks_inside_initializers_do_not_end_the_item() {
+    let source = r#"
+const LIMIT: u32 = { 4 * 4 } + 1;
+
+static HOOK: fn() -> u32 = || { 1 } as fn() -> u32;
+
+pub fn broken() { let = ; }
+
+const fn twice(x: u32) -> u32 { x * 2 }
+
+thread_local! { static DEPTH: u32 = 0; }
+
+extern "C" { fn abs(x: i32) -> i32; }
+
+pub fn valid() {}
+"#;
+
+    let analysis = recover(source);
+
+    // Without the initializer blocks ending items, only `broken` is lost
+    assert_eq!(analysis.parse_errors.len(), 1);
+    assert_eq!(
+        analysis.parse_errors[0].skipped_items,
+        ["fn broken (line 6)"]
+    );
+    let constants: Vec<&str> = analysis
+        .constants
+        .iter()
+        .map(|constant| constant.name.as_str())
+        .collect();
+    assert_eq!(constants, ["LIMIT"]);
+    assert_eq!(analysis.statics[0].name, "HOOK");
+    assert_eq!(function_names(&analysis), ["twice", "valid"]);
+}
+
+#[test]
+fn struct_kind_does_not_depend_on_field_names() {
+    let source = r#"
+pub struct Named { pub field_0: u8 }
+
+pub struct Pair(pub u8, u16);
+
+pub struct Empty {}
+
+pub struct Marker;
+"#;
+
+    let analysis = recover(source);
+
+    let kinds: Vec<(&str, VariantKind)> = analysis
+        .structs
+        .iter()
+        .map(|item| (item.name.as_str(), item.kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            ("Named", VariantKind::Struct),
+            ("Pair", VariantKind::Tuple),
+            ("Empty", VariantKind::Struct),
+            ("Marker", VariantKind::Unit),
+        ]
+    );
+}
+
+#[test]
+fn top_level_items_and_imports_are_extracted() {
+    let source = r#"
+use std::fmt::{self, Display};
+
+pub type Id = u64;
+pub const MAX: usize = 8;
+
+macro_rules! square { ($x:expr) => { $x * $x }; }
+"#;
+
+    let analysis = RustParser::parse_rust_file("lib.rs", source).unwrap();
+
+    assert_eq!(analysis.type_aliases[0].target, "u64");
+    assert_eq!(analysis.constants[0].value, "8");
+    assert_eq!(analysis.macros[0].name, "square");
+    let imports: Vec<&str> = analysis
+        .imports
+        .iter()
+        .map(|import| import.path.as_str())
+        .collect();
+    assert_eq!(imports, ["std::fmt", "std::fmt::Display"]);
+}
+
+#[test]
+fn cfg_gates_of_enclosing_modules_are_folded_in() {
+    let source = r#"
+#[derive(Debug, Clone)]
+#[cfg(feature = "serde")]
+pub struct Config;
+
+#[cfg(test)]
+mod tests {
+    #[cfg(unix)]
+    #[test]
+    fn works() {}
+}
+"#;
+
+    let analysis = RustParser::parse_rust_file("lib.rs", source).unwrap();
+
+    assert_eq!(analysis.structs[0].derives, ["Debug", "Clone"]);
+    assert_eq!(
+        analysis.structs[0].cfg.as_deref(),
+        Some("feature = \"serde\"")
+    );
+    assert_eq!(analysis.modules[0].cfg.as_deref(), Some("test"));
+    assert_eq!(analysis.functions[0].attributes, ["#[test]"]);
+    assert_eq!(
+        analysis.functions[0].cfg.as_deref(),
+        Some("all(test, unix)")
+    );
+}
@@ -0,0 +1,310 @@
+//! Tests for rendering the public API of library crates as stubs.
+
+mod common;
+
+use ai_context_gen::module_tree::ModuleTree;
+use ai_context_gen::public_api::PublicApi;
+use common::{scan, write};
+use std::path::Path;
+
+/// Scans `root` and returns the stub of its only library crate.
+async fn stub(root: &Path) -> String {
+    let scan_result = scan(root).await;
+    let tree = ModuleTree::resolve(&scan_result);
+    let mut api = PublicApi::extract(&scan_result, &tree);
+    assert_eq!(api.crates.len(), 1);
+    api.crates.remove(0).stub
+}
+
+#[tokio::test]
+async fn reexports_of_private_modules_are_inlined() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        r#"
+        mod engine;
+        mod util;
+        mod shapes;
+        pub mod api;
+
+        pub use engine::Engine;
+        pub use util::*;
+        pub use shapes as geometry;
+        pub use std::fmt::Debug;
+        "#,
+    );
+    write(
+        dir.path(),
+        "src/engine.rs",
+        r#"
+        /// Runs things.
+        pub struct Engine;
+
+        impl Engine {
+            pub fn start(&self) {}
+            fn warm_up(&self) {}
+        }
+
+        pub struct Hidden;
+        "#,
+    );
+    write(
+        dir.path(),
+        "src/util.rs",
+        "pub fn clamp(x: i32) -> i32 { x }\n",
+    );
+    write(dir.path(), "src/shapes.rs", "pub struct Circle;\n");
+    write(
+        dir.path(),
+        "src/api.rs",
+        "pub fn call() {}\nfn private() {}\n",
+    );
+
+    let stub = stub(dir.path()).await;
+
+    assert_eq!(
+        stub,
+        "\
+// re-exported from `crate::engine`
+/// Runs things.
+pub struct Engine;
+
+impl Engine {
+    pub fn start(&self);
+}
+
+// re-exported from `crate::util`
+pub fn clamp(x: i32) -> i32;
+
+// re-exported from `crate::shapes`
+pub mod geometry {
+    pub struct Circle;
+}
+
+pub use std::fmt::Debug;
+
+pub mod api {
+    pub fn call();
+}
+"
+    );
+}
+
+#[tokio::test]
+async fn self_and_super_reexports_resolve_relative_to_their_module() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        r#"
+        mod internal {
+            pub struct Token;
+
+            pub(crate) mod nested {
+                pub fn helper() {}
+            }
+        }
+
+        pub mod outer {
+            mod private {
+                pub struct Secret;
+            }
+
+            pub use self::private::Secret;
+
+            pub mod inner {
+                pub use super::super::internal::Token;
+                pub use super::super::internal::nested::helper;
+            }
+        }
+        "#,
+    );
+
+    let stub = stub(dir.path()).await;
+
+    assert_eq!(
+        stub,
+        "\
+pub mod outer {
+    // re-exported from `crate::outer::private`
+    pub struct Secret;
+
+    pub mod inner {
+        // re-exported from `crate::internal`
+        pub struct Token;
+
+        // re-exported from `crate::internal::nested`
+        pub fn helper();
+    }
+}
+"
+    );
+}
+
+#[tokio::test]
+async fn test_only_items_are_left_out() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        r#"
+        pub fn always() {}
+
+        #[cfg(test)]
+        pub fn only_test() {}
+
+        #[cfg(all(unix, test))]
+        pub fn unix_test() {}
+
+        #[cfg(any(test, feature = "testing"))]
+        pub fn test_or_feature() {}
+
+        #[cfg(not(test))]
+        pub fn not_test() {}
+
+        #[cfg(unix)]
+        pub fn unix() {}
+
+        #[cfg(all(unix, any(test, debug_assertions)))]
+        pub mod debug {}
+
+        pub struct Widget;
+
+        #[cfg(all(feature = "mock", test))]
+        impl Widget {
+            pub fn mock() -> Self { Widget }
+        }
+        "#,
+    );
+
+    let stub = stub(dir.path()).await;
+
+    assert_eq!(
+        stub,
+        "\
+pub struct Widget;
+
+pub fn always();
+
+pub fn not_test();
+
+pub fn unix();
+"
+    );
+}
+
+#[tokio::test]
+async fn impls_are_matched_to_the_type_in_their_module() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        r#"
+        pub mod client;
+        pub mod server;
+        mod glue;
+        "#,
+    );
+    write(
+        dir.path(),
+        "src/client.rs",
+        r#"
+        pub struct Config;
+
+        impl Config {
+            pub fn retries(&self) -> u32 { 3 }
+        }
+        "#,
+    );
+    write(
+        dir.path(),
+        "src/server.rs",
+        r#"
+        pub struct Config;
+
+        impl Default for crate::server::Config {
+            fn default() -> Self { Config }
+        }
+        "#,
+    );
+    write(
+        dir.path(),
+        "src/glue.rs",
+        r#"
+        use crate::server::Config;
+        use crate::client::*;
+
+        impl Config {
+            pub fn port(&self) -> u16 { 80 }
+        }
+
+        impl Clone for crate::client::Config {
+            fn clone(&self) -> Self { Self }
+        }
+        "#,
+    );
+
+    let stub = stub(dir.path()).await;
+
+    assert_eq!(
+        stub,
+        "\
+pub mod client {
+    pub struct Config;
+
+    impl Config {
+        pub fn retries(&self) -> u32;
+    }
+
+    impl Clone for crate::client::Config { ... }
+}
+
+pub mod server {
+    pub struct Config;
+
+    impl Config {
+        pub fn port(&self) -> u16;
+    }
+
+    impl Default for crate::server::Config { ... }
+}
+"
+    );
+}
+
+#[tokio::test]
+async fn struct_fields_are_rendered_by_struct_kind() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        r#"
+        pub struct Named { pub field_0: u8, secret: u8 }
+
+        pub struct Pair(u8, pub u16, String);
+
+        pub struct Empty {}
+
+        pub struct Marker;
+        "#,
+    );
+
+    let stub = stub(dir.path()).await;
+
+    assert_eq!(
+        stub,
+        "\
+pub struct Named {
+    pub field_0: u8,
+    /* private fields */
+}
+
+pub struct Pair(/* private field */, pub u16, /* private field */);
+
+pub struct Empty {}
+
+pub struct Marker;
+"
+    );
+}
@@ -0,0 +1,229 @@
+//! Regression tests for path filtering in `RepositoryScanner`.
+
+mod common;
+
+use ai_context_gen::{Config, RepositoryScanner};
+use common::write;
+use std::path::{Path, PathBuf};
+
+async fn scanned_paths(config: Config) -> Vec<String> {
+    let result = RepositoryScanner::new(config).scan().await.unwrap();
+    let mut paths: Vec<_> = result
+        .files
+        .iter()
+        .map(|f| f.relative_path.to_string_lossy().replace('\\', "/"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn config_for(repo_path: PathBuf) -> Config {
+    Config {
+        repo_path,
+        ..Config::default()
+    }
+}
+
+#[tokio::test]
+async fn keeps_files_whose_names_contain_ignored_dir_names() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "src/targeting.rs", "fn aim() {}");
+    write(dir.path(), "src/node_modules_bridge.rs", "fn bridge() {}");
+    write(dir.path(), "src/target_finder/mod.rs", "fn find() {}");
+    write(dir.path(), "docs/gitops.md", "# GitOps");
+
+    let paths = scanned_paths(config_for(dir.path().to_path_buf())).await;
+
+    assert_eq!(
+        paths,
+        [
+            "docs/gitops.md",
+            "src/node_modules_bridge.rs",
+            "src/target_finder/mod.rs",
+            "src/targeting.rs",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn skips_ignored_directories_at_any_depth() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "src/lib.rs", "");
+    write(dir.path(), "target/debug/build/out.rs", "");
+    write(dir.path(), "crates/core/target/gen.rs", "");
+    write(dir.path(), "web/node_modules/pkg/README.md", "");
+
+    let paths = scanned_paths(config_for(dir.path().to_path_buf())).await;
+
+    assert_eq!(paths, ["src/lib.rs"]);
+}
+
+#[tokio::test]
+async fn hidden_entries_follow_include_hidden() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "src/lib.rs", "");
+    write(dir.path(), ".github/CONTRIBUTING.md", "");
+    write(dir.path(), "src/.generated.rs", "");
+    write(dir.path(), ".git/notes.md", "");
+    write(dir.path(), ".vscode/notes.md", "");
+
+    let paths = scanned_paths(config_for(dir.path().to_path_buf())).await;
+    assert_eq!(paths, ["src/lib.rs"]);
+
+    let config = Config {
+        include_hidden: true,
+        ..config_for(dir.path().to_path_buf())
+    };
+    let paths = scanned_paths(config).await;
+    assert_eq!(
+        paths,
+        [".github/CONTRIBUTING.md", "src/.generated.rs", "src/lib.rs"]
+    );
+}
+
+#[tokio::test]
+async fn repository_inside_hidden_directory_is_scanned() {
+    let parent = tempfile::tempdir().unwrap();
+    let repo = parent.path().join(".config").join("project");
+    write(&repo, "src/main.rs", "fn main() {}");
+    write(&repo, "src/.hidden.rs", "");
+
+    let paths = scanned_paths(config_for(repo)).await;
+
+    assert_eq!(paths, ["src/main.rs"]);
+}
+
+#[tokio::test]
+async fn relative_repo_path_with_dot_prefix_is_scanned() {
+    let dir = tempfile::Builder::new()
+        .prefix("scanner-test-")
+        .tempdir_in(env!("CARGO_TARGET_TMPDIR"))
+        .unwrap();
+    write(dir.path(), "src/lib.rs", "");
+    write(dir.path(), "README.md", "# Readme");
+
+    let cwd = std::env::current_dir().unwrap();
+    let relative = dir.path().strip_prefix(&cwd).unwrap();
+    let repo_path = Path::new(".").join(relative);
+
+    let paths = scanned_paths(config_for(repo_path)).await;
+
+    assert_eq!(paths, ["README.md", "src/lib.rs"]);
+}
+
+#[tokio::test]
+async fn ignored_file_names_and_unsupported_extensions_are_skipped() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "src/lib.rs", "");
+    write(dir.path(), "Cargo.lock", "");
+    write(dir.path(), "src/data.json", "{}");
+    write(dir.path(), "src/target", "");
+
+    let paths = scanned_paths(config_for(dir.path().to_path_buf())).await;
+
+    assert_eq!(paths, ["src/lib.rs"]);
+}
+
+#[tokio::test]
+async fn ignore_files_apply_with_increasing_precedence() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "src/lib.rs", "");
+    write(dir.path(), "src/generated.rs", "");
+    write(dir.path(), "src/schema.rs", "");
+    write(dir.path(), "docs/guide.md", "");
+    write(dir.path(), "docs/internal.md", "");
+    write(dir.path(), "docs/drafts/plan.md", "");
+
+    // `.ignore` overrides `.gitignore`, and `.aicontextignore` overrides both
+    write(
+        dir.path(),
+        ".gitignore",
+        "src/generated.rs\nsrc/schema.rs\n",
+    );
+    write(dir.path(), ".ignore", "!src/generated.rs\ndocs/*.md\n");
+    write(
+        dir.path(),
+        ".aicontextignore",
+        "!docs/guide.md\nsrc/lib.rs\n",
+    );
+    // Nested ignore files apply below their own directory
+    write(dir.path(), "docs/drafts/.gitignore", "*.md\n");
+
+    let paths = scanned_paths(config_for(dir.path().to_path_buf())).await;
+
+    assert_eq!(paths, ["docs/guide.md", "src/generated.rs"]);
+}
+
+fn filtered_config(repo_path: PathBuf, include: &[&str], exclude: &[&str]) -> Config {
+    Config {
+        include_patterns: include.iter().map(|p| p.to_string()).collect(),
+        exclude_patterns: exclude.iter().map(|p| p.to_string()).collect(),
+        ..config_for(repo_path)
+    }
+}
+
+fn write_filter_fixture(root: &Path) {
+    write(root, "README.md", "");
+    write(root, "src/lib.rs", "");
+    write(root, "src/tests/helpers.rs", "");
+    write(root, "crates/core/src/lib.rs", "");
+    write(root, "crates/core/tests/api.rs", "");
+    write(root, "crates/core/README.md", "");
+    write(root, "crates/cli/src/main.rs", "");
+}
+
+#[tokio::test]
+async fn include_patterns_restrict_files_but_not_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    write_filter_fixture(dir.path());
+
+    let config = filtered_config(dir.path().to_path_buf(), &["crates/core/**"], &[]);
+    let paths = scanned_paths(config).await;
+    assert_eq!(
+        paths,
+        [
+            "crates/core/README.md",
+            "crates/core/src/lib.rs",
+            "crates/core/tests/api.rs",
+        ]
+    );
+
+    // `*` does not cross directory separators
+    let config = filtered_config(dir.path().to_path_buf(), &["*.md", "./src/*.rs"], &[]);
+    let paths = scanned_paths(config).await;
+    assert_eq!(paths, ["README.md", "src/lib.rs"]);
+}
+
+#[tokio::test]
+async fn exclude_patterns_win_over_include_patterns() {
+    let dir = tempfile::tempdir().unwrap();
+    write_filter_fixture(dir.path());
+
+    let config = filtered_config(
+        dir.path().to_path_buf(),
+        &["**/*.rs"],
+        &["**/tests/**", "crates/cli"],
+    );
+    let paths = scanned_paths(config).await;
+    assert_eq!(paths, ["crates/core/src/lib.rs", "src/lib.rs"]);
+
+    // `!` in the include list excludes, and is ignored in the exclude list
+    let config = filtered_config(
+        dir.path().to_path_buf(),
+        &["crates/**", "!**/README.md"],
+        &["!crates/core/tests/"],
+    );
+    let paths = scanned_paths(config).await;
+    assert_eq!(paths, ["crates/cli/src/main.rs", "crates/core/src/lib.rs"]);
+}
+
+#[tokio::test]
+async fn invalid_glob_patterns_fail_the_scan() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "src/lib.rs", "");
+
+    let config = filtered_config(dir.path().to_path_buf(), &[], &["src/[lib.rs"]);
+    let error = RepositoryScanner::new(config).scan().await.unwrap_err();
+
+    assert!(error.to_string().contains("src/[lib.rs"));
+}
@@ -0,0 +1,215 @@
+//! Tests for locating comments and compacting Rust sources.
+
+mod common;
+
+use ai_context_gen::config::CompactionConfig;
+use ai_context_gen::parser::RustParser;
+use ai_context_gen::source_transform::SourceTransformer;
+use ai_context_gen::{Config, ContextGenerator, RepositoryScanner};
+use common::write;
+use std::fs;
+
+/// Text of every comment found in `source`.
+fn comments(source: &str) -> Vec<&str> {
+    SourceTransformer::comments(source)
+        .into_iter()
+        .map(|comment| &source[comment.range])
+        .collect()
+}
+
+fn strip_comments(source: &str) -> String {
+    let options = CompactionConfig {
+        strip_comments: true,
+        ..CompactionConfig::default()
+    };
+    SourceTransformer::compact(source, &options)
+}
+
+#[test]
+fn lifetimes_and_labels_are_not_char_literals() {
+    let source = r#"
+        fn pick<'a, 'b: 'a>(x: &'a str, _y: &'b str) -> &'a str { x } // one
+        fn run() { 'outer: loop { break 'outer; } } /* two */
+        struct Holder<'a>(&'a [u8]); // three
+    "#;
+
+    assert_eq!(comments(source), ["// one", "/* two */", "// three"]);
+}
+
+#[test]
+fn char_and_byte_literals_hide_quotes_and_slashes() {
+    let source = r#"
+        let quote = b'"'; // one
+        let slash = '/'; let star = '*'; // two
+        let escaped = '\''; let tick = b'\''; // three
+        let unicode = '\u{1F600}'; let newline = '\n'; // four
+        let text = "a \" // still a string"; // five
+    "#;
+
+    assert_eq!(
+        comments(source),
+        ["// one", "// two", "// three", "// four", "// five"]
+    );
+}
+
+#[test]
+fn raw_strings_end_at_the_matching_number_of_hashes() {
+    let source = r####"
+        let a = r"C:\path\"; // one
+        let b = r#"a "quoted" // string"#; // two
+        let c = br##"ends with "# // still inside"##; // three
+        let identifier_r = "x"; let r = 1; let s = r#"// inside"#; // four
+    "####;
+
+    assert_eq!(
+        comments(source),
+        ["// one", "// two", "// three", "// four"]
+    );
+}
+
+#[test]
+fn block_comments_nest() {
+    let source = "a /* outer /* inner */ still outer */ b /**/ c /*** not doc */";
+
+    assert_eq!(
+        comments(source),
+        [
+            "/* outer /* inner */ still outer */",
+            "/**/",
+            "/*** not doc */"
+        ]
+    );
+    assert!(SourceTransformer::comments(source)
+        .iter()
+        .all(|comment| !comment.is_doc));
+}
+
+#[test]
+fn unterminated_literals_and_comments_run_to_the_end() {
+    assert!(comments("let s = \"never closed // hidden\n").is_empty());
+    assert_eq!(
+        comments("let x = 1; /* open /* nested */ forever"),
+        ["/* open /* nested */ forever"]
+    );
+    assert!(comments("let s = r#\"missing hashes\" // hidden").is_empty());
+    // A lone quote at the end of the input must not panic
+    assert_eq!(comments("// fine\nlet c = '"), ["// fine"]);
+}
+
+#[test]
+fn stripping_keeps_code_around_comments() {
+    let source = "\
+// License header
+fn main() {
+    let url = \"http://example.com\"; // trailing
+    /* alone */
+    let tick = b'\\''; call(/* inline */ 1);
+}
+";
+
+    assert_eq!(
+        strip_comments(source),
+        "\
+fn main() {
+    let url = \"http://example.com\";
+    let tick = b'\\''; call( 1);
+}
+"
+    );
+}
+
+#[test]
+fn test_modules_behind_compound_cfgs_are_stripped() {
+    let source = "\
+pub fn kept() {}
+
+#[cfg(all(test, feature = \"slow\"))]
+mod slow_tests {
+    #[test]
+    fn slow() {}
+}
+
+#[cfg(not(test))]
+mod runtime {}
+";
+    let options = CompactionConfig {
+        strip_test_modules: true,
+        ..CompactionConfig::default()
+    };
+
+    let compacted = SourceTransformer::compact(source, &options);
+
+    assert!(compacted.contains("pub fn kept() {}"));
+    assert!(!compacted.contains("slow_tests"));
+    assert!(compacted.contains("mod runtime {}"));
+}
+
+#[test]
+fn recovered_items_are_elided_and_compacted() {
+    let source = "\
+/// Loads it.
+pub fn load() -> u32 {
+    // the hard part
+    1
+}
+
+pub fn broken() { let = ; }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn loads() {}
+}
+";
+    let (syntax_tree, parse_errors) = RustParser::parse_file_recovering(source);
+    assert_eq!(parse_errors.len(), 1);
+
+    let options = CompactionConfig {
+        collapse_blank_lines: true,
+        strip_test_modules: true,
+        ..CompactionConfig::default()
+    };
+
+    assert_eq!(
+        SourceTransformer::elide_and_compact(source, &syntax_tree, &options),
+        "\
+/// Loads it.
+pub fn load() -> u32 { ... }
+
+pub fn broken() { let = ; }
+"
+    );
+    // Without a full parse, `compact` keeps the test module
+    assert!(SourceTransformer::compact(source, &options).contains("mod tests"));
+    assert!(
+        !SourceTransformer::compact_parsed(source, &syntax_tree, &options).contains("mod tests")
+    );
+}
+
+#[tokio::test]
+async fn compaction_totals_are_part_of_the_context() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        "// A long license header that compaction removes.\npub fn demo() {}\n",
+    );
+
+    let output = dir.path().join("context.md");
+    let config = Config {
+        repo_path: dir.path().to_path_buf(),
+        output_file: output.to_string_lossy().to_string(),
+        compaction: CompactionConfig::standard(),
+        ..Config::default()
+    };
+    let result = RepositoryScanner::new(config.clone()).scan().await.unwrap();
+    ContextGenerator::new(config)
+        .generate_context(result)
+        .await
+        .unwrap();
+    let context = fs::read_to_string(output).unwrap();
+
+    let summary = context.find("# Source Compaction").unwrap();
+    assert!(context[summary..].contains("**Rust sources:** "));
+    assert!(summary < context.find("# Source: src/lib.rs").unwrap());
+}
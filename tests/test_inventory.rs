@@ -0,0 +1,125 @@
+//! Tests for listing tests and mapping them to what they exercise.
+
+mod common;
+
+use ai_context_gen::call_graph::CallGraph;
+use ai_context_gen::module_tree::ModuleTree;
+use ai_context_gen::parser::ParsedSources;
+use ai_context_gen::test_inventory::TestInventory;
+use ai_context_gen::{Config, ContextGenerator, RepositoryScanner};
+use common::{scan, write};
+use std::fs;
+use std::path::Path;
+
+fn config(root: &Path) -> Config {
+    Config {
+        repo_path: root.to_path_buf(),
+        output_file: root.join("context.md").to_string_lossy().to_string(),
+        test_inventory: true,
+        ..Config::default()
+    }
+}
+
+#[tokio::test]
+async fn custom_harnesses_are_read_from_the_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        r#"
+        [package]
+        name = "demo"
+        version = "0.1.0"
+
+        [[bench]]
+        name = "speed"
+        harness = false
+        "#,
+    );
+    write(dir.path(), "src/lib.rs", "pub fn parse() {}\n");
+    write(
+        dir.path(),
+        "benches/speed.rs",
+        "fn main() { demo::parse(); }\n",
+    );
+    write(
+        dir.path(),
+        "tests/api.rs",
+        "#[test]\nfn parses() { demo::parse(); }\n",
+    );
+    // Only helpers so far, but still run by the libtest harness
+    write(dir.path(), "tests/common.rs", "pub fn setup() {}\n");
+
+    let scan_result = RepositoryScanner::new(config(dir.path()))
+        .scan()
+        .await
+        .unwrap();
+    let inventory = TestInventory::build(&scan_result, &ModuleTree::resolve(&scan_result));
+
+    let test_files: Vec<(String, usize, bool)> = inventory
+        .test_files
+        .iter()
+        .map(|file| (file.file.display().to_string(), file.tests, file.harness))
+        .collect();
+    assert_eq!(
+        test_files,
+        [
+            ("benches/speed.rs".to_string(), 0, false),
+            ("tests/api.rs".to_string(), 1, true),
+            ("tests/common.rs".to_string(), 0, true),
+        ]
+    );
+
+    ContextGenerator::new(config(dir.path()))
+        .generate_context(scan_result)
+        .await
+        .unwrap();
+    let context = fs::read_to_string(dir.path().join("context.md")).unwrap();
+    assert!(context.contains("- `benches/speed.rs` (bench `speed`): custom harness\n"));
+    assert!(context.contains("- `tests/common.rs` (test `common`): 0 test functions\n"));
+}
+
+#[tokio::test]
+async fn exercised_functions_are_listed_once_in_call_order() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+    );
+    write(
+        dir.path(),
+        "src/lib.rs",
+        "pub fn parse(input: &str) -> u32 { input.len() as u32 }\npub fn render() {}\n",
+    );
+    write(
+        dir.path(),
+        "tests/cli.rs",
+        r#"
+        fn setup() -> u32 { demo::render(); demo::parse("1") }
+
+        #[test]
+        fn parse_works() {
+            setup();
+            demo::parse("2");
+            setup();
+        }
+        "#,
+    );
+
+    let scan_result = scan(dir.path()).await;
+    let sources = ParsedSources::parse(&scan_result);
+    let module_tree = ModuleTree::from_sources(&scan_result, &sources);
+    let graph = CallGraph::from_sources(&module_tree, &sources);
+    let inventory = TestInventory::from_sources(&scan_result, &module_tree, &sources, &graph);
+
+    assert_eq!(inventory.tests.len(), 1);
+    assert_eq!(
+        inventory.tests[0].exercises,
+        ["setup", "demo::parse", "demo::render"]
+    );
+    assert_eq!(
+        TestInventory::build(&scan_result, &module_tree).tests[0].exercises,
+        inventory.tests[0].exercises
+    );
+}
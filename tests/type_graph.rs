@@ -0,0 +1,210 @@
+//! Tests for linking crate-local types to each other.
+
+mod common;
+
+use ai_context_gen::module_tree::ModuleTree;
+use ai_context_gen::type_graph::TypeGraph;
+use common::{scan, write};
+use std::path::Path;
+
+async fn build(root: &Path) -> TypeGraph {
+    let scan_result = scan(root).await;
+    TypeGraph::build(&scan_result, &ModuleTree::resolve(&scan_result))
+}
+
+/// Index of the type at `short_path` in crate `target`.
+fn index(graph: &TypeGraph, target: &str, short_path: &str) -> usize {
+    graph
+        .types
+        .iter()
+        .position(|node| node.target == target && node.short_path() == short_path)
+        .unwrap()
+}
+
+#[tokio::test]
+async fn conversions_alone_are_drawn() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        r#"
+        pub struct Celsius(f64);
+        pub struct Fahrenheit(f64);
+
+        impl From<Celsius> for Fahrenheit {
+            fn from(c: Celsius) -> Self { Fahrenheit(c.0 * 1.8 + 32.0) }
+        }
+        "#,
+    );
+
+    let graph = build(dir.path()).await;
+
+    assert!(graph.has_edges());
+    assert_eq!(
+        graph.to_mermaid(),
+        "classDiagram\n    Fahrenheit ..> Celsius : from\n"
+    );
+    let celsius = &graph.types[graph.find("Celsius").unwrap()];
+    assert_eq!(celsius.converts_into[0].other, "Fahrenheit");
+    assert_eq!(celsius.converts_into[0].index, graph.find("Fahrenheit"));
+}
+
+#[tokio::test]
+async fn unrelated_types_have_no_edges() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        r#"
+        #[derive(Debug, Clone)]
+        pub struct Alone { pub name: String }
+
+        impl From<String> for Alone {
+            fn from(name: String) -> Self { Alone { name } }
+        }
+        "#,
+    );
+
+    let graph = build(dir.path()).await;
+
+    assert!(!graph.has_edges());
+    assert_eq!(graph.to_mermaid(), "classDiagram\n");
+}
+
+#[tokio::test]
+async fn same_named_types_are_linked_within_their_crate() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"alpha\", \"beta\"]\n",
+    );
+    for name in ["alpha", "beta"] {
+        write(
+            dir.path(),
+            &format!("{name}/Cargo.toml"),
+            &format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n"),
+        );
+    }
+    write(
+        dir.path(),
+        "alpha/src/lib.rs",
+        r#"
+        pub trait Plugin {}
+        pub struct Config;
+        pub struct Loader;
+        impl Plugin for Loader {}
+        "#,
+    );
+    write(
+        dir.path(),
+        "beta/src/lib.rs",
+        r#"
+        pub trait Plugin {}
+        pub struct Config;
+        pub struct Settings;
+        pub struct Exporter;
+        impl Plugin for Exporter {}
+        impl From<Config> for Settings {
+            fn from(_: Config) -> Self { Settings }
+        }
+        "#,
+    );
+
+    let graph = build(dir.path()).await;
+
+    let alpha_plugin = &graph.types[index(&graph, "alpha", "Plugin")];
+    let beta_plugin = &graph.types[index(&graph, "beta", "Plugin")];
+    assert_eq!(
+        alpha_plugin.implementors,
+        [index(&graph, "alpha", "Loader")]
+    );
+    assert_eq!(
+        beta_plugin.implementors,
+        [index(&graph, "beta", "Exporter")]
+    );
+
+    let settings = &graph.types[index(&graph, "beta", "Settings")];
+    assert_eq!(
+        settings.converts_from[0].index,
+        Some(index(&graph, "beta", "Config"))
+    );
+
+    let mermaid = graph.to_mermaid();
+    let edges: Vec<&str> = mermaid
+        .lines()
+        .filter(|line| line.contains("..|>") || line.contains("..>"))
+        .map(str::trim)
+        .collect();
+    assert_eq!(
+        edges,
+        [
+            "Loader ..|> Plugin_0",
+            "Exporter ..|> Plugin_3",
+            "Settings ..> Config_4 : from",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn foreign_types_do_not_link_to_local_types_of_the_same_name() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "src/lib.rs",
+        "mod error;\nmod config;\nmod app;\n",
+    );
+    write(
+        dir.path(),
+        "src/error.rs",
+        r#"
+        use std::io;
+
+        #[derive(Debug)]
+        pub enum Error { Io(io::Error), Parse(std::num::ParseIntError) }
+
+        impl From<io::Error> for Error {
+            fn from(error: io::Error) -> Self { Error::Io(error) }
+        }
+
+        impl From<std::fmt::Error> for Error {
+            fn from(_: std::fmt::Error) -> Self { unimplemented!() }
+        }
+        "#,
+    );
+    write(
+        dir.path(),
+        "src/config.rs",
+        "pub struct Config { pub path: String }\n",
+    );
+    write(
+        dir.path(),
+        "src/app.rs",
+        r#"
+        use serde_json::Error;
+        use crate::config::Config as Settings;
+
+        pub struct App {
+            pub last_error: Option<Error>,
+            pub settings: Settings,
+            pub other: toml::Config,
+        }
+        "#,
+    );
+
+    let graph = build(dir.path()).await;
+
+    let error = &graph.types[graph.find("Error").unwrap()];
+    let sources: Vec<(&str, Option<usize>)> = error
+        .converts_from
+        .iter()
+        .map(|conversion| (conversion.other.as_str(), conversion.index))
+        .collect();
+    assert_eq!(sources, [("io::Error", None), ("std::fmt::Error", None)]);
+    assert!(error.converts_into.is_empty());
+    assert!(error.contains.is_empty());
+
+    let app = &graph.types[graph.find("App").unwrap()];
+    assert_eq!(app.contains, [graph.find("Config").unwrap()]);
+    assert!(!graph.to_mermaid().contains("Error"));
+}